@@ -107,6 +107,8 @@ impl ActiveCall {
         }
     }
 
+    /// Returns the channel backing the active room, or `None` when there is no
+    /// active call or the call isn't a channel call.
     pub fn channel_id(&self, cx: &App) -> Option<ChannelId> {
         self.room()?.read(cx).channel_id()
     }
@@ -334,10 +336,34 @@ impl ActiveCall {
         Ok(())
     }
 
+    /// Joins the channel's call, leaving the current room if it belongs to another channel.
+    ///
+    /// Joining a channel while a previous join is still in flight cancels the
+    /// previous one, whose task then resolves to `None`, so the call always ends
+    /// up in the most recently requested channel.
     pub fn join_channel(
         &mut self,
         channel_id: ChannelId,
         cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Entity<Room>>>> {
+        self.join_channel_internal(channel_id, false, cx)
+    }
+
+    /// Joins the channel's call as a guest, who can listen in but can't speak or share
+    /// projects, even if they're a member of the channel.
+    pub fn join_channel_as_guest(
+        &mut self,
+        channel_id: ChannelId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<Entity<Room>>>> {
+        self.join_channel_internal(channel_id, true, cx)
+    }
+
+    fn join_channel_internal(
+        &mut self,
+        channel_id: ChannelId,
+        as_guest: bool,
+        cx: &mut Context<Self>,
     ) -> Task<Result<Option<Entity<Room>>>> {
         if let Some(room) = self.room().cloned() {
             if room.read(cx).channel_id() == Some(channel_id) {
@@ -354,15 +380,18 @@ impl ActiveCall {
         let client = self.client.clone();
         let user_store = self.user_store.clone();
         let join = self._join_debouncer.spawn(cx, move |cx| async move {
-            Room::join_channel(channel_id, client, user_store, cx).await
+            Room::join_channel(channel_id, as_guest, client, user_store, cx).await
         });
 
         cx.spawn(async move |this, cx| {
-            let room = join.await?;
-            this.update(cx, |this, cx| this.set_room(room.clone(), cx))?
+            // A superseded join leaves the room to the join that replaced it.
+            let Some(room) = join.await? else {
+                return Ok(None);
+            };
+            this.update(cx, |this, cx| this.set_room(Some(room.clone()), cx))?
                 .await?;
             this.update(cx, |this, cx| this.report_call_event("Channel Joined", cx))?;
-            Ok(room)
+            Ok(Some(room))
         })
     }
 
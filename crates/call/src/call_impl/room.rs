@@ -207,6 +207,7 @@ impl Room {
 
     pub(crate) async fn join_channel(
         channel_id: ChannelId,
+        as_guest: bool,
         client: Arc<Client>,
         user_store: Entity<UserStore>,
         cx: AsyncApp,
@@ -215,6 +216,7 @@ impl Room {
             client
                 .request(proto::JoinChannel {
                     channel_id: channel_id.0,
+                    as_guest,
                 })
                 .await?,
             client,
@@ -11,7 +11,11 @@ pub use channel_chat::{
     ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId, MessageParams,
     mentions_to_proto,
 };
-pub use channel_store::{Channel, ChannelEvent, ChannelMembership, ChannelStore};
+pub use channel_store::{
+    CONNECTION_STATS_INTERVAL, Channel, ChannelEvent, ChannelMembership, ChannelStore,
+    ConnectionQuality, ConnectionStats, ConnectionStatsSource, InviteMembersError,
+    TYPING_BROADCAST_INTERVAL, TYPING_TIMEOUT,
+};
 
 #[cfg(test)]
 mod channel_store_tests;
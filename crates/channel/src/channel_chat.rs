@@ -25,6 +25,9 @@ pub struct ChannelChat {
     loaded_all_messages: bool,
     last_acknowledged_id: Option<u64>,
     next_pending_message_id: usize,
+    /// Pending messages that couldn't be sent, which can be sent again with
+    /// [`ChannelChat::retry_message`].
+    failed_message_ids: HashSet<ChannelMessageId>,
     first_loaded_message_id: Option<u64>,
     user_store: Entity<UserStore>,
     rpc: Arc<Client>,
@@ -128,6 +131,7 @@ impl ChannelChat {
                 acknowledged_message_ids: Default::default(),
                 loaded_all_messages: false,
                 next_pending_message_id: 0,
+                failed_message_ids: Default::default(),
                 last_acknowledged_id: None,
                 rng: StdRng::from_entropy(),
                 first_loaded_message_id: None,
@@ -165,6 +169,11 @@ impl ChannelChat {
         &self.rpc
     }
 
+    /// Sends a message to the channel. The message is shown as pending right away, and replaced
+    /// with the one saved by the server once it has been sent.
+    ///
+    /// If sending fails, the pending message is kept and marked as failed, see
+    /// [`ChannelChat::is_failed`].
     pub fn send_message(
         &mut self,
         message: MessageParams,
@@ -199,12 +208,52 @@ impl ChannelChat {
             ),
             cx,
         );
+        Ok(self.send_pending_message(pending_id, nonce, message, cx))
+    }
+
+    /// Whether the given pending message couldn't be sent.
+    pub fn is_failed(&self, id: ChannelMessageId) -> bool {
+        self.failed_message_ids.contains(&id)
+    }
+
+    /// Sends a pending message that previously failed to send again.
+    pub fn retry_message(
+        &mut self,
+        id: ChannelMessageId,
+        cx: &mut Context<Self>,
+    ) -> Result<Task<Result<u64>>> {
+        let message = self
+            .pending_messages()
+            .find(|message| message.id == id)
+            .filter(|_| self.failed_message_ids.contains(&id))
+            .cloned()
+            .ok_or_else(|| anyhow!("no failed message with id {id:?}"))?;
+        self.set_message_failed(id, false, cx);
+        Ok(self.send_pending_message(
+            id,
+            message.nonce,
+            MessageParams {
+                text: message.body,
+                mentions: message.mentions,
+                reply_to_message_id: message.reply_to_message_id,
+            },
+            cx,
+        ))
+    }
+
+    fn send_pending_message(
+        &mut self,
+        pending_id: ChannelMessageId,
+        nonce: u128,
+        message: MessageParams,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<u64>> {
+        let channel_id = self.channel_id;
         let user_store = self.user_store.clone();
         let rpc = self.rpc.clone();
         let outgoing_messages_lock = self.outgoing_messages_lock.clone();
 
-        // todo - handle messages that fail to send (e.g. >1024 chars)
-        Ok(cx.spawn(async move |this, cx| {
+        cx.spawn(async move |this, cx| {
             let outgoing_message_guard = outgoing_messages_lock.lock().await;
             let request = rpc.request(proto::SendChannelMessage {
                 channel_id: channel_id.0,
@@ -213,9 +262,17 @@ impl ChannelChat {
                 mentions: mentions_to_proto(&message.mentions),
                 reply_to_message_id: message.reply_to_message_id,
             });
-            let response = request.await?;
+            let response = request
+                .await
+                .and_then(|response| response.message.ok_or_else(|| anyhow!("invalid message")));
             drop(outgoing_message_guard);
-            let response = response.message.ok_or_else(|| anyhow!("invalid message"))?;
+            let response = match response {
+                Ok(response) => response,
+                Err(error) => {
+                    this.update(cx, |this, cx| this.set_message_failed(pending_id, true, cx))?;
+                    return Err(error);
+                }
+            };
             let id = response.id;
             let message = ChannelMessage::from_proto(response, &user_store, cx).await?;
             this.update(cx, |this, cx| {
@@ -225,7 +282,29 @@ impl ChannelChat {
                 }
             })?;
             Ok(id)
-        }))
+        })
+    }
+
+    fn set_message_failed(&mut self, id: ChannelMessageId, failed: bool, cx: &mut Context<Self>) {
+        let changed = if failed {
+            self.failed_message_ids.insert(id)
+        } else {
+            self.failed_message_ids.remove(&id)
+        };
+        if !changed {
+            return;
+        }
+
+        let mut cursor = self.messages.cursor::<(ChannelMessageId, Count)>(&());
+        cursor.seek(&id, Bias::Left, &());
+        if cursor.item().is_some_and(|message| message.id == id) {
+            let message_ix = cursor.start().1.0;
+            cx.emit(ChannelChatEvent::UpdateMessage {
+                message_id: id,
+                message_ix,
+            });
+        }
+        cx.notify();
     }
 
     pub fn remove_message(&mut self, id: u64, cx: &mut Context<Self>) -> Task<Result<()>> {
@@ -285,7 +364,8 @@ impl ChannelChat {
                 let response = rpc
                     .request(proto::GetChannelMessages {
                         channel_id: channel_id.0,
-                        before_message_id,
+                        before_message_id: Some(before_message_id),
+                        limit: None,
                     })
                     .await?;
                 Self::handle_loaded_messages(
@@ -477,6 +557,7 @@ impl ChannelChat {
                     .await?;
                     this.update(cx, |this, cx| {
                         this.insert_messages(SumTree::from_item(message, &()), cx);
+                        this.set_message_failed(pending_message.id, false, cx);
                     })?;
                 }
 
@@ -766,6 +847,14 @@ impl ChannelMessage {
         matches!(self.id, ChannelMessageId::Pending(_))
     }
 
+    /// Whether the message mentions the given user, either because they were picked when
+    /// composing it or because the server found an `@login` of theirs in its body.
+    pub fn mentions_user(&self, user_id: UserId) -> bool {
+        self.mentions
+            .iter()
+            .any(|(_, mentioned_user_id)| *mentioned_user_id == user_id)
+    }
+
     pub async fn from_proto_vec(
         proto_messages: Vec<proto::ChannelMessage>,
         user_store: &Entity<UserStore>,
@@ -5,6 +5,7 @@ use anyhow::{Result, anyhow};
 use channel_index::ChannelIndex;
 use client::{ChannelId, Client, ClientSettings, Subscription, User, UserId, UserStore};
 use collections::{HashMap, HashSet, hash_map};
+use db::kvp::KEY_VALUE_STORE;
 use futures::{Future, FutureExt, StreamExt, channel::mpsc, future::Shared};
 use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Global, SharedString, Task,
@@ -15,12 +16,35 @@ use rpc::{
     TypedEnvelope,
     proto::{self, ChannelRole, ChannelVisibility},
 };
+use serde::{Deserialize, Serialize};
 use settings::Settings;
-use std::{mem, sync::Arc, time::Duration};
+use std::{
+    mem,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use util::{ResultExt, maybe};
 
 pub const RECONNECT_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// How often call participants' connection stats are sampled while a
+/// [`ConnectionStatsSource`] is set.
+pub const CONNECTION_STATS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often the user typing in a channel's chat is re-broadcast to the
+/// channel's other members.
+pub const TYPING_BROADCAST_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How long another member is shown as typing after their typing was last
+/// broadcast.
+pub const TYPING_TIMEOUT: Duration = Duration::from_secs(6);
+
+/// The key under which the given user's channels are cached.
+fn channel_cache_key(user_id: u64) -> String {
+    format!("channel_store_cache-{user_id}")
+}
+
 pub fn init(client: &Arc<Client>, user_store: Entity<UserStore>, cx: &mut App) {
     let channel_store = cx.new(|cx| ChannelStore::new(client.clone(), user_store.clone(), cx));
     cx.set_global(GlobalChannelStore(channel_store));
@@ -37,6 +61,8 @@ pub struct ChannelStore {
     channel_invitations: Vec<Arc<Channel>>,
     channel_participants: HashMap<ChannelId, Vec<Arc<User>>>,
     channel_states: HashMap<ChannelId, ChannelState>,
+    /// In the order they were favorited.
+    favorite_channel_ids: Vec<ChannelId>,
     outgoing_invites: HashSet<(ChannelId, UserId)>,
     update_channels_tx: mpsc::UnboundedSender<proto::UpdateChannels>,
     opened_buffers: HashMap<ChannelId, OpenEntityHandle<ChannelBuffer>>,
@@ -44,10 +70,49 @@ pub struct ChannelStore {
     client: Arc<Client>,
     did_subscribe: bool,
     user_store: Entity<UserStore>,
-    _rpc_subscriptions: [Subscription; 2],
+    _rpc_subscriptions: [Subscription; 4],
     _watch_connection_status: Task<Option<()>>,
     disconnect_channel_buffers_task: Option<Task<()>>,
     _update_channels: Task<()>,
+    cache_enabled: bool,
+    /// Channels shown from the cache that the server hasn't confirmed yet.
+    unreconciled_cached_channels: Option<HashSet<ChannelId>>,
+    /// Invitations from before reconnecting that the server hasn't resent yet.
+    unreconciled_channel_invitations: Option<HashSet<ChannelId>>,
+    /// Whether the next channel update is the first since connecting, whose participant
+    /// lists replace the ones from before.
+    unreconciled_channel_participants: bool,
+    persist_channel_cache_task: Option<Task<()>>,
+    /// The user whose cached channels were last loaded.
+    channel_cache_user_id: Option<u64>,
+    load_channel_cache_task: Option<Task<()>>,
+    participant_connection_quality: HashMap<(ChannelId, UserId), ConnectionQuality>,
+    sample_connection_stats_task: Option<Task<()>>,
+    /// The members fetched by [`ChannelStore::get_members_for_channels`], keyed by root
+    /// channel, since members of a root channel are members of all of its descendants.
+    channel_members: HashMap<ChannelId, Vec<Arc<User>>>,
+    /// Channel creations that are in flight, by idempotency key.
+    pending_channel_creations: HashMap<String, Shared<Task<Result<ChannelId, Arc<anyhow::Error>>>>>,
+    /// The members typing in each channel's chat, along with the task that
+    /// stops showing them as typing after [`TYPING_TIMEOUT`].
+    typing_participants: HashMap<ChannelId, Vec<(Arc<User>, Task<()>)>>,
+    /// When the user's typing was last broadcast, for the channels they're typing in.
+    typing_broadcasts: HashMap<ChannelId, Instant>,
+}
+
+/// The subset of a [`Channel`] persisted to disk by the channel cache.
+#[derive(Serialize, Deserialize)]
+struct SerializedChannel {
+    id: u64,
+    name: String,
+    visibility: i32,
+    parent_path: Vec<u64>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    max_participants: Option<u32>,
 }
 
 #[derive(Clone, Debug)]
@@ -56,6 +121,11 @@ pub struct Channel {
     pub name: SharedString,
     pub visibility: proto::ChannelVisibility,
     pub parent_path: Vec<ChannelId>,
+    pub description: Option<String>,
+    /// Archived channels keep their history but are hidden from [`ChannelStore::channels`].
+    pub archived: bool,
+    /// The maximum number of users that can be in the channel's call at once.
+    pub max_participants: Option<u32>,
 }
 
 #[derive(Default, Debug)]
@@ -65,6 +135,8 @@ pub struct ChannelState {
     observed_notes_version: NotesVersion,
     observed_chat_message: Option<u64>,
     role: Option<ChannelRole>,
+    notification_level: proto::ChannelNotificationLevel,
+    has_activity: bool,
 }
 
 impl Channel {
@@ -103,6 +175,61 @@ impl Channel {
     }
 }
 
+/// Network statistics for a call participant, as reported by WebRTC.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ConnectionStats {
+    pub round_trip_time: Duration,
+    /// The fraction of packets lost, between 0 and 1.
+    pub packet_loss: f32,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConnectionQuality {
+    /// No stats have been reported for the participant yet.
+    #[default]
+    Unknown,
+    Poor,
+    Good,
+    Excellent,
+}
+
+impl ConnectionQuality {
+    pub fn from_stats(stats: &ConnectionStats) -> Self {
+        if stats.round_trip_time > Duration::from_millis(400) || stats.packet_loss > 0.1 {
+            Self::Poor
+        } else if stats.round_trip_time > Duration::from_millis(150) || stats.packet_loss > 0.02 {
+            Self::Good
+        } else {
+            Self::Excellent
+        }
+    }
+}
+
+/// Reports the connection stats of call participants, such as those of the
+/// active call's WebRTC session.
+pub trait ConnectionStatsSource: 'static {
+    fn participant_stats(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        cx: &App,
+    ) -> Option<ConnectionStats>;
+}
+
+impl<F> ConnectionStatsSource for F
+where
+    F: 'static + Fn(ChannelId, UserId, &App) -> Option<ConnectionStats>,
+{
+    fn participant_stats(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        cx: &App,
+    ) -> Option<ConnectionStats> {
+        self(channel_id, user_id, cx)
+    }
+}
+
 #[derive(Debug)]
 pub struct ChannelMembership {
     pub user: Arc<User>,
@@ -110,6 +237,23 @@ pub struct ChannelMembership {
     pub role: proto::ChannelRole,
 }
 impl ChannelMembership {
+    /// Converts members of a channel, skipping those whose user isn't in the user store.
+    fn from_proto_members(
+        members: Vec<proto::ChannelMember>,
+        user_store: &UserStore,
+    ) -> Vec<ChannelMembership> {
+        members
+            .into_iter()
+            .filter_map(|member| {
+                Some(ChannelMembership {
+                    user: user_store.get_cached_user(member.user_id)?,
+                    role: member.role(),
+                    kind: member.kind(),
+                })
+            })
+            .collect()
+    }
+
     pub fn sort_key(&self) -> MembershipSortKey {
         MembershipSortKey {
             role_order: match self.role {
@@ -121,7 +265,8 @@ impl ChannelMembership {
             },
             kind_order: match self.kind {
                 proto::channel_member::Kind::Member => 0,
-                proto::channel_member::Kind::Invitee => 1,
+                proto::channel_member::Kind::Guest => 1,
+                proto::channel_member::Kind::Invitee => 2,
             },
             username_order: self.user.github_login.as_str(),
         }
@@ -138,10 +283,48 @@ pub struct MembershipSortKey<'a> {
 pub enum ChannelEvent {
     ChannelCreated(ChannelId),
     ChannelRenamed(ChannelId),
+    /// Users joined or left the call in the given channel.
+    ParticipantsChanged {
+        channel_id: ChannelId,
+        added: Vec<UserId>,
+        removed: Vec<UserId>,
+    },
+    /// The user was invited to the given channel.
+    InvitationReceived {
+        channel_id: ChannelId,
+    },
+    /// An invitation was accepted, declined or revoked.
+    InvitationRemoved {
+        channel_id: ChannelId,
+    },
+    /// A call participant's connection got better or worse.
+    ParticipantConnectionQualityChanged {
+        channel_id: ChannelId,
+        user_id: UserId,
+        quality: ConnectionQuality,
+    },
+    /// Someone started or stopped typing in the given channel's chat.
+    TypingParticipantsChanged {
+        channel_id: ChannelId,
+    },
 }
 
 impl EventEmitter<ChannelEvent> for ChannelStore {}
 
+/// Returned by [`ChannelStore::invite_members`] when some of the invites couldn't be sent.
+#[derive(Debug)]
+pub struct InviteMembersError {
+    pub failed_user_ids: Vec<UserId>,
+}
+
+impl std::fmt::Display for InviteMembersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to invite users {:?}", self.failed_user_ids)
+    }
+}
+
+impl std::error::Error for InviteMembersError {}
+
 enum OpenEntityHandle<E> {
     Open(WeakEntity<E>),
     Loading(Shared<Task<Result<Entity<E>, Arc<anyhow::Error>>>>),
@@ -160,6 +343,8 @@ impl ChannelStore {
         let rpc_subscriptions = [
             client.add_message_handler(cx.weak_entity(), Self::handle_update_channels),
             client.add_message_handler(cx.weak_entity(), Self::handle_update_user_channels),
+            client.add_message_handler(cx.weak_entity(), Self::handle_channel_activity),
+            client.add_message_handler(cx.weak_entity(), Self::handle_channel_typing_update),
         ];
 
         let mut connection_status = client.status();
@@ -167,6 +352,8 @@ impl ChannelStore {
         let watch_connection_status = cx.spawn(async move |this, cx| {
             while let Some(status) = connection_status.next().await {
                 let this = this.upgrade()?;
+                // The signed-in user is known once the client starts connecting.
+                this.update(cx, |this, cx| this.load_channel_cache(cx)).ok();
                 match status {
                     client::Status::Connected { .. } => {
                         this.update(cx, |this, cx| this.handle_connect(cx))
@@ -191,6 +378,7 @@ impl ChannelStore {
             channel_invitations: Vec::default(),
             channel_index: ChannelIndex::default(),
             channel_participants: Default::default(),
+            favorite_channel_ids: Vec::new(),
             outgoing_invites: Default::default(),
             opened_buffers: Default::default(),
             opened_chats: Default::default(),
@@ -218,7 +406,127 @@ impl ChannelStore {
             }),
             channel_states: Default::default(),
             did_subscribe: false,
+            cache_enabled: false,
+            unreconciled_cached_channels: None,
+            unreconciled_channel_invitations: None,
+            persist_channel_cache_task: None,
+            channel_cache_user_id: None,
+            load_channel_cache_task: None,
+            unreconciled_channel_participants: false,
+            participant_connection_quality: Default::default(),
+            sample_connection_stats_task: None,
+            channel_members: Default::default(),
+            pending_channel_creations: Default::default(),
+            typing_participants: Default::default(),
+            typing_broadcasts: Default::default(),
+        }
+    }
+
+    /// Persist the channel list to disk as it changes, and restore the list
+    /// persisted by a previous session of the signed-in user if no channels are
+    /// known yet. Each user's channels are cached separately.
+    ///
+    /// Restored channels are shown until the server sends its channel list, at
+    /// which point any channels that no longer exist are removed.
+    pub fn enable_channel_cache(&mut self, cx: &mut Context<Self>) {
+        self.cache_enabled = true;
+        self.load_channel_cache(cx);
+    }
+
+    /// Reads the signed-in user's cached channels in the background, unless
+    /// they were already loaded.
+    fn load_channel_cache(&mut self, cx: &mut Context<Self>) {
+        let Some(user_id) = self.client.user_id() else {
+            return;
+        };
+        if !self.cache_enabled || self.channel_cache_user_id == Some(user_id) {
+            return;
+        }
+        if self.channel_cache_user_id.is_some() {
+            // Don't keep showing the previous user's channels.
+            self.channel_index.clear();
+            self.unreconciled_cached_channels = None;
+            cx.notify();
+        }
+        self.channel_cache_user_id = Some(user_id);
+
+        self.load_channel_cache_task = Some(cx.spawn(async move |this, cx| {
+            let serialized = cx
+                .background_spawn(
+                    async move { KEY_VALUE_STORE.read_kvp(&channel_cache_key(user_id)) },
+                )
+                .await;
+            let Some(channels) = serialized.log_err().flatten().and_then(|serialized| {
+                serde_json::from_str::<Vec<SerializedChannel>>(&serialized).log_err()
+            }) else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                this.restore_cached_channels(user_id, channels, cx)
+            })
+            .ok();
+        }));
+    }
+
+    fn restore_cached_channels(
+        &mut self,
+        user_id: u64,
+        channels: Vec<SerializedChannel>,
+        cx: &mut Context<Self>,
+    ) {
+        // The server's channel list takes precedence if it arrived first.
+        if self.client.user_id() != Some(user_id) || !self.channel_index.by_id().is_empty() {
+            return;
+        }
+
+        let mut cached_channel_ids = HashSet::default();
+        let mut index = self.channel_index.bulk_insert();
+        for channel in channels {
+            cached_channel_ids.insert(ChannelId(channel.id));
+            index.insert(proto::Channel {
+                id: channel.id,
+                name: channel.name,
+                visibility: channel.visibility,
+                parent_path: channel.parent_path,
+                description: channel.description,
+                archived: channel.archived,
+                max_participants: channel.max_participants,
+            });
         }
+        drop(index);
+
+        self.unreconciled_cached_channels = Some(cached_channel_ids);
+        cx.notify();
+    }
+
+    fn persist_channel_cache(&mut self, cx: &mut Context<Self>) {
+        let Some(user_id) = self.client.user_id() else {
+            return;
+        };
+        let channels = self
+            .channel_index
+            .by_id()
+            .values()
+            .map(|channel| SerializedChannel {
+                id: channel.id.0,
+                name: channel.name.to_string(),
+                visibility: channel.visibility as i32,
+                parent_path: channel.parent_path.iter().map(|id| id.0).collect(),
+                description: channel.description.clone(),
+                archived: channel.archived,
+                max_participants: channel.max_participants,
+            })
+            .collect::<Vec<_>>();
+        self.persist_channel_cache_task = Some(cx.background_spawn(async move {
+            maybe!(async move {
+                let serialized = serde_json::to_string(&channels)?;
+                KEY_VALUE_STORE
+                    .write_kvp(channel_cache_key(user_id), serialized)
+                    .await
+            })
+            .await
+            .log_err();
+        }));
     }
 
     pub fn initialize(&mut self) {
@@ -250,9 +558,34 @@ impl ChannelStore {
             .position(|id| *id == channel_id)
     }
 
-    /// Returns an iterator over all unique channels
+    /// Returns an iterator over all unique channels, except archived ones
     pub fn channels(&self) -> impl '_ + Iterator<Item = &Arc<Channel>> {
-        self.channel_index.by_id().values()
+        self.channel_index
+            .by_id()
+            .values()
+            .filter(|channel| !self.is_channel_archived(channel.id))
+    }
+
+    /// Returns the channels that are archived, or that are inside an archived channel.
+    pub fn archived_channels(&self) -> Vec<Arc<Channel>> {
+        self.channel_index
+            .by_id()
+            .values()
+            .filter(|channel| self.is_channel_archived(channel.id))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns whether the channel or one of its ancestors is archived.
+    pub fn is_channel_archived(&self, channel_id: ChannelId) -> bool {
+        let Some(channel) = self.channel_for_id(channel_id) else {
+            return false;
+        };
+        channel.archived
+            || channel.parent_path.iter().any(|ancestor_id| {
+                self.channel_for_id(*ancestor_id)
+                    .map_or(false, |ancestor| ancestor.archived)
+            })
     }
 
     /// Iterate over all entries in the channel DAG
@@ -266,6 +599,21 @@ impl ChannelStore {
             })
     }
 
+    /// Returns the channels whose names fuzzily match `query`, ignoring case,
+    /// in the same order as [`Self::ordered_channels`].
+    pub fn channels_matching(&self, query: &str) -> Vec<Arc<Channel>> {
+        let query = query.to_lowercase();
+        self.ordered_channels()
+            .filter(|(_, channel)| {
+                let mut name_chars = channel.name.chars().flat_map(char::to_lowercase);
+                query
+                    .chars()
+                    .all(|query_char| name_chars.any(|name_char| name_char == query_char))
+            })
+            .map(|(_, channel)| channel.clone())
+            .collect()
+    }
+
     pub fn channel_at_index(&self, ix: usize) -> Option<&Arc<Channel>> {
         let channel_id = self.channel_index.ordered_channels().get(ix)?;
         self.channel_index.by_id().get(channel_id)
@@ -285,10 +633,76 @@ impl ChannelStore {
         &self.channel_invitations
     }
 
+    /// Returns the user's favorite channels in the order they were favorited.
+    pub fn favorite_channels(&self) -> Vec<Arc<Channel>> {
+        self.favorite_channel_ids
+            .iter()
+            .filter_map(|id| self.channel_index.by_id().get(id).cloned())
+            .collect()
+    }
+
+    pub fn is_favorite(&self, channel_id: ChannelId) -> bool {
+        self.favorite_channel_ids.contains(&channel_id)
+    }
+
+    pub fn toggle_favorite(
+        &mut self,
+        channel_id: ChannelId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let favorite = !self.is_favorite(channel_id);
+        let previous_favorite_channel_ids = self.favorite_channel_ids.clone();
+        if favorite {
+            self.favorite_channel_ids.push(channel_id);
+        } else {
+            self.favorite_channel_ids.retain(|id| *id != channel_id);
+        }
+        cx.notify();
+
+        // The server then sends the full list of favorites to all of the user's
+        // connections, which replaces this optimistic update.
+        let client = self.client.clone();
+        cx.spawn(async move |this, cx| {
+            let result = client
+                .request(proto::SetChannelFavorite {
+                    channel_id: channel_id.0,
+                    favorite,
+                })
+                .await;
+
+            if result.is_err() {
+                this.update(cx, |this, cx| {
+                    if this.is_favorite(channel_id) == favorite {
+                        this.favorite_channel_ids = previous_favorite_channel_ids;
+                    }
+                    cx.notify();
+                })?;
+            }
+
+            result?;
+            Ok(())
+        })
+    }
+
     pub fn channel_for_id(&self, channel_id: ChannelId) -> Option<&Arc<Channel>> {
         self.channel_index.by_id().get(&channel_id)
     }
 
+    /// Returns the channels from the root down to the given channel, inclusive,
+    /// or an empty list if the channel isn't known.
+    pub fn channel_path(&self, channel_id: ChannelId) -> Vec<Arc<Channel>> {
+        let Some(channel) = self.channel_for_id(channel_id) else {
+            return Vec::new();
+        };
+        channel
+            .parent_path
+            .iter()
+            .filter_map(|ancestor_id| self.channel_for_id(*ancestor_id))
+            .chain(Some(channel))
+            .cloned()
+            .collect()
+    }
+
     pub fn has_open_channel_buffer(&self, channel_id: ChannelId, _cx: &App) -> bool {
         if let Some(buffer) = self.opened_buffers.get(&channel_id) {
             if let OpenEntityHandle::Open(buffer) = buffer {
@@ -343,6 +757,30 @@ impl ChannelStore {
         })
     }
 
+    /// Fetches up to `limit` of the channel's chat messages, oldest first. When `before` is given,
+    /// these are the messages sent before the one with that id, otherwise the latest ones.
+    ///
+    /// Pages aren't cached, since messages can be edited or removed and the server only tells the
+    /// participants of a channel's chat about it.
+    pub fn load_channel_messages(
+        &mut self,
+        channel_id: ChannelId,
+        before: Option<u64>,
+        limit: usize,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<ChannelMessage>>> {
+        let request = self.client.request(proto::GetChannelMessages {
+            channel_id: channel_id.0,
+            before_message_id: before,
+            limit: Some(u32::try_from(limit).unwrap_or(u32::MAX)),
+        });
+        let user_store = self.user_store.clone();
+        cx.spawn(async move |_, cx| {
+            let response = request.await?;
+            ChannelMessage::from_proto_vec(response.messages, &user_store, cx).await
+        })
+    }
+
     pub fn has_channel_buffer_changed(&self, channel_id: ChannelId) -> bool {
         self.channel_states
             .get(&channel_id)
@@ -355,6 +793,70 @@ impl ChannelStore {
             .is_some_and(|state| state.has_new_messages())
     }
 
+    /// Whether new messages in the channel should be surfaced to the user.
+    ///
+    /// Messages in channels that aren't set to [`proto::ChannelNotificationLevel::All`]
+    /// still count as unread, but aren't surfaced. Mentions are delivered
+    /// separately, as notifications.
+    pub fn should_notify(&self, channel_id: ChannelId) -> bool {
+        self.channel_states.get(&channel_id).is_some_and(|state| {
+            state.notification_level == proto::ChannelNotificationLevel::All
+                && state.has_new_messages()
+        })
+    }
+
+    /// Whether something notable, like someone joining the channel while it was
+    /// empty, has happened since the local user was last in the channel.
+    ///
+    /// Activity in channels set to [`proto::ChannelNotificationLevel::None`] isn't reported.
+    pub fn channel_has_activity(&self, channel_id: ChannelId) -> bool {
+        self.channel_states.get(&channel_id).is_some_and(|state| {
+            state.has_activity && state.notification_level != proto::ChannelNotificationLevel::None
+        })
+    }
+
+    pub fn notification_level(&self, channel_id: ChannelId) -> proto::ChannelNotificationLevel {
+        self.channel_states
+            .get(&channel_id)
+            .map(|state| state.notification_level)
+            .unwrap_or_default()
+    }
+
+    pub fn set_channel_notifications(
+        &mut self,
+        channel_id: ChannelId,
+        level: proto::ChannelNotificationLevel,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let state = self.channel_states.entry(channel_id).or_default();
+        let previous_level = mem::replace(&mut state.notification_level, level);
+        cx.notify();
+
+        let client = self.client.clone();
+        cx.spawn(async move |this, cx| {
+            let result = client
+                .request(proto::SetChannelNotificationLevel {
+                    channel_id: channel_id.0,
+                    level: level.into(),
+                })
+                .await;
+
+            if result.is_err() {
+                this.update(cx, |this, cx| {
+                    if let Some(state) = this.channel_states.get_mut(&channel_id) {
+                        if state.notification_level == level {
+                            state.notification_level = previous_level;
+                        }
+                    }
+                    cx.notify();
+                })?;
+            }
+
+            result?;
+            Ok(())
+        })
+    }
+
     pub fn set_acknowledged_message_id(&mut self, channel_id: ChannelId, message_id: Option<u64>) {
         if let Some(state) = self.channel_states.get_mut(&channel_id) {
             state.latest_chat_message = message_id;
@@ -562,11 +1064,220 @@ impl ChannelStore {
             .map_or(&[], |v| v.as_slice())
     }
 
+    /// The other members currently typing in the channel's chat, in the order
+    /// they started typing.
+    pub fn typing_participants(&self, channel_id: ChannelId) -> Vec<Arc<User>> {
+        self.typing_participants
+            .get(&channel_id)
+            .map_or(Vec::new(), |participants| {
+                participants.iter().map(|(user, _)| user.clone()).collect()
+            })
+    }
+
+    /// Lets the channel's other members know whether the user is typing in its
+    /// chat. This can be called on every keystroke, as the user typing is only
+    /// broadcast once every [`TYPING_BROADCAST_INTERVAL`].
+    pub fn set_typing(&mut self, channel_id: ChannelId, is_typing: bool, cx: &mut Context<Self>) {
+        if is_typing {
+            let now = cx.background_executor().now();
+            if let Some(last_broadcast) = self.typing_broadcasts.get(&channel_id) {
+                if now.duration_since(*last_broadcast) < TYPING_BROADCAST_INTERVAL {
+                    return;
+                }
+            }
+            self.typing_broadcasts.insert(channel_id, now);
+        } else if self.typing_broadcasts.remove(&channel_id).is_none() {
+            return;
+        }
+
+        self.client
+            .send(proto::SetChannelTyping {
+                channel_id: channel_id.0,
+                is_typing,
+            })
+            .log_err();
+    }
+
+    fn remove_typing_participant(
+        &mut self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(participants) = self.typing_participants.get_mut(&channel_id) else {
+            return;
+        };
+        let Some(ix) = participants.iter().position(|(user, _)| user.id == user_id) else {
+            return;
+        };
+        participants.remove(ix);
+        if participants.is_empty() {
+            self.typing_participants.remove(&channel_id);
+        }
+        cx.emit(ChannelEvent::TypingParticipantsChanged { channel_id });
+        cx.notify();
+    }
+
+    /// The connection quality of a participant in the channel's call, as of the
+    /// last time stats were sampled from the [`ConnectionStatsSource`].
+    pub fn participant_connection_state(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> ConnectionQuality {
+        self.participant_connection_quality
+            .get(&(channel_id, user_id))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Samples the connection stats of all channel participants from `source`
+    /// every [`CONNECTION_STATS_INTERVAL`], emitting
+    /// [`ChannelEvent::ParticipantConnectionQualityChanged`] whenever a
+    /// participant's quality changes. Pass `None` to stop sampling.
+    pub fn set_connection_stats_source(
+        &mut self,
+        source: Option<Rc<dyn ConnectionStatsSource>>,
+        cx: &mut Context<Self>,
+    ) {
+        self.participant_connection_quality.clear();
+        self.sample_connection_stats_task = source.map(|source| {
+            cx.spawn(async move |this, cx| {
+                loop {
+                    cx.background_executor()
+                        .timer(CONNECTION_STATS_INTERVAL)
+                        .await;
+                    if this
+                        .update(cx, |this, cx| this.sample_connection_stats(&*source, cx))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            })
+        });
+        cx.notify();
+    }
+
+    fn sample_connection_stats(
+        &mut self,
+        source: &dyn ConnectionStatsSource,
+        cx: &mut Context<Self>,
+    ) {
+        let mut qualities = HashMap::default();
+        for (channel_id, participants) in &self.channel_participants {
+            for participant in participants {
+                let quality = source
+                    .participant_stats(*channel_id, participant.id, cx)
+                    .map_or(ConnectionQuality::Unknown, |stats| {
+                        ConnectionQuality::from_stats(&stats)
+                    });
+                qualities.insert((*channel_id, participant.id), quality);
+            }
+        }
+
+        let previous_qualities = mem::take(&mut self.participant_connection_quality);
+        let mut changed = false;
+        for (&(channel_id, user_id), &quality) in &qualities {
+            let previous_quality = previous_qualities
+                .get(&(channel_id, user_id))
+                .copied()
+                .unwrap_or_default();
+            if quality != previous_quality {
+                changed = true;
+                cx.emit(ChannelEvent::ParticipantConnectionQualityChanged {
+                    channel_id,
+                    user_id,
+                    quality,
+                });
+            }
+        }
+        self.participant_connection_quality = qualities;
+        if changed || previous_qualities.len() != self.participant_connection_quality.len() {
+            cx.notify();
+        }
+    }
+
     pub fn create_channel(
         &self,
         name: &str,
         parent_id: Option<ChannelId>,
         cx: &mut Context<Self>,
+    ) -> Task<Result<ChannelId>> {
+        self.create_channel_with_description(name, None, parent_id, cx)
+    }
+
+    pub fn create_channel_with_description(
+        &self,
+        name: &str,
+        description: Option<String>,
+        parent_id: Option<ChannelId>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ChannelId>> {
+        self.create_channel_internal(name, description, parent_id, None, cx)
+    }
+
+    /// Creates a channel at most once per `idempotency_key`.
+    ///
+    /// Calls made while a creation with the same key is in flight share its
+    /// result, and the server returns the existing channel when a request with
+    /// a key it has already seen is resent.
+    pub fn create_channel_with_idempotency_key(
+        &mut self,
+        name: &str,
+        parent_id: Option<ChannelId>,
+        idempotency_key: String,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ChannelId>> {
+        let task = if let Some(task) = self.pending_channel_creations.get(&idempotency_key) {
+            task.clone()
+        } else {
+            let create = self.create_channel_internal(
+                name,
+                None,
+                parent_id,
+                Some(idempotency_key.clone()),
+                cx,
+            );
+            let task = cx
+                .spawn({
+                    let idempotency_key = idempotency_key.clone();
+                    async move |this, cx| {
+                        let result = create.await;
+                        this.update(cx, |this, _| {
+                            this.pending_channel_creations.remove(&idempotency_key);
+                        })
+                        .ok();
+                        result.map_err(Arc::new)
+                    }
+                })
+                .shared();
+            self.pending_channel_creations
+                .insert(idempotency_key, task.clone());
+            task
+        };
+        cx.background_spawn(async move {
+            task.await.map_err(|error| {
+                // The error is shared with other callers, so it can't be moved out.
+                // Keep the rpc error that callers match error codes on, or else the
+                // whole chain's messages.
+                match error.downcast_ref::<proto::RpcError>() {
+                    Some(rpc_error) => {
+                        anyhow::Error::new(rpc_error.clone()).context("failed to create channel")
+                    }
+                    None => anyhow!("failed to create channel: {error:#}"),
+                }
+            })
+        })
+    }
+
+    fn create_channel_internal(
+        &self,
+        name: &str,
+        description: Option<String>,
+        parent_id: Option<ChannelId>,
+        idempotency_key: Option<String>,
+        cx: &mut Context<Self>,
     ) -> Task<Result<ChannelId>> {
         let client = self.client.clone();
         let name = name.trim_start_matches('#').to_owned();
@@ -575,6 +1286,8 @@ impl ChannelStore {
                 .request(proto::CreateChannel {
                     name,
                     parent_id: parent_id.map(|cid| cid.0),
+                    description,
+                    idempotency_key,
                 })
                 .await?;
 
@@ -604,10 +1317,11 @@ impl ChannelStore {
         })
     }
 
+    /// Moves the channel and its descendants under `to`, or to the root when `to` is `None`.
     pub fn move_channel(
         &mut self,
         channel_id: ChannelId,
-        to: ChannelId,
+        to: Option<ChannelId>,
         cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
         let client = self.client.clone();
@@ -615,7 +1329,7 @@ impl ChannelStore {
             let _ = client
                 .request(proto::MoveChannel {
                     channel_id: channel_id.0,
-                    to: to.0,
+                    to: to.map(|to| to.0),
                 })
                 .await?;
 
@@ -666,6 +1380,7 @@ impl ChannelStore {
 
             this.update(cx, |this, cx| {
                 this.outgoing_invites.remove(&(channel_id, user_id));
+                this.invalidate_channel_members(channel_id);
                 cx.notify();
             })?;
 
@@ -675,6 +1390,65 @@ impl ChannelStore {
         })
     }
 
+    /// Invites several users to a channel with a single request.
+    ///
+    /// Invites the server rejects don't prevent the others from being sent, but
+    /// cause the returned task to fail with an [`InviteMembersError`].
+    pub fn invite_members(
+        &mut self,
+        channel_id: ChannelId,
+        invitees: &[(UserId, proto::ChannelRole)],
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if invitees
+            .iter()
+            .any(|(user_id, _)| self.outgoing_invites.contains(&(channel_id, *user_id)))
+        {
+            return Task::ready(Err(anyhow!("invite request already in progress")));
+        }
+
+        let user_ids = invitees
+            .iter()
+            .map(|(user_id, _)| *user_id)
+            .collect::<Vec<_>>();
+        self.outgoing_invites
+            .extend(user_ids.iter().map(|user_id| (channel_id, *user_id)));
+        cx.notify();
+
+        let client = self.client.clone();
+        let request = proto::InviteChannelMembers {
+            channel_id: channel_id.0,
+            invitees: invitees
+                .iter()
+                .map(|(user_id, role)| proto::ChannelInvitee {
+                    user_id: *user_id,
+                    role: (*role).into(),
+                })
+                .collect(),
+        };
+        cx.spawn(async move |this, cx| {
+            let result = client.request(request).await;
+
+            this.update(cx, |this, cx| {
+                for user_id in &user_ids {
+                    this.outgoing_invites.remove(&(channel_id, *user_id));
+                }
+                this.invalidate_channel_members(channel_id);
+                cx.notify();
+            })?;
+
+            let response = result?;
+            if !response.failed_user_ids.is_empty() {
+                return Err(InviteMembersError {
+                    failed_user_ids: response.failed_user_ids,
+                }
+                .into());
+            }
+
+            Ok(())
+        })
+    }
+
     pub fn remove_member(
         &mut self,
         channel_id: ChannelId,
@@ -697,6 +1471,7 @@ impl ChannelStore {
 
             this.update(cx, |this, cx| {
                 this.outgoing_invites.remove(&(channel_id, user_id));
+                this.invalidate_channel_members(channel_id);
                 cx.notify();
             })?;
             result?;
@@ -728,6 +1503,59 @@ impl ChannelStore {
 
             this.update(cx, |this, cx| {
                 this.outgoing_invites.remove(&(channel_id, user_id));
+                this.invalidate_channel_members(channel_id);
+                cx.notify();
+            })?;
+
+            result?;
+            Ok(())
+        })
+    }
+
+    /// Promotes a member of the channel to admin, or demotes an admin to a regular member.
+    pub fn set_member_admin(
+        &mut self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        is_admin: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let role = if is_admin {
+            proto::ChannelRole::Admin
+        } else {
+            proto::ChannelRole::Member
+        };
+        self.set_member_role(channel_id, user_id, role, cx)
+    }
+
+    /// Makes another member of the channel an admin, so the channel stays manageable after
+    /// the current admin leaves. If `demote_self` is set, the current user becomes a regular
+    /// member.
+    pub fn transfer_admin(
+        &mut self,
+        channel_id: ChannelId,
+        to_user_id: UserId,
+        demote_self: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        if !self.outgoing_invites.insert((channel_id, to_user_id)) {
+            return Task::ready(Err(anyhow!("member request already in progress")));
+        }
+
+        cx.notify();
+        let client = self.client.clone();
+        cx.spawn(async move |this, cx| {
+            let result = client
+                .request(proto::TransferChannelAdmin {
+                    channel_id: channel_id.0,
+                    user_id: to_user_id,
+                    demote_self,
+                })
+                .await;
+
+            this.update(cx, |this, cx| {
+                this.outgoing_invites.remove(&(channel_id, to_user_id));
+                this.invalidate_channel_members(channel_id);
                 cx.notify();
             })?;
 
@@ -773,6 +1601,107 @@ impl ChannelStore {
         })
     }
 
+    /// Sets the channel's one-line description, or clears it when `None`.
+    pub fn set_channel_description(
+        &mut self,
+        channel_id: ChannelId,
+        description: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let client = self.client.clone();
+        cx.spawn(async move |this, cx| {
+            let response = client
+                .request(proto::SetChannelDescription {
+                    channel_id: channel_id.0,
+                    description,
+                })
+                .await?;
+            this.update(cx, |this, cx| {
+                this.apply_channel_response(response.channel, cx)
+            })?
+        })
+    }
+
+    /// Applies the updated channel that the server returned in response to a request.
+    fn apply_channel_response(
+        &mut self,
+        channel: Option<proto::Channel>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let channel = channel.ok_or_else(|| anyhow!("missing channel in response"))?;
+        let task = self.update_channels(
+            proto::UpdateChannels {
+                channels: vec![channel],
+                ..Default::default()
+            },
+            cx,
+        );
+        assert!(task.is_none());
+        Ok(())
+    }
+
+    /// Limits the number of users that can be in the channel's call at once, or removes the
+    /// limit when `None`. Joining a full channel fails with [`ErrorCode::ChannelFull`].
+    ///
+    /// [`ErrorCode::ChannelFull`]: rpc::ErrorCode::ChannelFull
+    pub fn set_channel_max_participants(
+        &mut self,
+        channel_id: ChannelId,
+        max_participants: Option<u32>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let client = self.client.clone();
+        cx.spawn(async move |this, cx| {
+            let response = client
+                .request(proto::SetChannelMaxParticipants {
+                    channel_id: channel_id.0,
+                    max_participants,
+                })
+                .await?;
+            this.update(cx, |this, cx| {
+                this.apply_channel_response(response.channel, cx)
+            })?
+        })
+    }
+
+    /// Hides the channel and its descendants from [`Self::channels`] without deleting them.
+    pub fn archive_channel(
+        &mut self,
+        channel_id: ChannelId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.set_channel_archived(channel_id, true, cx)
+    }
+
+    /// Restores a channel archived with [`Self::archive_channel`].
+    pub fn unarchive_channel(
+        &mut self,
+        channel_id: ChannelId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.set_channel_archived(channel_id, false, cx)
+    }
+
+    fn set_channel_archived(
+        &mut self,
+        channel_id: ChannelId,
+        archived: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let client = self.client.clone();
+        cx.spawn(async move |this, cx| {
+            let response = client
+                .request(proto::SetChannelArchived {
+                    channel_id: channel_id.0,
+                    archived,
+                })
+                .await?;
+            this.update(cx, |this, cx| {
+                this.apply_channel_response(response.channel, cx)
+            })?
+        })
+    }
+
     pub fn respond_to_channel_invite(
         &mut self,
         channel_id: ChannelId,
@@ -780,16 +1709,63 @@ impl ChannelStore {
         cx: &mut Context<Self>,
     ) -> Task<Result<()>> {
         let client = self.client.clone();
-        cx.background_spawn(async move {
+        cx.spawn(async move |this, cx| {
             client
                 .request(proto::RespondToChannelInvite {
                     channel_id: channel_id.0,
                     accept,
                 })
                 .await?;
+            if accept {
+                this.update(cx, |this, _| this.invalidate_channel_members(channel_id))?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Declines every pending channel invitation in a single request.
+    ///
+    /// Invitations the server failed to decline are left in
+    /// [`Self::channel_invitations`].
+    pub fn decline_all_invitations(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let channel_ids = self
+            .channel_invitations
+            .iter()
+            .map(|channel| channel.id)
+            .collect::<Vec<_>>();
+        if channel_ids.is_empty() {
+            return Task::ready(Ok(()));
+        }
+
+        let client = self.client.clone();
+        cx.spawn(async move |this, cx| {
+            let response = client
+                .request(proto::DeclineChannelInvitations {
+                    channel_ids: channel_ids.iter().map(|channel_id| channel_id.0).collect(),
+                })
+                .await?;
+
+            this.update(cx, |this, cx| {
+                this.remove_channel_invitations(
+                    |channel| {
+                        channel_ids.contains(&channel.id)
+                            && !response.failed_channel_ids.contains(&channel.id.0)
+                    },
+                    cx,
+                );
+                cx.notify();
+            })?;
+
+            if !response.failed_channel_ids.is_empty() {
+                return Err(anyhow!(
+                    "failed to decline invitations to channels {:?}",
+                    response.failed_channel_ids
+                ));
+            }
             Ok(())
         })
     }
+
     pub fn fuzzy_search_members(
         &self,
         channel_id: ChannelId,
@@ -808,22 +1784,110 @@ impl ChannelStore {
                 })
                 .await?;
             user_store.update(cx, |user_store, _| {
+                user_store.insert(response.users);
+                ChannelMembership::from_proto_members(response.members, user_store)
+            })
+        })
+    }
+
+    /// Fetch the members of several channels in a single request.
+    ///
+    /// Channels that the current user can't administer are omitted from the result.
+    pub fn get_members_for_channels(
+        &self,
+        channel_ids: &[ChannelId],
+        cx: &mut Context<Self>,
+    ) -> Task<Result<HashMap<ChannelId, Vec<ChannelMembership>>>> {
+        let client = self.client.clone();
+        let user_store = self.user_store.downgrade();
+        let channel_ids = channel_ids.iter().map(|id| id.0).collect();
+        cx.spawn(async move |this, cx| {
+            let response = client
+                .request(proto::GetMembersForChannels { channel_ids })
+                .await?;
+            let members_by_channel = user_store.update(cx, |user_store, _| {
                 user_store.insert(response.users);
                 response
-                    .members
+                    .channels
                     .into_iter()
-                    .filter_map(|member| {
-                        Some(ChannelMembership {
-                            user: user_store.get_cached_user(member.user_id)?,
-                            role: member.role(),
-                            kind: member.kind(),
-                        })
+                    .map(|channel| {
+                        let members =
+                            ChannelMembership::from_proto_members(channel.members, user_store);
+                        (ChannelId(channel.channel_id), members)
+                    })
+                    .collect::<HashMap<_, _>>()
+            })?;
+            this.update(cx, |this, _| {
+                for (channel_id, members) in &members_by_channel {
+                    let users = members
+                        .iter()
+                        .filter(|member| member.kind != proto::channel_member::Kind::Invitee)
+                        .map(|member| member.user.clone())
+                        .collect();
+                    let root_id = this.root_channel_id(*channel_id);
+                    this.channel_members.insert(root_id, users);
+                }
+            })?;
+            Ok(members_by_channel)
+        })
+    }
+
+    /// Returns the users that are members of any channel the current user
+    /// administers, other than the current user, ordered by GitHub login.
+    ///
+    /// Member lists fetched earlier are reused, and the ones that are missing
+    /// are fetched in a single request.
+    pub fn all_known_members(&mut self, cx: &mut Context<Self>) -> Task<Result<Vec<Arc<User>>>> {
+        let root_ids = self
+            .channel_index
+            .by_id()
+            .values()
+            .filter(|channel| channel.is_root_channel() && self.is_channel_admin(channel.id))
+            .map(|channel| channel.id)
+            .collect::<Vec<_>>();
+        let missing_channel_ids = root_ids
+            .iter()
+            .copied()
+            .filter(|root_id| !self.channel_members.contains_key(root_id))
+            .collect::<Vec<_>>();
+        let fetch_members = (!missing_channel_ids.is_empty())
+            .then(|| self.get_members_for_channels(&missing_channel_ids, cx));
+        let current_user_id = self.client.user_id();
+        cx.spawn(async move |this, cx| {
+            if let Some(fetch_members) = fetch_members {
+                fetch_members.await?;
+            }
+            this.read_with(cx, |this, _| {
+                let mut seen_user_ids = HashSet::default();
+                let mut users = root_ids
+                    .iter()
+                    .filter_map(|root_id| this.channel_members.get(root_id))
+                    .flatten()
+                    .filter(|user| {
+                        Some(user.id) != current_user_id && seen_user_ids.insert(user.id)
                     })
-                    .collect()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                users.sort_by(|a, b| a.github_login.cmp(&b.github_login));
+                users
             })
         })
     }
 
+    fn root_channel_id(&self, channel_id: ChannelId) -> ChannelId {
+        self.channel_index
+            .by_id()
+            .get(&channel_id)
+            .map_or(channel_id, |channel| channel.root_id())
+    }
+
+    /// Forgets the cached members of the root channel containing the given channel,
+    /// so that they're fetched again the next time they're needed.
+    fn invalidate_channel_members(&mut self, channel_id: ChannelId) {
+        let root_id = self.root_channel_id(channel_id);
+        self.channel_members.remove(&root_id);
+    }
+
     pub fn remove_channel(
         &self,
         channel_id: ChannelId,
@@ -853,6 +1917,13 @@ impl ChannelStore {
         mut cx: AsyncApp,
     ) -> Result<()> {
         this.update(&mut cx, |this, _| {
+            for channel in &message.payload.channels {
+                let root_id = channel.parent_path.first().copied().unwrap_or(channel.id);
+                this.channel_members.remove(&ChannelId(root_id));
+            }
+            for channel_id in &message.payload.delete_channels {
+                this.invalidate_channel_members(ChannelId(*channel_id));
+            }
             this.update_channels_tx
                 .unbounded_send(message.payload)
                 .unwrap();
@@ -860,6 +1931,67 @@ impl ChannelStore {
         Ok(())
     }
 
+    async fn handle_channel_activity(
+        this: Entity<Self>,
+        message: TypedEnvelope<proto::ChannelActivity>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let channel_id = ChannelId(message.payload.channel_id);
+            let user_id = this.client.user_id();
+            let is_participant = this
+                .channel_participants(channel_id)
+                .iter()
+                .any(|user| Some(user.id) == user_id);
+            if !is_participant {
+                this.channel_states
+                    .entry(channel_id)
+                    .or_default()
+                    .has_activity = true;
+                cx.notify();
+            }
+        })
+    }
+
+    async fn handle_channel_typing_update(
+        this: Entity<Self>,
+        message: TypedEnvelope<proto::ChannelTypingUpdate>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        let channel_id = ChannelId(message.payload.channel_id);
+        let user_id = message.payload.user_id;
+        if !message.payload.is_typing {
+            return this.update(&mut cx, |this, cx| {
+                this.remove_typing_participant(channel_id, user_id, cx)
+            });
+        }
+
+        let user = this
+            .update(&mut cx, |this, cx| {
+                this.user_store
+                    .update(cx, |user_store, cx| user_store.get_user(user_id, cx))
+            })?
+            .await?;
+        this.update(&mut cx, |this, cx| {
+            let expire = cx.spawn(async move |this, cx| {
+                cx.background_executor().timer(TYPING_TIMEOUT).await;
+                this.update(cx, |this, cx| {
+                    this.remove_typing_participant(channel_id, user_id, cx)
+                })
+                .ok();
+            });
+            let participants = this.typing_participants.entry(channel_id).or_default();
+            if let Some(participant) = participants.iter_mut().find(|(user, _)| user.id == user_id)
+            {
+                participant.1 = expire;
+            } else {
+                participants.push((user, expire));
+                cx.emit(ChannelEvent::TypingParticipantsChanged { channel_id });
+                cx.notify();
+            }
+        })
+    }
+
     async fn handle_update_user_channels(
         this: Entity<Self>,
         message: TypedEnvelope<proto::UpdateUserChannels>,
@@ -883,6 +2015,7 @@ impl ChannelStore {
                 );
             }
             for membership in message.payload.channel_memberships {
+                this.invalidate_channel_members(ChannelId(membership.channel_id));
                 if let Some(role) = ChannelRole::from_i32(membership.role) {
                     this.channel_states
                         .entry(ChannelId(membership.channel_id))
@@ -890,16 +2023,44 @@ impl ChannelStore {
                         .set_role(role)
                 }
             }
+            for setting in message.payload.channel_notification_levels {
+                this.channel_states
+                    .entry(ChannelId(setting.channel_id))
+                    .or_default()
+                    .notification_level = setting.level();
+            }
+            if let Some(favorite_channels) = message.payload.favorite_channels {
+                this.favorite_channel_ids = favorite_channels
+                    .channel_ids
+                    .into_iter()
+                    .map(ChannelId)
+                    .collect();
+            }
+            cx.notify();
         })
     }
 
     fn handle_connect(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
-        self.channel_index.clear();
-        self.channel_invitations.clear();
-        self.channel_participants.clear();
-        self.channel_index.clear();
+        if self.cache_enabled {
+            // Keep showing the last known channels until the server sends its
+            // channel list, rather than flashing an empty list.
+            self.unreconciled_cached_channels =
+                Some(self.channel_index.by_id().keys().copied().collect());
+        } else {
+            self.channel_index.clear();
+        }
+        // Keep showing pending invitations until the server resends them, so that
+        // only the ones withdrawn in the meantime are reported as removed.
+        self.unreconciled_channel_invitations = Some(
+            self.channel_invitations
+                .iter()
+                .map(|channel| channel.id)
+                .collect(),
+        );
         self.outgoing_invites.clear();
+        self.channel_members.clear();
         self.disconnect_channel_buffers_task.take();
+        self.unreconciled_channel_participants = true;
 
         for chat in self.opened_chats.values() {
             if let OpenEntityHandle::Open(chat) = chat {
@@ -1008,6 +2169,15 @@ impl ChannelStore {
     fn handle_disconnect(&mut self, wait_for_reconnect: bool, cx: &mut Context<Self>) {
         cx.notify();
         self.did_subscribe = false;
+        self.typing_broadcasts.clear();
+        for (channel_id, _) in self.typing_participants.drain() {
+            cx.emit(ChannelEvent::TypingParticipantsChanged { channel_id });
+        }
+        if !wait_for_reconnect {
+            // Invitations belong to the signed-out user, so they won't be resent.
+            self.unreconciled_channel_invitations = None;
+            self.remove_channel_invitations(|_| true, cx);
+        }
         self.disconnect_channel_buffers_task.get_or_insert_with(|| {
             cx.spawn(async move |this, cx| {
                 if wait_for_reconnect {
@@ -1030,14 +2200,47 @@ impl ChannelStore {
         });
     }
 
+    fn remove_channel_invitations(
+        &mut self,
+        mut predicate: impl FnMut(&Channel) -> bool,
+        cx: &mut Context<Self>,
+    ) {
+        self.channel_invitations.retain(|channel| {
+            if predicate(channel) {
+                cx.emit(ChannelEvent::InvitationRemoved {
+                    channel_id: channel.id,
+                });
+                false
+            } else {
+                true
+            }
+        });
+    }
+
     pub(crate) fn update_channels(
         &mut self,
         payload: proto::UpdateChannels,
         cx: &mut Context<ChannelStore>,
     ) -> Option<Task<Result<()>>> {
+        // The first update after connecting contains every pending invitation, so
+        // any earlier invitation missing from it was withdrawn while disconnected.
+        if let Some(previous_invitation_ids) = self.unreconciled_channel_invitations.take() {
+            self.remove_channel_invitations(
+                |channel| {
+                    previous_invitation_ids.contains(&channel.id)
+                        && !payload
+                            .channel_invitations
+                            .iter()
+                            .any(|invitation| invitation.id == channel.id.0)
+                },
+                cx,
+            );
+        }
         if !payload.remove_channel_invitations.is_empty() {
-            self.channel_invitations
-                .retain(|channel| !payload.remove_channel_invitations.contains(&channel.id.0));
+            self.remove_channel_invitations(
+                |channel| payload.remove_channel_invitations.contains(&channel.id.0),
+                cx,
+            );
         }
         for channel in payload.channel_invitations {
             match self
@@ -1045,25 +2248,47 @@ impl ChannelStore {
                 .binary_search_by_key(&channel.id, |c| c.id.0)
             {
                 Ok(ix) => {
-                    Arc::make_mut(&mut self.channel_invitations[ix]).name = channel.name.into()
+                    let invitation = Arc::make_mut(&mut self.channel_invitations[ix]);
+                    invitation.name = channel.name.into();
+                    invitation.description = channel.description;
+                }
+                Err(ix) => {
+                    let channel_id = ChannelId(channel.id);
+                    self.channel_invitations.insert(
+                        ix,
+                        Arc::new(Channel {
+                            id: channel_id,
+                            visibility: channel.visibility(),
+                            name: channel.name.into(),
+                            parent_path: channel.parent_path.into_iter().map(ChannelId).collect(),
+                            description: channel.description,
+                            archived: channel.archived,
+                            max_participants: channel.max_participants,
+                        }),
+                    );
+                    cx.emit(ChannelEvent::InvitationReceived { channel_id });
                 }
-                Err(ix) => self.channel_invitations.insert(
-                    ix,
-                    Arc::new(Channel {
-                        id: ChannelId(channel.id),
-                        visibility: channel.visibility(),
-                        name: channel.name.into(),
-                        parent_path: channel.parent_path.into_iter().map(ChannelId).collect(),
-                    }),
-                ),
             }
         }
 
-        let channels_changed = !payload.channels.is_empty()
+        let mut channels_changed = !payload.channels.is_empty()
             || !payload.delete_channels.is_empty()
             || !payload.latest_channel_message_ids.is_empty()
             || !payload.latest_channel_buffer_versions.is_empty();
 
+        // The first update after connecting contains every channel the user can
+        // see, so any cached channel missing from it has been removed server-side.
+        if let Some(cached_channel_ids) = self.unreconciled_cached_channels.take() {
+            let stale_channel_ids = cached_channel_ids
+                .into_iter()
+                .filter(|id| !payload.channels.iter().any(|channel| channel.id == id.0))
+                .collect::<Vec<_>>();
+            if !stale_channel_ids.is_empty() {
+                self.channel_index.delete_channels(&stale_channel_ids);
+                channels_changed = true;
+            }
+        }
+
         if channels_changed {
             if !payload.delete_channels.is_empty() {
                 let delete_channels: Vec<ChannelId> =
@@ -1119,15 +2344,33 @@ impl ChannelStore {
                     .or_default()
                     .update_latest_message_id(latest_channel_message.message_id);
             }
+
+            if self.cache_enabled {
+                self.persist_channel_cache(cx);
+            }
         }
 
         cx.notify();
-        if payload.channel_participants.is_empty() {
+        // The first update after connecting contains the participants of every channel,
+        // replacing any we missed updates for while disconnected.
+        let replace_all = mem::take(&mut self.unreconciled_channel_participants);
+        if payload.channel_participants.is_empty() && !replace_all {
             return None;
         }
 
+        Some(self.set_channel_participants(payload.channel_participants, replace_all, cx))
+    }
+
+    /// Applies the given participant lists. When `replace_all` is true, the
+    /// lists are treated as the complete state, and any channel missing from
+    /// them is considered to have no participants.
+    fn set_channel_participants(
+        &mut self,
+        channel_participants: Vec<proto::ChannelParticipants>,
+        replace_all: bool,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
         let mut all_user_ids = Vec::new();
-        let channel_participants = payload.channel_participants;
         for entry in &channel_participants {
             for user_id in entry.participant_user_ids.iter() {
                 if let Err(ix) = all_user_ids.binary_search(user_id) {
@@ -1139,10 +2382,35 @@ impl ChannelStore {
         let users = self
             .user_store
             .update(cx, |user_store, cx| user_store.get_users(all_user_ids, cx));
-        Some(cx.spawn(async move |this, cx| {
+        cx.spawn(async move |this, cx| {
             let users = users.await?;
 
             this.update(cx, |this, cx| {
+                if replace_all {
+                    let stale_channel_ids = this
+                        .channel_participants
+                        .keys()
+                        .copied()
+                        .filter(|channel_id| {
+                            !channel_participants
+                                .iter()
+                                .any(|entry| entry.channel_id == channel_id.0)
+                        })
+                        .collect::<Vec<_>>();
+                    for channel_id in stale_channel_ids {
+                        let Some(previous) = this.channel_participants.remove(&channel_id) else {
+                            continue;
+                        };
+                        if !previous.is_empty() {
+                            cx.emit(ChannelEvent::ParticipantsChanged {
+                                channel_id,
+                                added: Vec::new(),
+                                removed: previous.iter().map(|user| user.id).collect(),
+                            });
+                        }
+                    }
+                }
+
                 for entry in &channel_participants {
                     let mut participants: Vec<_> = entry
                         .participant_user_ids
@@ -1157,13 +2425,43 @@ impl ChannelStore {
 
                     participants.sort_by_key(|u| u.id);
 
-                    this.channel_participants
-                        .insert(ChannelId(entry.channel_id), participants);
+                    let channel_id = ChannelId(entry.channel_id);
+                    let previous = this
+                        .channel_participants
+                        .insert(channel_id, participants)
+                        .unwrap_or_default();
+                    let current = &this.channel_participants[&channel_id];
+                    let added = current
+                        .iter()
+                        .filter(|user| !previous.iter().any(|prev| prev.id == user.id))
+                        .map(|user| user.id)
+                        .collect::<Vec<_>>();
+                    let removed = previous
+                        .iter()
+                        .filter(|prev| !current.iter().any(|user| user.id == prev.id))
+                        .map(|user| user.id)
+                        .collect::<Vec<_>>();
+
+                    // Joining the channel means the user has seen its activity.
+                    let user_id = this.client.user_id();
+                    if current.iter().any(|user| Some(user.id) == user_id) {
+                        if let Some(state) = this.channel_states.get_mut(&channel_id) {
+                            state.has_activity = false;
+                        }
+                    }
+
+                    if !added.is_empty() || !removed.is_empty() {
+                        cx.emit(ChannelEvent::ParticipantsChanged {
+                            channel_id,
+                            added,
+                            removed,
+                        });
+                    }
                 }
 
                 cx.notify();
             })
-        }))
+        })
     }
 }
 
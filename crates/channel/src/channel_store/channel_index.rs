@@ -61,11 +61,17 @@ impl ChannelPathsInsertGuard<'_> {
 
             ret = existing_channel.visibility != channel_proto.visibility()
                 || existing_channel.name != channel_proto.name
-                || existing_channel.parent_path != parent_path;
+                || existing_channel.parent_path != parent_path
+                || existing_channel.description != channel_proto.description
+                || existing_channel.archived != channel_proto.archived
+                || existing_channel.max_participants != channel_proto.max_participants;
 
             existing_channel.visibility = channel_proto.visibility();
             existing_channel.name = channel_proto.name.into();
             existing_channel.parent_path = parent_path;
+            existing_channel.description = channel_proto.description;
+            existing_channel.archived = channel_proto.archived;
+            existing_channel.max_participants = channel_proto.max_participants;
         } else {
             self.channels_by_id.insert(
                 ChannelId(channel_proto.id),
@@ -74,6 +80,9 @@ impl ChannelPathsInsertGuard<'_> {
                     visibility: channel_proto.visibility(),
                     name: channel_proto.name.into(),
                     parent_path,
+                    description: channel_proto.description,
+                    archived: channel_proto.archived,
+                    max_participants: channel_proto.max_participants,
                 }),
             );
             self.insert_root(ChannelId(channel_proto.id));
@@ -1,4 +1,4 @@
-use crate::channel_chat::ChannelChatEvent;
+use crate::channel_chat::{ChannelChatEvent, ChannelMessageId};
 
 use super::*;
 use client::{Client, UserStore, test::FakeServer};
@@ -7,6 +7,11 @@ use gpui::{App, AppContext as _, Entity, SemanticVersion, TestAppContext};
 use http_client::FakeHttpClient;
 use rpc::proto::{self};
 use settings::SettingsStore;
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    time::Duration,
+};
 
 #[gpui::test]
 fn test_update_channels(cx: &mut App) {
@@ -21,12 +26,14 @@ fn test_update_channels(cx: &mut App) {
                     name: "b".to_string(),
                     visibility: proto::ChannelVisibility::Members as i32,
                     parent_path: Vec::new(),
+                    ..Default::default()
                 },
                 proto::Channel {
                     id: 2,
                     name: "a".to_string(),
                     visibility: proto::ChannelVisibility::Members as i32,
                     parent_path: Vec::new(),
+                    ..Default::default()
                 },
             ],
             ..Default::default()
@@ -52,12 +59,14 @@ fn test_update_channels(cx: &mut App) {
                     name: "x".to_string(),
                     visibility: proto::ChannelVisibility::Members as i32,
                     parent_path: vec![1],
+                    ..Default::default()
                 },
                 proto::Channel {
                     id: 4,
                     name: "y".to_string(),
                     visibility: proto::ChannelVisibility::Members as i32,
                     parent_path: vec![2],
+                    ..Default::default()
                 },
             ],
             ..Default::default()
@@ -89,18 +98,21 @@ fn test_dangling_channel_paths(cx: &mut App) {
                     name: "a".to_string(),
                     visibility: proto::ChannelVisibility::Members as i32,
                     parent_path: vec![],
+                    ..Default::default()
                 },
                 proto::Channel {
                     id: 1,
                     name: "b".to_string(),
                     visibility: proto::ChannelVisibility::Members as i32,
                     parent_path: vec![0],
+                    ..Default::default()
                 },
                 proto::Channel {
                     id: 2,
                     name: "c".to_string(),
                     visibility: proto::ChannelVisibility::Members as i32,
                     parent_path: vec![0, 1],
+                    ..Default::default()
                 },
             ],
             ..Default::default()
@@ -132,6 +144,107 @@ fn test_dangling_channel_paths(cx: &mut App) {
     assert_channels(&channel_store, &[(0, "a".to_string())], cx);
 }
 
+#[gpui::test]
+fn test_channel_path(cx: &mut App) {
+    let channel_store = init_test(cx);
+
+    update_channels(
+        &channel_store,
+        proto::UpdateChannels {
+            channels: vec![
+                proto::Channel {
+                    id: 1,
+                    name: "zed".to_string(),
+                    visibility: proto::ChannelVisibility::Members as i32,
+                    parent_path: vec![],
+                    ..Default::default()
+                },
+                proto::Channel {
+                    id: 2,
+                    name: "editor".to_string(),
+                    visibility: proto::ChannelVisibility::Members as i32,
+                    parent_path: vec![1],
+                    ..Default::default()
+                },
+                proto::Channel {
+                    id: 3,
+                    name: "text".to_string(),
+                    visibility: proto::ChannelVisibility::Members as i32,
+                    parent_path: vec![1, 2],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        },
+        cx,
+    );
+
+    let path_names = |channel_id: u64, cx: &mut App| {
+        channel_store.update(cx, |store, _| {
+            store
+                .channel_path(ChannelId(channel_id))
+                .iter()
+                .map(|channel| channel.name.to_string())
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(path_names(3, cx), ["zed", "editor", "text"]);
+    assert_eq!(path_names(2, cx), ["zed", "editor"]);
+    assert_eq!(path_names(1, cx), ["zed"]);
+    assert!(path_names(4, cx).is_empty());
+}
+
+#[gpui::test]
+fn test_channels_matching(cx: &mut App) {
+    let channel_store = init_test(cx);
+
+    update_channels(
+        &channel_store,
+        proto::UpdateChannels {
+            channels: vec![
+                proto::Channel {
+                    id: 1,
+                    name: "zed".to_string(),
+                    visibility: proto::ChannelVisibility::Members as i32,
+                    parent_path: vec![],
+                    ..Default::default()
+                },
+                proto::Channel {
+                    id: 2,
+                    name: "Editor".to_string(),
+                    visibility: proto::ChannelVisibility::Members as i32,
+                    parent_path: vec![1],
+                    ..Default::default()
+                },
+                proto::Channel {
+                    id: 3,
+                    name: "rust".to_string(),
+                    visibility: proto::ChannelVisibility::Members as i32,
+                    parent_path: vec![],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        },
+        cx,
+    );
+
+    let matching_names = |query: &str, cx: &mut App| {
+        channel_store.update(cx, |store, _| {
+            store
+                .channels_matching(query)
+                .iter()
+                .map(|channel| channel.name.to_string())
+                .collect::<Vec<_>>()
+        })
+    };
+    assert_eq!(matching_names("ed", cx), ["zed", "Editor"]);
+    assert_eq!(matching_names("ED", cx), ["zed", "Editor"]);
+    assert_eq!(matching_names("rst", cx), ["rust"]);
+    assert_eq!(matching_names("", cx).len(), 3);
+    assert!(matching_names("xyz", cx).is_empty());
+}
+
 #[gpui::test]
 async fn test_channel_messages(cx: &mut TestAppContext) {
     let user_id = 5;
@@ -147,6 +260,7 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
             name: "the-channel".to_string(),
             visibility: proto::ChannelVisibility::Members as i32,
             parent_path: vec![],
+            ..Default::default()
         }],
         ..Default::default()
     });
@@ -292,7 +406,7 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
     });
     let get_messages = server.receive::<proto::GetChannelMessages>().await.unwrap();
     assert_eq!(get_messages.payload.channel_id, 5);
-    assert_eq!(get_messages.payload.before_message_id, 10);
+    assert_eq!(get_messages.payload.before_message_id, Some(10));
     server.respond(
         get_messages.receipt(),
         proto::GetChannelMessagesResponse {
@@ -343,6 +457,464 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_send_channel_message(cx: &mut TestAppContext) {
+    let user_id = 5;
+    let channel_id = ChannelId(5);
+    let channel_store = cx.update(init_test);
+    let client = channel_store.update(cx, |s, _| s.client());
+    let server = FakeServer::for_client(user_id, &client, cx).await;
+
+    server.send(proto::UpdateChannels {
+        channels: vec![proto::Channel {
+            id: channel_id.0,
+            name: "the-channel".to_string(),
+            visibility: proto::ChannelVisibility::Members as i32,
+            parent_path: vec![],
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+    cx.executor().run_until_parked();
+    let get_users = server.receive::<proto::GetUsers>().await.unwrap();
+    server.respond(
+        get_users.receipt(),
+        proto::UsersResponse {
+            users: vec![proto::User {
+                id: user_id,
+                github_login: "nathansobo".into(),
+                avatar_url: "http://avatar.com/nathansobo".into(),
+                name: None,
+                email: None,
+            }],
+        },
+    );
+
+    let chat = channel_store.update(cx, |store, cx| store.open_channel_chat(channel_id, cx));
+    let join_channel = server.receive::<proto::JoinChannelChat>().await.unwrap();
+    server.respond(
+        join_channel.receipt(),
+        proto::JoinChannelChatResponse {
+            messages: vec![],
+            done: true,
+        },
+    );
+    let chat = chat.await.unwrap();
+    let messages = |cx: &mut TestAppContext| {
+        chat.read_with(cx, |chat, _| {
+            chat.messages()
+                .iter()
+                .map(|message| (message.id, message.body.clone(), chat.is_failed(message.id)))
+                .collect::<Vec<_>>()
+        })
+    };
+    let saved_message = |id: u64, body: &str, nonce: Option<proto::Nonce>| proto::ChannelMessage {
+        id,
+        body: body.into(),
+        timestamp: 1000,
+        sender_id: user_id,
+        mentions: vec![],
+        nonce,
+        reply_to_message_id: None,
+        edited_at: None,
+    };
+
+    // The message is shown as pending until the server has saved it.
+    let send_task = chat.update(cx, |chat, cx| chat.send_message("a".into(), cx).unwrap());
+    assert_eq!(
+        messages(cx),
+        [(ChannelMessageId::Pending(0), "a".into(), false)]
+    );
+    let send_message = server.receive::<proto::SendChannelMessage>().await.unwrap();
+    server.respond(
+        send_message.receipt(),
+        proto::SendChannelMessageResponse {
+            message: Some(saved_message(20, "a", send_message.payload.nonce.clone())),
+        },
+    );
+    assert_eq!(send_task.await.unwrap(), 20);
+    assert_eq!(
+        messages(cx),
+        [(ChannelMessageId::Saved(20), "a".into(), false)]
+    );
+
+    // A message that couldn't be sent stays pending and can be sent again.
+    let send_task = chat.update(cx, |chat, cx| chat.send_message("b".into(), cx).unwrap());
+    let send_message = server.receive::<proto::SendChannelMessage>().await.unwrap();
+    server.respond_with_error(
+        send_message.receipt(),
+        proto::Error {
+            message: "message is too long".into(),
+            ..Default::default()
+        },
+    );
+    assert!(send_task.await.is_err());
+    assert_eq!(
+        messages(cx),
+        [
+            (ChannelMessageId::Saved(20), "a".into(), false),
+            (ChannelMessageId::Pending(1), "b".into(), true)
+        ]
+    );
+
+    let retry_task = chat.update(cx, |chat, cx| {
+        chat.retry_message(ChannelMessageId::Pending(1), cx)
+            .unwrap()
+    });
+    assert_eq!(
+        messages(cx),
+        [
+            (ChannelMessageId::Saved(20), "a".into(), false),
+            (ChannelMessageId::Pending(1), "b".into(), false)
+        ]
+    );
+    let retried_message = server.receive::<proto::SendChannelMessage>().await.unwrap();
+    assert_eq!(retried_message.payload.body, "b");
+    assert_eq!(retried_message.payload.nonce, send_message.payload.nonce);
+    server.respond(
+        retried_message.receipt(),
+        proto::SendChannelMessageResponse {
+            message: Some(saved_message(
+                21,
+                "b",
+                retried_message.payload.nonce.clone(),
+            )),
+        },
+    );
+    assert_eq!(retry_task.await.unwrap(), 21);
+    assert_eq!(
+        messages(cx),
+        [
+            (ChannelMessageId::Saved(20), "a".into(), false),
+            (ChannelMessageId::Saved(21), "b".into(), false)
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_channel_cache(cx: &mut TestAppContext) {
+    cx.executor().allow_parking();
+    let channel_store = cx.update(init_test);
+    let client = channel_store.read_with(cx, |store, _| store.client());
+    let server = FakeServer::for_client(5, &client, cx).await;
+    channel_store.update(cx, |store, cx| store.enable_channel_cache(cx));
+    server.send(proto::UpdateChannels {
+        channels: vec![
+            proto::Channel {
+                id: 1,
+                name: "a".to_string(),
+                visibility: proto::ChannelVisibility::Members as i32,
+                ..Default::default()
+            },
+            proto::Channel {
+                id: 2,
+                name: "b".to_string(),
+                visibility: proto::ChannelVisibility::Members as i32,
+                parent_path: vec![1],
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    });
+    cx.executor().run_until_parked();
+
+    // Another session of the same user shows the cached channels before the
+    // server has sent anything.
+    let restored_store = cx.update(new_session);
+    let restored_client = restored_store.read_with(cx, |store, _| store.client());
+    let _restored_server = FakeServer::for_client(5, &restored_client, cx).await;
+    restored_store.update(cx, |store, cx| store.enable_channel_cache(cx));
+    cx.executor().run_until_parked();
+    cx.update(|cx| {
+        assert_channels(
+            &restored_store,
+            &[(0, "a".to_string()), (1, "b".to_string())],
+            cx,
+        )
+    });
+
+    // Other users don't see them.
+    let other_store = cx.update(new_session);
+    let other_client = other_store.read_with(cx, |store, _| store.client());
+    let _other_server = FakeServer::for_client(6, &other_client, cx).await;
+    other_store.update(cx, |store, cx| store.enable_channel_cache(cx));
+    cx.executor().run_until_parked();
+    cx.update(|cx| assert_channels(&other_store, &[], cx));
+
+    // Channels that the server no longer reports are pruned once it syncs.
+    cx.update(|cx| {
+        update_channels(
+            &restored_store,
+            proto::UpdateChannels {
+                channels: vec![proto::Channel {
+                    id: 1,
+                    name: "a".to_string(),
+                    visibility: proto::ChannelVisibility::Members as i32,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            cx,
+        );
+        assert_channels(&restored_store, &[(0, "a".to_string())], cx);
+    });
+}
+
+#[gpui::test]
+async fn test_channel_notification_level(cx: &mut TestAppContext) {
+    let user_id = 5;
+    let channel_id = ChannelId(1);
+    let channel_store = cx.update(init_test);
+    let client = channel_store.update(cx, |s, _| s.client());
+    let server = FakeServer::for_client(user_id, &client, cx).await;
+
+    server.send(proto::UpdateChannels {
+        channels: vec![proto::Channel {
+            id: channel_id.0,
+            name: "the-channel".to_string(),
+            visibility: proto::ChannelVisibility::Members as i32,
+            parent_path: vec![],
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+    cx.executor().run_until_parked();
+    channel_store.read_with(cx, |store, _| {
+        assert_eq!(
+            store.notification_level(channel_id),
+            proto::ChannelNotificationLevel::All
+        );
+    });
+
+    // Muting the channel is applied locally and sent to the server.
+    let task = channel_store.update(cx, |store, cx| {
+        store.set_channel_notifications(channel_id, proto::ChannelNotificationLevel::None, cx)
+    });
+    let request = server
+        .receive::<proto::SetChannelNotificationLevel>()
+        .await
+        .unwrap();
+    assert_eq!(request.payload.channel_id, channel_id.0);
+    assert_eq!(
+        request.payload.level(),
+        proto::ChannelNotificationLevel::None
+    );
+    server.respond(request.receipt(), proto::Ack {});
+    task.await.unwrap();
+
+    // New messages in a muted channel count as unread, but aren't surfaced.
+    server.send(proto::UpdateChannels {
+        latest_channel_message_ids: vec![proto::ChannelMessageId {
+            channel_id: channel_id.0,
+            message_id: 10,
+        }],
+        ..Default::default()
+    });
+    cx.executor().run_until_parked();
+    channel_store.read_with(cx, |store, _| {
+        assert_eq!(
+            store.notification_level(channel_id),
+            proto::ChannelNotificationLevel::None
+        );
+        assert!(store.has_new_messages(channel_id));
+        assert!(!store.should_notify(channel_id));
+    });
+
+    // Changes made on another device are synced from the server.
+    server.send(proto::UpdateUserChannels {
+        channel_notification_levels: vec![proto::ChannelNotificationSetting {
+            channel_id: channel_id.0,
+            level: proto::ChannelNotificationLevel::All.into(),
+        }],
+        ..Default::default()
+    });
+    cx.executor().run_until_parked();
+    channel_store.read_with(cx, |store, _| {
+        assert_eq!(
+            store.notification_level(channel_id),
+            proto::ChannelNotificationLevel::All
+        );
+        assert!(store.should_notify(channel_id));
+    });
+}
+
+#[gpui::test]
+async fn test_toggle_favorite(cx: &mut TestAppContext) {
+    let user_id = 5;
+    let channel_id = ChannelId(1);
+    let channel_store = cx.update(init_test);
+    let client = channel_store.update(cx, |s, _| s.client());
+    let server = FakeServer::for_client(user_id, &client, cx).await;
+
+    server.send(proto::UpdateChannels {
+        channels: vec![proto::Channel {
+            id: channel_id.0,
+            name: "the-channel".to_string(),
+            visibility: proto::ChannelVisibility::Members as i32,
+            parent_path: vec![],
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+    cx.executor().run_until_parked();
+
+    // Favoriting is applied right away, before the server responds.
+    let task = channel_store.update(cx, |store, cx| store.toggle_favorite(channel_id, cx));
+    channel_store.read_with(cx, |store, _| assert!(store.is_favorite(channel_id)));
+    let request = server.receive::<proto::SetChannelFavorite>().await.unwrap();
+    assert_eq!(request.payload.channel_id, channel_id.0);
+    assert!(request.payload.favorite);
+    server.respond(request.receipt(), proto::Ack {});
+    task.await.unwrap();
+    channel_store.read_with(cx, |store, _| assert!(store.is_favorite(channel_id)));
+
+    // If the server rejects the change, it's rolled back.
+    let task = channel_store.update(cx, |store, cx| store.toggle_favorite(channel_id, cx));
+    channel_store.read_with(cx, |store, _| assert!(!store.is_favorite(channel_id)));
+    let request = server.receive::<proto::SetChannelFavorite>().await.unwrap();
+    assert!(!request.payload.favorite);
+    server.respond_with_error(
+        request.receipt(),
+        proto::Error {
+            message: "could not update favorites".into(),
+            ..Default::default()
+        },
+    );
+    assert!(task.await.is_err());
+    channel_store.read_with(cx, |store, _| assert!(store.is_favorite(channel_id)));
+}
+
+#[gpui::test]
+async fn test_participant_connection_quality(cx: &mut TestAppContext) {
+    let user_id = 5;
+    let participant_id = 6;
+    let channel_id = ChannelId(1);
+    let channel_store = cx.update(init_test);
+    let client = channel_store.update(cx, |s, _| s.client());
+    let server = FakeServer::for_client(user_id, &client, cx).await;
+
+    server.send(proto::UpdateChannels {
+        channels: vec![proto::Channel {
+            id: channel_id.0,
+            name: "the-channel".to_string(),
+            visibility: proto::ChannelVisibility::Members as i32,
+            parent_path: vec![],
+            ..Default::default()
+        }],
+        channel_participants: vec![proto::ChannelParticipants {
+            channel_id: channel_id.0,
+            participant_user_ids: vec![participant_id],
+        }],
+        ..Default::default()
+    });
+    cx.executor().run_until_parked();
+    while channel_store.read_with(cx, |store, _| {
+        store.channel_participants(channel_id).is_empty()
+    }) {
+        let get_users = server.receive::<proto::GetUsers>().await.unwrap();
+        let users = get_users
+            .payload
+            .user_ids
+            .iter()
+            .map(|id| proto::User {
+                id: *id,
+                github_login: format!("user-{id}"),
+                avatar_url: String::new(),
+                name: None,
+                email: None,
+            })
+            .collect();
+        server.respond(get_users.receipt(), proto::UsersResponse { users });
+        cx.executor().run_until_parked();
+    }
+
+    let stats = Rc::new(Cell::new(ConnectionStats {
+        round_trip_time: Duration::from_millis(20),
+        packet_loss: 0.,
+    }));
+    let events = Rc::new(RefCell::new(Vec::new()));
+    cx.update(|cx| {
+        let events = events.clone();
+        cx.subscribe(&channel_store, move |_, event: &ChannelEvent, _| {
+            if let ChannelEvent::ParticipantConnectionQualityChanged {
+                channel_id,
+                user_id,
+                quality,
+            } = event
+            {
+                events.borrow_mut().push((*channel_id, *user_id, *quality));
+            }
+        })
+        .detach();
+    });
+    channel_store.update(cx, |store, cx| {
+        let stats = stats.clone();
+        store.set_connection_stats_source(
+            Some(Rc::new(move |_: ChannelId, _: u64, _: &App| {
+                Some(stats.get())
+            })),
+            cx,
+        );
+    });
+    channel_store.read_with(cx, |store, _| {
+        assert_eq!(
+            store.participant_connection_state(channel_id, participant_id),
+            ConnectionQuality::Unknown
+        );
+    });
+
+    cx.executor().advance_clock(CONNECTION_STATS_INTERVAL);
+    cx.executor().run_until_parked();
+    channel_store.read_with(cx, |store, _| {
+        assert_eq!(
+            store.participant_connection_state(channel_id, participant_id),
+            ConnectionQuality::Excellent
+        );
+    });
+    assert_eq!(
+        events.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [(channel_id, participant_id, ConnectionQuality::Excellent)]
+    );
+
+    // Stats that stay within the same quality don't emit events.
+    stats.set(ConnectionStats {
+        round_trip_time: Duration::from_millis(50),
+        packet_loss: 0.01,
+    });
+    cx.executor().advance_clock(CONNECTION_STATS_INTERVAL);
+    cx.executor().run_until_parked();
+    assert_eq!(events.borrow().len(), 0);
+
+    stats.set(ConnectionStats {
+        round_trip_time: Duration::from_millis(50),
+        packet_loss: 0.2,
+    });
+    cx.executor().advance_clock(CONNECTION_STATS_INTERVAL);
+    cx.executor().run_until_parked();
+    channel_store.read_with(cx, |store, _| {
+        assert_eq!(
+            store.participant_connection_state(channel_id, participant_id),
+            ConnectionQuality::Poor
+        );
+    });
+    assert_eq!(
+        events.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [(channel_id, participant_id, ConnectionQuality::Poor)]
+    );
+
+    // Sampling stops once the source is removed.
+    channel_store.update(cx, |store, cx| store.set_connection_stats_source(None, cx));
+    cx.executor().advance_clock(CONNECTION_STATS_INTERVAL);
+    cx.executor().run_until_parked();
+    channel_store.read_with(cx, |store, _| {
+        assert_eq!(
+            store.participant_connection_state(channel_id, participant_id),
+            ConnectionQuality::Unknown
+        );
+    });
+    assert_eq!(events.borrow().len(), 0);
+}
+
 fn init_test(cx: &mut App) -> Entity<ChannelStore> {
     let settings_store = SettingsStore::test(cx);
     cx.set_global(settings_store);
@@ -360,6 +932,15 @@ fn init_test(cx: &mut App) -> Entity<ChannelStore> {
     ChannelStore::global(cx)
 }
 
+/// A channel store with its own client, as if in another session of the app.
+fn new_session(cx: &mut App) -> Entity<ChannelStore> {
+    let clock = Arc::new(FakeSystemClock::new());
+    let http = FakeHttpClient::with_404_response();
+    let client = Client::new(clock, http, cx);
+    let user_store = cx.new(|cx| UserStore::new(client.clone(), cx));
+    cx.new(|cx| ChannelStore::new(client, user_store, cx))
+}
+
 fn update_channels(
     channel_store: &Entity<ChannelStore>,
     message: proto::UpdateChannels,
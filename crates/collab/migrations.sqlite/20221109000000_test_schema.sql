@@ -265,7 +265,10 @@ CREATE TABLE "channels" (
     "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
     "visibility" VARCHAR NOT NULL,
     "parent_path" TEXT NOT NULL,
-    "requires_zed_cla" BOOLEAN NOT NULL DEFAULT FALSE
+    "requires_zed_cla" BOOLEAN NOT NULL DEFAULT FALSE,
+    "description" TEXT,
+    "archived" BOOLEAN NOT NULL DEFAULT FALSE,
+    "max_participants" INTEGER
 );
 
 CREATE INDEX "index_channels_on_parent_path" ON "channels" ("parent_path");
@@ -406,6 +409,28 @@ CREATE TABLE IF NOT EXISTS "observed_channel_messages" (
 
 CREATE UNIQUE INDEX "index_observed_channel_messages_user_and_channel_id" ON "observed_channel_messages" ("user_id", "channel_id");
 
+CREATE TABLE IF NOT EXISTS "channel_notification_levels" (
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    "level" VARCHAR NOT NULL,
+    PRIMARY KEY (user_id, channel_id)
+);
+
+CREATE TABLE IF NOT EXISTS "channel_favorites" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX "index_channel_favorites_on_user_id_and_channel_id" ON "channel_favorites" ("user_id", "channel_id");
+
+CREATE TABLE IF NOT EXISTS "channel_creation_keys" (
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "key" VARCHAR NOT NULL,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    PRIMARY KEY (user_id, key)
+);
+
 CREATE TABLE "notification_kinds" (
     "id" INTEGER PRIMARY KEY AUTOINCREMENT,
     "name" VARCHAR NOT NULL
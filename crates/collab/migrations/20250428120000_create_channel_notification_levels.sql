@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS "channel_notification_levels" (
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    "level" VARCHAR NOT NULL,
+    PRIMARY KEY (user_id, channel_id)
+);
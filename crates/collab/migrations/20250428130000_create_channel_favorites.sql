@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS "channel_favorites" (
+    "id" SERIAL PRIMARY KEY,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE
+);
+
+CREATE UNIQUE INDEX "index_channel_favorites_on_user_id_and_channel_id" ON "channel_favorites" ("user_id", "channel_id");
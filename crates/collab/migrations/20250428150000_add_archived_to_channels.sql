@@ -0,0 +1 @@
+ALTER TABLE channels ADD COLUMN archived BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS "channel_creation_keys" (
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "key" VARCHAR NOT NULL,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    PRIMARY KEY (user_id, key)
+);
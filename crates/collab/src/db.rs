@@ -475,12 +475,16 @@ pub type NotificationBatch = Vec<(UserId, proto::Notification)>;
 
 pub struct CreatedChannelMessage {
     pub message_id: MessageId,
+    /// The mentions given with the message, along with those found in its body.
+    pub mentions: Vec<proto::ChatMention>,
     pub participant_connection_ids: HashSet<ConnectionId>,
     pub notifications: NotificationBatch,
 }
 
 pub struct UpdatedChannelMessage {
     pub message_id: MessageId,
+    /// The mentions given with the edited message, along with those found in its body.
+    pub mentions: Vec<proto::ChatMention>,
     pub participant_connection_ids: Vec<ConnectionId>,
     pub notifications: NotificationBatch,
     pub reply_to_message_id: Option<MessageId>,
@@ -568,13 +572,37 @@ pub struct RemoveChannelMemberResult {
     pub notification_id: Option<NotificationId>,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+/// The result of moving a channel.
+#[derive(Debug)]
+pub struct MoveChannelResult {
+    /// The root of the channel tree the channel was moved within or out of.
+    pub previous_root_id: ChannelId,
+    /// The moved channel and its descendants.
+    pub channels: Vec<Channel>,
+    /// The memberships that were copied over when the channel became a root channel.
+    pub new_memberships: Vec<channel_member::Model>,
+}
+
+/// The maximum number of levels channels can be nested, including the root channel.
+pub const MAX_CHANNEL_DEPTH: usize = 8;
+
+/// The maximum number of characters in a channel's name.
+pub const MAX_CHANNEL_NAME_LENGTH: usize = 64;
+
+/// The maximum number of characters in a channel's description.
+pub const MAX_CHANNEL_DESCRIPTION_LENGTH: usize = 256;
+
+#[derive(Debug, Default, PartialEq, Eq, Hash)]
 pub struct Channel {
     pub id: ChannelId,
     pub name: String,
     pub visibility: ChannelVisibility,
     /// parent_path is the channel ids from the root to this one (not including this one)
     pub parent_path: Vec<ChannelId>,
+    pub description: Option<String>,
+    pub archived: bool,
+    /// The maximum number of users that can be in the channel's call at once.
+    pub max_participants: Option<u32>,
 }
 
 impl Channel {
@@ -584,6 +612,11 @@ impl Channel {
             visibility: value.visibility,
             name: value.clone().name,
             parent_path: value.ancestors().collect(),
+            description: value.description,
+            archived: value.archived,
+            max_participants: value
+                .max_participants
+                .and_then(|max| u32::try_from(max).ok()),
         }
     }
 
@@ -593,6 +626,9 @@ impl Channel {
             name: self.name.clone(),
             visibility: self.visibility.into(),
             parent_path: self.parent_path.iter().map(|c| c.to_proto()).collect(),
+            description: self.description.clone(),
+            archived: self.archived,
+            max_participants: self.max_participants,
         }
     }
 }
@@ -625,6 +661,8 @@ pub struct ChannelsForUser {
     pub observed_channel_messages: Vec<proto::ChannelMessageId>,
     pub latest_buffer_versions: Vec<proto::ChannelBufferVersion>,
     pub latest_channel_messages: Vec<proto::ChannelMessageId>,
+    pub channel_notification_levels: Vec<proto::ChannelNotificationSetting>,
+    pub favorite_channel_ids: Vec<ChannelId>,
 }
 
 #[derive(Debug)]
@@ -76,6 +76,7 @@ id_type!(BillingPreferencesId);
 id_type!(BufferId);
 id_type!(ChannelBufferCollaboratorId);
 id_type!(ChannelChatParticipantId);
+id_type!(ChannelFavoriteId);
 id_type!(ChannelId);
 id_type!(ChannelMemberId);
 id_type!(ContactId);
@@ -275,6 +276,49 @@ impl From<ChannelVisibility> for i32 {
     }
 }
 
+/// ChannelNotificationLevel controls which activity in a channel is surfaced to a user.
+#[derive(Eq, PartialEq, Copy, Clone, Debug, EnumIter, DeriveActiveEnum, Default, Hash)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::None)")]
+pub enum ChannelNotificationLevel {
+    /// Every new message is surfaced.
+    #[sea_orm(string_value = "all")]
+    #[default]
+    All,
+    /// Only messages that mention the user are surfaced.
+    #[sea_orm(string_value = "mentions")]
+    Mentions,
+    /// Nothing is surfaced, though messages still count as unread.
+    #[sea_orm(string_value = "none")]
+    None,
+}
+
+impl From<proto::ChannelNotificationLevel> for ChannelNotificationLevel {
+    fn from(value: proto::ChannelNotificationLevel) -> Self {
+        match value {
+            proto::ChannelNotificationLevel::All => ChannelNotificationLevel::All,
+            proto::ChannelNotificationLevel::Mentions => ChannelNotificationLevel::Mentions,
+            proto::ChannelNotificationLevel::None => ChannelNotificationLevel::None,
+        }
+    }
+}
+
+impl From<ChannelNotificationLevel> for proto::ChannelNotificationLevel {
+    fn from(val: ChannelNotificationLevel) -> Self {
+        match val {
+            ChannelNotificationLevel::All => proto::ChannelNotificationLevel::All,
+            ChannelNotificationLevel::Mentions => proto::ChannelNotificationLevel::Mentions,
+            ChannelNotificationLevel::None => proto::ChannelNotificationLevel::None,
+        }
+    }
+}
+
+impl From<ChannelNotificationLevel> for i32 {
+    fn from(val: ChannelNotificationLevel) -> Self {
+        let proto: proto::ChannelNotificationLevel = val.into();
+        proto.into()
+    }
+}
+
 /// Indicate whether a [Buffer] has permissions to edit.
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub enum Capability {
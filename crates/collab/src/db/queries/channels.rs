@@ -45,9 +45,46 @@ impl Database {
         name: &str,
         parent_channel_id: Option<ChannelId>,
         admin_id: UserId,
+    ) -> Result<(channel::Model, Option<channel_member::Model>)> {
+        self.create_channel_with_description(name, None, parent_channel_id, admin_id, None)
+            .await
+    }
+
+    /// Creates a new channel with the given description.
+    ///
+    /// If the admin already created a channel with the same `idempotency_key`,
+    /// that channel is returned instead of creating a new one.
+    pub async fn create_channel_with_description(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        parent_channel_id: Option<ChannelId>,
+        admin_id: UserId,
+        idempotency_key: Option<&str>,
     ) -> Result<(channel::Model, Option<channel_member::Model>)> {
         let name = Self::sanitize_channel_name(name)?;
+        let description = Self::sanitize_channel_description(description)?;
         self.transaction(move |tx| async move {
+            if let Some(key) = idempotency_key {
+                let existing_channel_id = channel_creation_key::Entity::find()
+                    .filter(
+                        channel_creation_key::Column::UserId
+                            .eq(admin_id)
+                            .and(channel_creation_key::Column::Key.eq(key)),
+                    )
+                    .one(&*tx)
+                    .await?
+                    .map(|row| row.channel_id);
+                if let Some(channel_id) = existing_channel_id {
+                    // The user must still be allowed to create the channel where it is,
+                    // just as when it was first created.
+                    let channel = self.get_channel_internal(channel_id, &tx).await?;
+                    self.check_user_is_channel_admin(&channel, admin_id, &tx)
+                        .await?;
+                    return Ok((channel, None));
+                }
+            }
+
             let mut parent = None;
             let mut membership = None;
 
@@ -55,6 +92,11 @@ impl Database {
                 let parent_channel = self.get_channel_internal(parent_channel_id, &tx).await?;
                 self.check_user_is_channel_admin(&parent_channel, admin_id, &tx)
                     .await?;
+                if parent_channel.ancestors_including_self().count() >= MAX_CHANNEL_DEPTH {
+                    Err(ErrorCode::ChannelTooDeep
+                        .with_tag("max_depth", &MAX_CHANNEL_DEPTH.to_string())
+                        .anyhow())?;
+                }
                 parent = Some(parent_channel);
             }
 
@@ -68,6 +110,9 @@ impl Database {
                         .map_or(String::new(), |parent| parent.path()),
                 ),
                 requires_zed_cla: ActiveValue::NotSet,
+                archived: ActiveValue::NotSet,
+                max_participants: ActiveValue::NotSet,
+                description: ActiveValue::Set(description.map(str::to_string)),
             }
             .insert(&*tx)
             .await?;
@@ -86,6 +131,16 @@ impl Database {
                 );
             }
 
+            if let Some(key) = idempotency_key {
+                channel_creation_key::Entity::insert(channel_creation_key::ActiveModel {
+                    user_id: ActiveValue::Set(admin_id),
+                    key: ActiveValue::Set(key.to_string()),
+                    channel_id: ActiveValue::Set(channel.id),
+                })
+                .exec_without_returning(&*tx)
+                .await?;
+            }
+
             Ok((channel, membership))
         })
         .await
@@ -97,6 +152,29 @@ impl Database {
         channel_id: ChannelId,
         user_id: UserId,
         connection: ConnectionId,
+    ) -> Result<(JoinRoom, Option<MembershipUpdated>, ChannelRole)> {
+        self.join_channel_internal(channel_id, user_id, connection, false)
+            .await
+    }
+
+    /// Adds a user to the call of the specified channel as a guest, who can't publish to the
+    /// room, even if they're a member of the channel.
+    pub async fn join_channel_as_guest(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        connection: ConnectionId,
+    ) -> Result<(JoinRoom, Option<MembershipUpdated>, ChannelRole)> {
+        self.join_channel_internal(channel_id, user_id, connection, true)
+            .await
+    }
+
+    async fn join_channel_internal(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        connection: ConnectionId,
+        as_guest: bool,
     ) -> Result<(JoinRoom, Option<MembershipUpdated>, ChannelRole)> {
         self.transaction(move |tx| async move {
             let channel = self.get_channel_internal(channel_id, &tx).await?;
@@ -152,13 +230,39 @@ impl Database {
             if role.is_none() || role == Some(ChannelRole::Banned) {
                 Err(ErrorCode::Forbidden.anyhow())?
             }
-            let role = role.unwrap();
+            let role = if as_guest {
+                ChannelRole::Guest
+            } else {
+                role.unwrap()
+            };
 
             let livekit_room = format!("channel-{}", nanoid::nanoid!(30));
             let room_id = self
                 .get_or_create_channel_room(channel_id, &livekit_room, &tx)
                 .await?;
 
+            if let Some(max_participants) = channel.max_participants {
+                let participant_count = room_participant::Entity::find()
+                    .filter(
+                        room_participant::Column::RoomId
+                            .eq(room_id)
+                            .and(room_participant::Column::UserId.ne(user_id))
+                            .and(room_participant::Column::AnsweringConnectionId.is_not_null()),
+                    )
+                    .count(&*tx)
+                    .await?;
+                let limit = u64::try_from(max_participants).map_err(|_| {
+                    anyhow!(
+                        "channel {channel_id} has an invalid participant limit {max_participants}"
+                    )
+                })?;
+                if participant_count >= limit {
+                    Err(ErrorCode::ChannelFull
+                        .with_tag("max_participants", &max_participants.to_string())
+                        .anyhow())?;
+                }
+            }
+
             self.join_channel_room_internal(room_id, user_id, connection, role, &tx)
                 .await
                 .map(|jr| (jr, accept_invite_result, role))
@@ -310,9 +414,102 @@ impl Database {
         if new_name.is_empty() {
             Err(anyhow!("channel name can't be blank"))?;
         }
+        if new_name.chars().count() > MAX_CHANNEL_NAME_LENGTH {
+            Err(anyhow!(
+                "channel name can't be longer than {MAX_CHANNEL_NAME_LENGTH} characters"
+            ))?;
+        }
         Ok(new_name)
     }
 
+    fn sanitize_channel_description(description: Option<&str>) -> Result<Option<&str>> {
+        let Some(description) = description.map(str::trim).filter(|d| !d.is_empty()) else {
+            return Ok(None);
+        };
+        if description.contains('\n') {
+            Err(anyhow!("channel description must be a single line"))?;
+        }
+        if description.chars().count() > MAX_CHANNEL_DESCRIPTION_LENGTH {
+            Err(anyhow!(
+                "channel description can't be longer than {MAX_CHANNEL_DESCRIPTION_LENGTH} characters"
+            ))?;
+        }
+        Ok(Some(description))
+    }
+
+    /// Sets or clears the description of the specified channel.
+    pub async fn set_channel_description(
+        &self,
+        channel_id: ChannelId,
+        admin_id: UserId,
+        description: Option<&str>,
+    ) -> Result<channel::Model> {
+        self.transaction(move |tx| async move {
+            let description = Self::sanitize_channel_description(description)?.map(str::to_string);
+
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_admin(&channel, admin_id, &tx)
+                .await?;
+
+            let mut model = channel.into_active_model();
+            model.description = ActiveValue::Set(description);
+            let channel = model.update(&*tx).await?;
+
+            Ok(channel)
+        })
+        .await
+    }
+
+    /// Archives or unarchives the specified channel.
+    pub async fn set_channel_archived(
+        &self,
+        channel_id: ChannelId,
+        admin_id: UserId,
+        archived: bool,
+    ) -> Result<channel::Model> {
+        self.transaction(move |tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_admin(&channel, admin_id, &tx)
+                .await?;
+
+            let mut model = channel.into_active_model();
+            model.archived = ActiveValue::Set(archived);
+            let channel = model.update(&*tx).await?;
+
+            Ok(channel)
+        })
+        .await
+    }
+
+    /// Limits the number of users that can be in the call of the specified channel at once, or
+    /// removes the limit when `None`.
+    pub async fn set_channel_max_participants(
+        &self,
+        channel_id: ChannelId,
+        admin_id: UserId,
+        max_participants: Option<u32>,
+    ) -> Result<channel::Model> {
+        if max_participants == Some(0) {
+            Err(anyhow!("channels must allow at least one participant"))?;
+        }
+        let max_participants = max_participants
+            .map(i32::try_from)
+            .transpose()
+            .map_err(|_| anyhow!("channels can allow at most {} participants", i32::MAX))?;
+        self.transaction(move |tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_admin(&channel, admin_id, &tx)
+                .await?;
+
+            let mut model = channel.into_active_model();
+            model.max_participants = ActiveValue::Set(max_participants);
+            let channel = model.update(&*tx).await?;
+
+            Ok(channel)
+        })
+        .await
+    }
+
     /// Renames the specified channel.
     pub async fn rename_channel(
         &self,
@@ -615,6 +812,14 @@ impl Database {
             .observed_channel_messages(&channel_ids, user_id, tx)
             .await?;
 
+        let channel_notification_levels = self
+            .channel_notification_levels(&channel_ids, user_id, tx)
+            .await?;
+
+        let favorite_channel_ids = self
+            .favorite_channel_ids(user_id, Some(&channel_ids), tx)
+            .await?;
+
         Ok(ChannelsForUser {
             channel_memberships,
             channels,
@@ -624,9 +829,135 @@ impl Database {
             latest_channel_messages,
             observed_buffer_versions,
             observed_channel_messages,
+            channel_notification_levels,
+            favorite_channel_ids,
         })
     }
 
+    /// Returns the user's favorite channels in the order they were favorited,
+    /// optionally restricted to the given channels.
+    async fn favorite_channel_ids(
+        &self,
+        user_id: UserId,
+        channel_ids: Option<&[ChannelId]>,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<ChannelId>> {
+        let mut query =
+            channel_favorite::Entity::find().filter(channel_favorite::Column::UserId.eq(user_id));
+        if let Some(channel_ids) = channel_ids {
+            query = query.filter(
+                channel_favorite::Column::ChannelId.is_in(channel_ids.iter().map(|id| id.0)),
+            );
+        }
+
+        Ok(query
+            .order_by_asc(channel_favorite::Column::Id)
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|favorite| favorite.channel_id)
+            .collect())
+    }
+
+    /// Adds the channel to or removes it from the user's favorites, returning
+    /// the updated favorites in the order they were favorited.
+    pub async fn set_channel_favorite(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        favorite: bool,
+    ) -> Result<Vec<ChannelId>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            if favorite {
+                channel_favorite::Entity::insert(channel_favorite::ActiveModel {
+                    user_id: ActiveValue::Set(user_id),
+                    channel_id: ActiveValue::Set(channel_id),
+                    ..Default::default()
+                })
+                .on_conflict(
+                    OnConflict::columns([
+                        channel_favorite::Column::UserId,
+                        channel_favorite::Column::ChannelId,
+                    ])
+                    .do_nothing()
+                    .to_owned(),
+                )
+                .exec_without_returning(&*tx)
+                .await?;
+            } else {
+                channel_favorite::Entity::delete_many()
+                    .filter(channel_favorite::Column::UserId.eq(user_id))
+                    .filter(channel_favorite::Column::ChannelId.eq(channel_id))
+                    .exec(&*tx)
+                    .await?;
+            }
+
+            self.favorite_channel_ids(user_id, None, &tx).await
+        })
+        .await
+    }
+
+    async fn channel_notification_levels(
+        &self,
+        channel_ids: &[ChannelId],
+        user_id: UserId,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::ChannelNotificationSetting>> {
+        let rows = channel_notification_level::Entity::find()
+            .filter(channel_notification_level::Column::UserId.eq(user_id))
+            .filter(
+                channel_notification_level::Column::ChannelId
+                    .is_in(channel_ids.iter().map(|id| id.0)),
+            )
+            .all(tx)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| proto::ChannelNotificationSetting {
+                channel_id: row.channel_id.to_proto(),
+                level: row.level.into(),
+            })
+            .collect())
+    }
+
+    /// Sets which activity in the given channel is surfaced to the user.
+    pub async fn set_channel_notification_level(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        level: ChannelNotificationLevel,
+    ) -> Result<()> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &tx)
+                .await?;
+
+            channel_notification_level::Entity::insert(channel_notification_level::ActiveModel {
+                user_id: ActiveValue::Set(user_id),
+                channel_id: ActiveValue::Set(channel_id),
+                level: ActiveValue::Set(level),
+            })
+            .on_conflict(
+                OnConflict::columns([
+                    channel_notification_level::Column::UserId,
+                    channel_notification_level::Column::ChannelId,
+                ])
+                .update_column(channel_notification_level::Column::Level)
+                .to_owned(),
+            )
+            .exec_without_returning(&*tx)
+            .await?;
+
+            Ok(())
+        })
+        .await
+    }
+
     /// Sets the role for the specified channel member.
     pub async fn set_channel_member_role(
         &self,
@@ -671,6 +1002,106 @@ impl Database {
         .await
     }
 
+    /// Makes the given member an admin of the channel, and if `demote_admin` is set, makes the
+    /// admin transferring the role a regular member.
+    ///
+    /// Returns the membership updates for the users whose role changed.
+    pub async fn transfer_channel_admin(
+        &self,
+        channel_id: ChannelId,
+        admin_id: UserId,
+        to_user: UserId,
+        demote_admin: bool,
+    ) -> Result<Vec<(UserId, MembershipUpdated)>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            self.check_user_is_channel_admin(&channel, admin_id, &tx)
+                .await?;
+
+            let membership = channel_member::Entity::find()
+                .filter(
+                    channel_member::Column::ChannelId
+                        .eq(channel_id)
+                        .and(channel_member::Column::UserId.eq(to_user))
+                        .and(channel_member::Column::Accepted.eq(true)),
+                )
+                .one(&*tx)
+                .await?;
+            let Some(membership) = membership else {
+                Err(anyhow!("no such member"))?
+            };
+
+            let mut roles = vec![(membership, ChannelRole::Admin)];
+            if demote_admin && admin_id != to_user {
+                let admin_membership = channel_member::Entity::find()
+                    .filter(
+                        channel_member::Column::ChannelId
+                            .eq(channel_id)
+                            .and(channel_member::Column::UserId.eq(admin_id)),
+                    )
+                    .one(&*tx)
+                    .await?;
+                if let Some(admin_membership) = admin_membership {
+                    roles.push((admin_membership, ChannelRole::Member));
+                }
+            }
+
+            let mut updates = Vec::new();
+            for (membership, role) in roles {
+                let user_id = membership.user_id;
+                let mut update = membership.into_active_model();
+                update.role = ActiveValue::Set(role);
+                channel_member::Entity::update(update).exec(&*tx).await?;
+                updates.push((
+                    user_id,
+                    self.calculate_membership_updated(&channel, user_id, &tx)
+                        .await?,
+                ));
+            }
+            Ok(updates)
+        })
+        .await
+    }
+
+    fn channel_member_kind(member: &channel_member::Model) -> Kind {
+        if !member.accepted {
+            Kind::Invitee
+        } else if member.role == ChannelRole::Guest {
+            Kind::Guest
+        } else {
+            Kind::Member
+        }
+    }
+
+    /// Converts channel members to protos, collecting each of their users in `users` once.
+    fn channel_members_to_proto(
+        members: Vec<(channel_member::Model, Option<user::Model>)>,
+        users: &mut BTreeMap<UserId, proto::User>,
+    ) -> Vec<proto::ChannelMember> {
+        members
+            .into_iter()
+            .map(|(member, user)| {
+                if let Some(user) = user {
+                    users.entry(user.id).or_insert_with(|| proto::User {
+                        id: user.id.to_proto(),
+                        avatar_url: format!(
+                            "https://github.com/{}.png?size=128",
+                            user.github_login
+                        ),
+                        github_login: user.github_login,
+                        name: user.name,
+                        email: user.email_address,
+                    });
+                }
+                proto::ChannelMember {
+                    role: member.role.into(),
+                    user_id: member.user_id.to_proto(),
+                    kind: Self::channel_member_kind(&member).into(),
+                }
+            })
+            .collect()
+    }
+
     /// Returns the details for the specified channel member.
     pub async fn get_channel_participant_details(
         &self,
@@ -682,12 +1113,18 @@ impl Database {
         let members = self
             .transaction(move |tx| async move {
                 let channel = self.get_channel_internal(channel_id, &tx).await?;
-                self.check_user_is_channel_participant(&channel, user_id, &tx)
+                let role = self
+                    .check_user_is_channel_participant(&channel, user_id, &tx)
                     .await?;
                 let mut query = channel_member::Entity::find()
                     .find_also_related(user::Entity)
                     .filter(channel_member::Column::ChannelId.eq(channel.root_id()));
 
+                // Only admins get to see who has been invited but hasn't accepted yet.
+                if role != ChannelRole::Admin {
+                    query = query.filter(channel_member::Column::Accepted.eq(true));
+                }
+
                 if cfg!(any(test, feature = "sqlite")) && self.pool.get_database_backend() == DbBackend::Sqlite {
                     query = query.filter(Expr::cust_with_values(
                         "UPPER(github_login) LIKE ?",
@@ -713,37 +1150,83 @@ impl Database {
             })
             .await?;
 
-        let mut users: Vec<proto::User> = Vec::with_capacity(members.len());
+        let mut users = BTreeMap::default();
+        let members = Self::channel_members_to_proto(members, &mut users);
+        Ok((members, users.into_values().collect()))
+    }
 
-        let members = members
-            .into_iter()
-            .map(|(member, user)| {
-                if let Some(user) = user {
-                    users.push(proto::User {
-                        id: user.id.to_proto(),
-                        avatar_url: format!(
-                            "https://github.com/{}.png?size=128",
-                            user.github_login
+    /// Returns the members of each of the given channels, along with the users they refer to.
+    ///
+    /// Channels that don't exist, or that the given user can't administer, are omitted.
+    pub async fn get_members_for_channels(
+        &self,
+        channel_ids: &[ChannelId],
+        user_id: UserId,
+    ) -> Result<(Vec<proto::ChannelMembers>, Vec<proto::User>)> {
+        self.transaction(move |tx| async move {
+            let channels = channel::Entity::find()
+                .filter(channel::Column::Id.is_in(channel_ids.iter().copied()))
+                .all(&*tx)
+                .await?;
+            let root_ids = channels
+                .iter()
+                .map(|channel| channel.root_id())
+                .collect::<HashSet<_>>();
+
+            // Memberships only exist on root channels, so admins of a root
+            // administer all of its descendants.
+            let admin_root_ids = channel_member::Entity::find()
+                .filter(channel_member::Column::UserId.eq(user_id))
+                .filter(channel_member::Column::ChannelId.is_in(root_ids))
+                .filter(channel_member::Column::Accepted.eq(true))
+                .filter(channel_member::Column::Role.eq(ChannelRole::Admin))
+                .all(&*tx)
+                .await?
+                .into_iter()
+                .map(|membership| membership.channel_id)
+                .collect::<HashSet<_>>();
+
+            let mut members_by_root_id = HashMap::<ChannelId, Vec<_>>::default();
+            if !admin_root_ids.is_empty() {
+                let members = channel_member::Entity::find()
+                    .find_also_related(user::Entity)
+                    .filter(channel_member::Column::ChannelId.is_in(admin_root_ids.iter().copied()))
+                    .order_by(
+                        Expr::cust(
+                            "not role = 'admin', not role = 'member', not role = 'guest', not accepted, github_login",
                         ),
-                        github_login: user.github_login,
-                        name: user.name,
-                        email: user.email_address,
-                    })
+                        sea_orm::Order::Asc,
+                    )
+                    .all(&*tx)
+                    .await?;
+                for (member, user) in members {
+                    members_by_root_id
+                        .entry(member.channel_id)
+                        .or_default()
+                        .push((member, user));
                 }
-                proto::ChannelMember {
-                    role: member.role.into(),
-                    user_id: member.user_id.to_proto(),
-                    kind: if member.accepted {
-                        Kind::Member
-                    } else {
-                        Kind::Invitee
+            }
+
+            let mut users = BTreeMap::default();
+            let channels = channel_ids
+                .iter()
+                .filter_map(|channel_id| {
+                    let channel = channels.iter().find(|channel| channel.id == *channel_id)?;
+                    let root_id = channel.root_id();
+                    if !admin_root_ids.contains(&root_id) {
+                        return None;
                     }
-                    .into(),
-                }
-            })
-            .collect();
+                    let members = members_by_root_id.get(&root_id).cloned().unwrap_or_default();
+                    Some(proto::ChannelMembers {
+                        channel_id: channel_id.to_proto(),
+                        members: Self::channel_members_to_proto(members, &mut users),
+                    })
+                })
+                .collect::<Vec<_>>();
 
-        Ok((members, users))
+            Ok((channels, users.into_values().collect()))
+        })
+        .await
     }
 
     /// Returns whether the given user is an admin in the specified channel.
@@ -760,9 +1243,9 @@ impl Database {
             | Some(ChannelRole::Talker)
             | Some(ChannelRole::Banned)
             | Some(ChannelRole::Guest)
-            | None => Err(anyhow!(
-                "user is not a channel admin or channel does not exist"
-            ))?,
+            | None => Err(ErrorCode::Forbidden
+                .message("user is not a channel admin or channel does not exist".to_string())
+                .anyhow())?,
         }
     }
 
@@ -923,44 +1406,84 @@ impl Database {
         Ok(room_id)
     }
 
-    /// Move a channel from one parent to another
+    /// Move a channel from one parent to another, or out of its parent to
+    /// become a root channel when `new_parent_id` is `None`.
+    ///
+    /// A channel moved to the root keeps the accepted members of its previous
+    /// root channel, so everyone who could see it before still can.
     pub async fn move_channel(
         &self,
         channel_id: ChannelId,
-        new_parent_id: ChannelId,
+        new_parent_id: Option<ChannelId>,
         admin_id: UserId,
-    ) -> Result<(ChannelId, Vec<Channel>)> {
+    ) -> Result<MoveChannelResult> {
         self.transaction(|tx| async move {
             let channel = self.get_channel_internal(channel_id, &tx).await?;
             self.check_user_is_channel_admin(&channel, admin_id, &tx)
                 .await?;
-            let new_parent = self.get_channel_internal(new_parent_id, &tx).await?;
 
-            if new_parent.root_id() != channel.root_id() {
-                Err(anyhow!(ErrorCode::WrongMoveTarget))?;
-            }
+            let new_parent_path = if let Some(new_parent_id) = new_parent_id {
+                let new_parent = self.get_channel_internal(new_parent_id, &tx).await?;
 
-            if new_parent
-                .ancestors_including_self()
-                .any(|id| id == channel.id)
-            {
-                Err(anyhow!(ErrorCode::CircularNesting))?;
-            }
+                if new_parent.root_id() != channel.root_id() {
+                    Err(anyhow!(ErrorCode::WrongMoveTarget))?;
+                }
 
-            if channel.visibility == ChannelVisibility::Public
-                && new_parent.visibility != ChannelVisibility::Public
-            {
-                Err(anyhow!(ErrorCode::BadPublicNesting))?;
-            }
+                if new_parent
+                    .ancestors_including_self()
+                    .any(|id| id == channel.id)
+                {
+                    Err(anyhow!(ErrorCode::CircularNesting))?;
+                }
 
-            let root_id = channel.root_id();
+                if channel.visibility == ChannelVisibility::Public
+                    && new_parent.visibility != ChannelVisibility::Public
+                {
+                    Err(anyhow!(ErrorCode::BadPublicNesting))?;
+                }
+
+                new_parent.path()
+            } else {
+                if channel.parent_path.is_empty() {
+                    Err(anyhow!(ErrorCode::WrongMoveTarget))?;
+                }
+
+                String::new()
+            };
+
+            let previous_root_id = channel.root_id();
             let old_path = format!("{}{}/", channel.parent_path, channel.id);
-            let new_path = format!("{}{}/", new_parent.path(), channel.id);
+            let new_path = format!("{}{}/", new_parent_path, channel.id);
 
             let mut model = channel.into_active_model();
-            model.parent_path = ActiveValue::Set(new_parent.path());
+            model.parent_path = ActiveValue::Set(new_parent_path);
             let channel = model.update(&*tx).await?;
 
+            let mut new_memberships = Vec::new();
+            if new_parent_id.is_none() {
+                let previous_root_members = channel_member::Entity::find()
+                    .filter(
+                        channel_member::Column::ChannelId
+                            .eq(previous_root_id)
+                            .and(channel_member::Column::Accepted.eq(true)),
+                    )
+                    .all(&*tx)
+                    .await?;
+                for member in previous_root_members {
+                    new_memberships.push(
+                        channel_member::ActiveModel {
+                            id: ActiveValue::NotSet,
+                            channel_id: ActiveValue::Set(channel.id),
+                            user_id: ActiveValue::Set(member.user_id),
+                            accepted: ActiveValue::Set(true),
+                            role: ActiveValue::Set(member.role),
+                        }
+                        .insert(&*tx)
+                        .await?,
+                    );
+                }
+            }
+
             let descendent_ids =
                 ChannelId::find_by_statement::<QueryIds>(Statement::from_sql_and_values(
                     self.pool.get_database_backend(),
@@ -984,7 +1507,20 @@ impl Database {
                 .map(Channel::from_model)
                 .collect::<Vec<_>>();
 
-            Ok((root_id, channels))
+            if channels
+                .iter()
+                .any(|channel| channel.parent_path.len() >= MAX_CHANNEL_DEPTH)
+            {
+                Err(ErrorCode::ChannelTooDeep
+                    .with_tag("max_depth", &MAX_CHANNEL_DEPTH.to_string())
+                    .anyhow())?;
+            }
+
+            Ok(MoveChannelResult {
+                previous_root_id,
+                channels,
+                new_memberships,
+            })
         })
         .await
     }
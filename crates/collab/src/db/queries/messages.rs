@@ -1,6 +1,7 @@
 use super::*;
 use rpc::Notification;
-use sea_orm::{SelectColumns, TryInsertResult};
+use sea_orm::{SelectColumns, TryInsertResult, sea_query::Func};
+use std::ops::Range;
 use time::OffsetDateTime;
 use util::ResultExt;
 
@@ -50,6 +51,28 @@ impl Database {
         Ok(())
     }
 
+    /// Returns the channel and the connections of everyone in its chat, or an error if the
+    /// given user isn't in it.
+    pub async fn channel_chat_participants(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<(channel::Model, HashSet<ConnectionId>)> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &tx).await?;
+            let rows = channel_chat_participant::Entity::find()
+                .filter(channel_chat_participant::Column::ChannelId.eq(channel_id))
+                .all(&*tx)
+                .await?;
+            if !rows.iter().any(|row| row.user_id == user_id) {
+                Err(anyhow!("not a chat participant"))?;
+            }
+            let participant_connection_ids = rows.iter().map(|row| row.connection()).collect();
+            Ok((channel, participant_connection_ids))
+        })
+        .await
+    }
+
     /// Removes `channel_chat_participant` records associated with the given user ID so they
     /// will no longer get chat notifications.
     pub async fn leave_channel_chat(
@@ -228,7 +251,70 @@ impl Database {
             .collect::<Vec<_>>())
     }
 
+    /// Finds the `@login` mentions in the message body of members of the channel, other than
+    /// the ones already given in `mentions`. Logins are matched case-insensitively, like on GitHub.
+    async fn extract_mentions(
+        &self,
+        channel: &channel::Model,
+        body: &str,
+        mentions: &[proto::ChatMention],
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<proto::ChatMention>> {
+        let candidates = parse_mentions(body)
+            .filter(|(range, _)| {
+                !mentions.iter().any(|mention| {
+                    mention.range.as_ref().is_some_and(|mention_range| {
+                        (mention_range.start as usize..mention_range.end as usize)
+                            .contains(&range.start)
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let users = user::Entity::find()
+            .filter(
+                Expr::expr(Func::lower(Expr::col(user::Column::GithubLogin))).is_in(
+                    candidates
+                        .iter()
+                        .map(|(_, login)| login.to_ascii_lowercase()),
+                ),
+            )
+            .all(tx)
+            .await?;
+        let member_ids = channel_member::Entity::find()
+            .filter(channel_member::Column::ChannelId.eq(channel.root_id()))
+            .filter(channel_member::Column::UserId.is_in(users.iter().map(|user| user.id)))
+            .filter(channel_member::Column::Accepted.eq(true))
+            .all(tx)
+            .await?
+            .into_iter()
+            .map(|member| member.user_id)
+            .collect::<HashSet<_>>();
+
+        Ok(candidates
+            .into_iter()
+            .filter_map(|(range, login)| {
+                let user = users.iter().find(|user| {
+                    user.github_login.eq_ignore_ascii_case(login) && member_ids.contains(&user.id)
+                })?;
+                Some(proto::ChatMention {
+                    range: Some(proto::Range {
+                        start: range.start as u64,
+                        end: range.end as u64,
+                    }),
+                    user_id: user.id.to_proto(),
+                })
+            })
+            .collect())
+    }
+
     /// Creates a new channel message.
+    ///
+    /// Besides the given `mentions`, any `@login` in the body that names a member of the channel
+    /// is treated as a mention of them.
     pub async fn create_channel_message(
         &self,
         channel_id: ChannelId,
@@ -292,24 +378,48 @@ impl Database {
             .await?;
 
             let message_id;
+            let mut mentions = mentions.to_vec();
             let mut notifications = Vec::new();
             match result {
                 TryInsertResult::Inserted(result) => {
                     message_id = result.last_insert_id;
+                    let extracted_mentions = self
+                        .extract_mentions(&channel, body, &mentions, &tx)
+                        .await?;
+                    mentions.extend(extracted_mentions);
                     let mentioned_user_ids =
                         mentions.iter().map(|m| m.user_id).collect::<HashSet<_>>();
 
-                    let mentions = self.format_mentions_to_entities(message_id, body, mentions)?;
-                    if !mentions.is_empty() {
-                        channel_message_mention::Entity::insert_many(mentions)
+                    let mention_entities =
+                        self.format_mentions_to_entities(message_id, body, &mentions)?;
+                    if !mention_entities.is_empty() {
+                        channel_message_mention::Entity::insert_many(mention_entities)
                             .exec(&*tx)
                             .await?;
                     }
 
+                    // Users who muted the channel still see the mention in the
+                    // chat, but aren't sent a notification for it.
+                    let muted_user_ids = channel_notification_level::Entity::find()
+                        .filter(channel_notification_level::Column::ChannelId.eq(channel_id))
+                        .filter(
+                            channel_notification_level::Column::Level
+                                .eq(ChannelNotificationLevel::None),
+                        )
+                        .all(&*tx)
+                        .await?
+                        .into_iter()
+                        .map(|row| row.user_id)
+                        .collect::<HashSet<_>>();
+
                     for mentioned_user in mentioned_user_ids {
+                        let mentioned_user = UserId::from_proto(mentioned_user);
+                        if muted_user_ids.contains(&mentioned_user) {
+                            continue;
+                        }
                         notifications.extend(
                             self.create_notification(
-                                UserId::from_proto(mentioned_user),
+                                mentioned_user,
                                 rpc::Notification::ChannelMessageMention {
                                     message_id: message_id.to_proto(),
                                     sender_id: user_id.to_proto(),
@@ -337,6 +447,7 @@ impl Database {
 
             Ok(CreatedChannelMessage {
                 message_id,
+                mentions,
                 participant_connection_ids,
                 notifications,
             })
@@ -562,6 +673,9 @@ impl Database {
     }
 
     /// Updates the channel message with the given ID, body and timestamp(edited_at).
+    ///
+    /// As when creating a message, any `@login` in the body that names a member of the channel
+    /// is treated as a mention of them, besides the given `mentions`.
     pub async fn update_channel_message(
         &self,
         channel_id: ChannelId,
@@ -646,7 +760,13 @@ impl Database {
                 .exec(&*tx)
                 .await?;
 
-            let new_mentions = self.format_mentions_to_entities(message_id, body, mentions)?;
+            let mut mentions = mentions.to_vec();
+            let extracted_mentions = self
+                .extract_mentions(&channel, body, &mentions, &tx)
+                .await?;
+            mentions.extend(extracted_mentions);
+
+            let new_mentions = self.format_mentions_to_entities(message_id, body, &mentions)?;
             if !new_mentions.is_empty() {
                 // insert new mentions
                 channel_message_mention::Entity::insert_many(new_mentions)
@@ -709,6 +829,7 @@ impl Database {
 
             Ok(UpdatedChannelMessage {
                 message_id,
+                mentions,
                 participant_connection_ids,
                 notifications,
                 reply_to_message_id: channel_message.reply_to_message_id,
@@ -722,3 +843,25 @@ impl Database {
         .await
     }
 }
+
+/// The ranges of the `@login` mentions in a message body, along with the mentioned logins.
+fn parse_mentions(body: &str) -> impl Iterator<Item = (Range<usize>, &str)> {
+    body.match_indices('@').filter_map(move |(start, _)| {
+        // Skip email addresses and the like.
+        if body[..start]
+            .chars()
+            .next_back()
+            .is_some_and(|ch| !ch.is_whitespace())
+        {
+            return None;
+        }
+        let login = &body[start + 1..];
+        let login_len = login
+            .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '-' || ch == '_'))
+            .unwrap_or(login.len());
+        if login_len == 0 {
+            return None;
+        }
+        Some((start..start + 1 + login_len, &login[..login_len]))
+    })
+}
@@ -8,9 +8,12 @@ pub mod buffer_snapshot;
 pub mod channel;
 pub mod channel_buffer_collaborator;
 pub mod channel_chat_participant;
+pub mod channel_creation_key;
+pub mod channel_favorite;
 pub mod channel_member;
 pub mod channel_message;
 pub mod channel_message_mention;
+pub mod channel_notification_level;
 pub mod contact;
 pub mod contributor;
 pub mod embedding;
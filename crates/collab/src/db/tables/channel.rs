@@ -10,6 +10,9 @@ pub struct Model {
     pub visibility: ChannelVisibility,
     pub parent_path: String,
     pub requires_zed_cla: bool,
+    pub description: Option<String>,
+    pub archived: bool,
+    pub max_participants: Option<i32>,
 }
 
 impl Model {
@@ -168,6 +168,7 @@ fn channel_tree(channels: &[(ChannelId, &[ChannelId], &'static str)]) -> Vec<Cha
             name: name.to_string(),
             visibility: ChannelVisibility::Members,
             parent_path: parent_path.to_vec(),
+            ..Default::default()
         })
         .collect()
 }
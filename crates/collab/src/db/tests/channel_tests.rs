@@ -1,6 +1,7 @@
 use crate::{
     db::{
-        Channel, ChannelId, ChannelRole, Database, NewUserParams, RoomId, UserId,
+        Channel, ChannelId, ChannelRole, Database, MAX_CHANNEL_DEPTH, NewUserParams, RoomId,
+        UserId,
         tests::{channel_tree, new_test_connection, new_test_user},
     },
     test_both_dbs,
@@ -311,6 +312,45 @@ async fn test_channel_renames(db: &Arc<Database>) {
     assert!(bad_name_rename.is_err())
 }
 
+test_both_dbs!(
+    test_channel_max_depth,
+    test_channel_max_depth_postgres,
+    test_channel_max_depth_sqlite
+);
+
+async fn test_channel_max_depth(db: &Arc<Database>) {
+    let user_id = new_test_user(db, "user1@example.com").await;
+
+    let root_id = db.create_root_channel("channel-0", user_id).await.unwrap();
+    let mut channel_id = root_id;
+    for depth in 1..MAX_CHANNEL_DEPTH {
+        channel_id = db
+            .create_sub_channel(&format!("channel-{depth}"), channel_id, user_id)
+            .await
+            .unwrap();
+    }
+    let deepest = db.get_channel(channel_id, user_id).await.unwrap();
+    assert_eq!(deepest.parent_path.len(), MAX_CHANNEL_DEPTH - 1);
+
+    // Neither creating nor moving a channel can nest it any deeper.
+    assert!(
+        db.create_sub_channel("too-deep", channel_id, user_id)
+            .await
+            .is_err()
+    );
+    let sibling_id = db
+        .create_sub_channel("sibling", root_id, user_id)
+        .await
+        .unwrap();
+    assert!(
+        db.move_channel(sibling_id, Some(channel_id), user_id)
+            .await
+            .is_err()
+    );
+    let sibling = db.get_channel(sibling_id, user_id).await.unwrap();
+    assert_eq!(sibling.parent_path, vec![root_id]);
+}
+
 test_both_dbs!(
     test_db_channel_moving,
     test_channels_moving_postgres,
@@ -410,7 +450,7 @@ async fn test_db_channel_moving_bugs(db: &Arc<Database>) {
     );
 
     // Can't move a channel into its ancestor
-    db.move_channel(projects_id, livestreaming_id, user_id)
+    db.move_channel(projects_id, Some(livestreaming_id), user_id)
         .await
         .unwrap_err();
     let result = db.get_channels_for_user(user_id).await.unwrap();
@@ -424,6 +464,70 @@ async fn test_db_channel_moving_bugs(db: &Arc<Database>) {
     );
 }
 
+test_both_dbs!(
+    test_db_channel_moving_to_root,
+    test_db_channel_moving_to_root_postgres,
+    test_db_channel_moving_to_root_sqlite
+);
+
+async fn test_db_channel_moving_to_root(db: &Arc<Database>) {
+    let admin = new_test_user(db, "admin@example.com").await;
+    let member = new_test_user(db, "member@example.com").await;
+
+    let zed_id = db.create_root_channel("zed", admin).await.unwrap();
+    let editor_id = db
+        .create_sub_channel("editor", zed_id, admin)
+        .await
+        .unwrap();
+    let multibuffer_id = db
+        .create_sub_channel("multibuffer", editor_id, admin)
+        .await
+        .unwrap();
+    db.invite_channel_member(zed_id, member, admin, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(zed_id, member, true)
+        .await
+        .unwrap();
+
+    // Members can't move channels.
+    db.move_channel(editor_id, None, member).await.unwrap_err();
+
+    let mut result = db.move_channel(editor_id, None, admin).await.unwrap();
+    assert_eq!(result.previous_root_id, zed_id);
+    result.channels.sort_by_key(|channel| channel.id);
+    assert_channel_tree(
+        result.channels,
+        &[(editor_id, &[]), (multibuffer_id, &[editor_id])],
+    );
+    let mut new_members = result
+        .new_memberships
+        .iter()
+        .map(|membership| (membership.channel_id, membership.user_id, membership.role))
+        .collect::<Vec<_>>();
+    new_members.sort_by_key(|(_, user_id, _)| *user_id);
+    assert_eq!(
+        new_members,
+        &[
+            (editor_id, admin, ChannelRole::Admin),
+            (editor_id, member, ChannelRole::Member),
+        ]
+    );
+
+    let result = db.get_channels_for_user(member).await.unwrap();
+    assert_channel_tree(
+        result.channels,
+        &[
+            (zed_id, &[]),
+            (editor_id, &[]),
+            (multibuffer_id, &[editor_id]),
+        ],
+    );
+
+    // Root channels can't be moved to the root.
+    db.move_channel(editor_id, None, admin).await.unwrap_err();
+}
+
 test_both_dbs!(
     test_user_is_channel_participant,
     test_user_is_channel_participant_postgres,
@@ -692,7 +796,7 @@ async fn test_user_is_channel_participant(db: &Arc<Database>) {
             },
             proto::ChannelMember {
                 user_id: guest.to_proto(),
-                kind: proto::channel_member::Kind::Member.into(),
+                kind: proto::channel_member::Kind::Guest.into(),
                 role: proto::ChannelRole::Guest.into(),
             },
         ]
@@ -737,7 +841,27 @@ async fn test_guest_access(db: &Arc<Database>) {
         db.join_channel_chat(zed_channel, guest_connection, guest)
             .await
             .is_ok()
-    )
+    );
+
+    let (members, _) = db
+        .get_channel_participant_details(zed_channel, "", 100, admin)
+        .await
+        .unwrap();
+    assert_eq!(
+        members,
+        &[
+            proto::ChannelMember {
+                user_id: admin.to_proto(),
+                kind: proto::channel_member::Kind::Member.into(),
+                role: proto::ChannelRole::Admin.into(),
+            },
+            proto::ChannelMember {
+                user_id: guest.to_proto(),
+                kind: proto::channel_member::Kind::Guest.into(),
+                role: proto::ChannelRole::Guest.into(),
+            },
+        ]
+    );
 }
 
 #[track_caller]
@@ -376,20 +376,30 @@ impl Server {
             .add_request_handler(create_channel)
             .add_request_handler(delete_channel)
             .add_request_handler(invite_channel_member)
+            .add_request_handler(invite_channel_members)
             .add_request_handler(remove_channel_member)
             .add_request_handler(set_channel_member_role)
+            .add_request_handler(transfer_channel_admin)
+            .add_request_handler(set_channel_notification_level)
+            .add_request_handler(set_channel_favorite)
             .add_request_handler(set_channel_visibility)
             .add_request_handler(rename_channel)
+            .add_request_handler(set_channel_description)
+            .add_request_handler(set_channel_archived)
+            .add_request_handler(set_channel_max_participants)
             .add_request_handler(join_channel_buffer)
             .add_request_handler(leave_channel_buffer)
             .add_message_handler(update_channel_buffer)
             .add_request_handler(rejoin_channel_buffers)
             .add_request_handler(get_channel_members)
+            .add_request_handler(get_members_for_channels)
             .add_request_handler(respond_to_channel_invite)
+            .add_request_handler(decline_channel_invitations)
             .add_request_handler(join_channel)
             .add_request_handler(join_channel_chat)
             .add_message_handler(leave_channel_chat)
             .add_request_handler(send_channel_message)
+            .add_message_handler(set_channel_typing)
             .add_request_handler(remove_channel_message)
             .add_request_handler(update_channel_message)
             .add_request_handler(get_channel_messages)
@@ -1295,7 +1305,7 @@ async fn join_room(
     let channel_id = session.db().await.channel_id_for_room(room_id).await?;
 
     if let Some(channel_id) = channel_id {
-        return join_channel_internal(channel_id, Box::new(response), session).await;
+        return join_channel_internal(channel_id, false, Box::new(response), session).await;
     }
 
     let joined_room = {
@@ -2740,6 +2750,8 @@ async fn subscribe_user_to_channels(user_id: UserId, session: &Session) -> Resul
         session.connection_id,
         build_update_user_channels(&channels_for_user),
     )?;
+    // Clients replace their channel participants with the ones in this update, since
+    // they may have missed changes while reconnecting.
     session.peer.send(
         session.connection_id,
         build_channels_update(channels_for_user),
@@ -2757,15 +2769,24 @@ async fn create_channel(
 
     let parent_id = request.parent_id.map(ChannelId::from_proto);
     let (channel, membership) = db
-        .create_channel(&request.name, parent_id, session.user_id())
+        .create_channel_with_description(
+            &request.name,
+            request.description.as_deref(),
+            parent_id,
+            session.user_id(),
+            request.idempotency_key.as_deref(),
+        )
         .await?;
 
     let root_id = channel.root_id();
+    // A replayed request returns the channel where it was created, which may
+    // differ from the request's parent.
+    let parent_id = channel.parent_id();
     let channel = Channel::from_model(channel);
 
     response.send(proto::CreateChannelResponse {
         channel: Some(channel.to_proto()),
-        parent_id: request.parent_id,
+        parent_id: parent_id.map(|id| id.to_proto()),
     })?;
 
     let mut connection_pool = session.connection_pool().await;
@@ -2867,6 +2888,58 @@ async fn invite_channel_member(
     Ok(())
 }
 
+/// Invite several users to a channel at once. Invites that fail don't prevent
+/// the others from being sent, and are reported back in the response.
+async fn invite_channel_members(
+    request: proto::InviteChannelMembers,
+    response: Response<proto::InviteChannelMembers>,
+    session: Session,
+) -> Result<()> {
+    let db = session.db().await;
+    let channel_id = ChannelId::from_proto(request.channel_id);
+
+    let mut invited = Vec::new();
+    let mut notifications = db::NotificationBatch::default();
+    let mut failed_user_ids = Vec::new();
+    for invitee in &request.invitees {
+        let invitee_id = UserId::from_proto(invitee.user_id);
+        match db
+            .invite_channel_member(
+                channel_id,
+                invitee_id,
+                session.user_id(),
+                invitee.role().into(),
+            )
+            .await
+        {
+            Ok(result) => {
+                invited.push((invitee_id, result.channel));
+                notifications.extend(result.notifications);
+            }
+            Err(error) => {
+                tracing::info!(%invitee_id, %channel_id, %error, "failed to invite channel member");
+                failed_user_ids.push(invitee.user_id);
+            }
+        }
+    }
+
+    let connection_pool = session.connection_pool().await;
+    for (invitee_id, channel) in invited {
+        let update = proto::UpdateChannels {
+            channel_invitations: vec![channel.to_proto()],
+            ..Default::default()
+        };
+        for connection_id in connection_pool.user_connection_ids(invitee_id) {
+            session.peer.send(connection_id, update.clone())?;
+        }
+    }
+
+    send_notifications(&connection_pool, &session.peer, notifications);
+
+    response.send(proto::InviteChannelMembersResponse { failed_user_ids })?;
+    Ok(())
+}
+
 /// remove someone from a channel
 async fn remove_channel_member(
     request: proto::RemoveChannelMember,
@@ -3004,6 +3077,37 @@ async fn set_channel_member_role(
     Ok(())
 }
 
+/// Make another member of a channel its admin.
+async fn transfer_channel_admin(
+    request: proto::TransferChannelAdmin,
+    response: Response<proto::TransferChannelAdmin>,
+    session: Session,
+) -> Result<()> {
+    let db = session.db().await;
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let updates = db
+        .transfer_channel_admin(
+            channel_id,
+            session.user_id(),
+            UserId::from_proto(request.user_id),
+            request.demote_self,
+        )
+        .await?;
+
+    let mut connection_pool = session.connection_pool().await;
+    for (user_id, membership_update) in updates {
+        notify_membership_updated(
+            &mut connection_pool,
+            membership_update,
+            user_id,
+            &session.peer,
+        );
+    }
+
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
 /// Change the name of a channel
 async fn rename_channel(
     request: proto::RenameChannel,
@@ -3036,24 +3140,130 @@ async fn rename_channel(
     Ok(())
 }
 
-/// Move a channel to a new parent.
+/// Set or clear the description of a channel.
+async fn set_channel_description(
+    request: proto::SetChannelDescription,
+    response: Response<proto::SetChannelDescription>,
+    session: Session,
+) -> Result<()> {
+    let db = session.db().await;
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let channel_model = db
+        .set_channel_description(
+            channel_id,
+            session.user_id(),
+            request.description.as_deref(),
+        )
+        .await?;
+    let root_id = channel_model.root_id();
+    let channel = Channel::from_model(channel_model);
+
+    response.send(proto::SetChannelDescriptionResponse {
+        channel: Some(channel.to_proto()),
+    })?;
+
+    broadcast_channel_update(&channel, root_id, &session).await
+}
+
+/// Sends the updated channel to every connection in its root channel that can see it.
+async fn broadcast_channel_update(
+    channel: &Channel,
+    root_id: ChannelId,
+    session: &Session,
+) -> Result<()> {
+    let connection_pool = session.connection_pool().await;
+    let update = proto::UpdateChannels {
+        channels: vec![channel.to_proto()],
+        ..Default::default()
+    };
+    for (connection_id, role) in connection_pool.channel_connection_ids(root_id) {
+        if role.can_see_channel(channel.visibility) {
+            session.peer.send(connection_id, update.clone())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Archive or unarchive a channel.
+async fn set_channel_archived(
+    request: proto::SetChannelArchived,
+    response: Response<proto::SetChannelArchived>,
+    session: Session,
+) -> Result<()> {
+    let db = session.db().await;
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let channel_model = db
+        .set_channel_archived(channel_id, session.user_id(), request.archived)
+        .await?;
+    let root_id = channel_model.root_id();
+    let channel = Channel::from_model(channel_model);
+
+    response.send(proto::SetChannelArchivedResponse {
+        channel: Some(channel.to_proto()),
+    })?;
+
+    broadcast_channel_update(&channel, root_id, &session).await
+}
+
+/// Limit the number of participants in a channel's call.
+async fn set_channel_max_participants(
+    request: proto::SetChannelMaxParticipants,
+    response: Response<proto::SetChannelMaxParticipants>,
+    session: Session,
+) -> Result<()> {
+    let db = session.db().await;
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let channel_model = db
+        .set_channel_max_participants(channel_id, session.user_id(), request.max_participants)
+        .await?;
+    let root_id = channel_model.root_id();
+    let channel = Channel::from_model(channel_model);
+
+    response.send(proto::SetChannelMaxParticipantsResponse {
+        channel: Some(channel.to_proto()),
+    })?;
+
+    broadcast_channel_update(&channel, root_id, &session).await
+}
+
+/// Move a channel to a new parent, or to the root.
 async fn move_channel(
     request: proto::MoveChannel,
     response: Response<proto::MoveChannel>,
     session: Session,
 ) -> Result<()> {
     let channel_id = ChannelId::from_proto(request.channel_id);
-    let to = ChannelId::from_proto(request.to);
+    let to = request.to.map(ChannelId::from_proto);
 
-    let (root_id, channels) = session
+    let result = session
         .db()
         .await
         .move_channel(channel_id, to, session.user_id())
         .await?;
 
-    let connection_pool = session.connection_pool().await;
-    for (connection_id, role) in connection_pool.channel_connection_ids(root_id) {
-        let channels = channels
+    let mut connection_pool = session.connection_pool().await;
+    for membership in &result.new_memberships {
+        connection_pool.subscribe_to_channel(
+            membership.user_id,
+            membership.channel_id,
+            membership.role,
+        );
+        let update = proto::UpdateUserChannels {
+            channel_memberships: vec![proto::ChannelMembership {
+                channel_id: membership.channel_id.to_proto(),
+                role: membership.role.into(),
+            }],
+            ..Default::default()
+        };
+        for connection_id in connection_pool.user_connection_ids(membership.user_id) {
+            session.peer.send(connection_id, update.clone())?;
+        }
+    }
+
+    for (connection_id, role) in connection_pool.channel_connection_ids(result.previous_root_id) {
+        let channels = result
+            .channels
             .iter()
             .filter_map(|channel| {
                 if role.can_see_channel(channel.visibility) {
@@ -3079,6 +3289,72 @@ async fn move_channel(
     Ok(())
 }
 
+/// Set which activity in a channel is surfaced to the current user.
+async fn set_channel_notification_level(
+    request: proto::SetChannelNotificationLevel,
+    response: Response<proto::SetChannelNotificationLevel>,
+    session: Session,
+) -> Result<()> {
+    let db = session.db().await;
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let level = request.level();
+    db.set_channel_notification_level(channel_id, session.user_id(), level.into())
+        .await?;
+
+    let update = proto::UpdateUserChannels {
+        channel_notification_levels: vec![proto::ChannelNotificationSetting {
+            channel_id: request.channel_id,
+            level: level.into(),
+        }],
+        ..Default::default()
+    };
+    for connection_id in session
+        .connection_pool()
+        .await
+        .user_connection_ids(session.user_id())
+    {
+        if connection_id != session.connection_id {
+            session.peer.send(connection_id, update.clone())?;
+        }
+    }
+
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
+/// Add a channel to or remove it from the current user's favorites.
+async fn set_channel_favorite(
+    request: proto::SetChannelFavorite,
+    response: Response<proto::SetChannelFavorite>,
+    session: Session,
+) -> Result<()> {
+    let db = session.db().await;
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let favorite_channel_ids = db
+        .set_channel_favorite(channel_id, session.user_id(), request.favorite)
+        .await?;
+
+    let update = proto::UpdateUserChannels {
+        favorite_channels: Some(proto::FavoriteChannels {
+            channel_ids: favorite_channel_ids
+                .into_iter()
+                .map(|id| id.to_proto())
+                .collect(),
+        }),
+        ..Default::default()
+    };
+    for connection_id in session
+        .connection_pool()
+        .await
+        .user_connection_ids(session.user_id())
+    {
+        session.peer.send(connection_id, update.clone())?;
+    }
+
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
 /// Get the list of channel members
 async fn get_channel_members(
     request: proto::GetChannelMembers,
@@ -3099,6 +3375,25 @@ async fn get_channel_members(
     Ok(())
 }
 
+/// Get the members of several channels at once, omitting any the user can't administer.
+async fn get_members_for_channels(
+    request: proto::GetMembersForChannels,
+    response: Response<proto::GetMembersForChannels>,
+    session: Session,
+) -> Result<()> {
+    let db = session.db().await;
+    let channel_ids = request
+        .channel_ids
+        .into_iter()
+        .map(ChannelId::from_proto)
+        .collect::<Vec<_>>();
+    let (channels, users) = db
+        .get_members_for_channels(&channel_ids, session.user_id())
+        .await?;
+    response.send(proto::GetMembersForChannelsResponse { channels, users })?;
+    Ok(())
+}
+
 /// Accept or decline a channel invitation.
 async fn respond_to_channel_invite(
     request: proto::RespondToChannelInvite,
@@ -3140,6 +3435,51 @@ async fn respond_to_channel_invite(
     Ok(())
 }
 
+/// Decline several channel invitations at once
+async fn decline_channel_invitations(
+    request: proto::DeclineChannelInvitations,
+    response: Response<proto::DeclineChannelInvitations>,
+    session: Session,
+) -> Result<()> {
+    let db = session.db().await;
+
+    let mut declined_channel_ids = Vec::new();
+    let mut notifications = db::NotificationBatch::default();
+    let mut failed_channel_ids = Vec::new();
+    for &channel_id in &request.channel_ids {
+        let channel_id = ChannelId::from_proto(channel_id);
+        match db
+            .respond_to_channel_invite(channel_id, session.user_id(), false)
+            .await
+        {
+            Ok(result) => {
+                declined_channel_ids.push(channel_id.to_proto());
+                notifications.extend(result.notifications);
+            }
+            Err(error) => {
+                tracing::info!(%channel_id, %error, "failed to decline channel invitation");
+                failed_channel_ids.push(channel_id.to_proto());
+            }
+        }
+    }
+
+    let connection_pool = session.connection_pool().await;
+    if !declined_channel_ids.is_empty() {
+        let update = proto::UpdateChannels {
+            remove_channel_invitations: declined_channel_ids,
+            ..Default::default()
+        };
+        for connection_id in connection_pool.user_connection_ids(session.user_id()) {
+            session.peer.send(connection_id, update.clone())?;
+        }
+    }
+
+    send_notifications(&connection_pool, &session.peer, notifications);
+
+    response.send(proto::DeclineChannelInvitationsResponse { failed_channel_ids })?;
+    Ok(())
+}
+
 /// Join the channels' room
 async fn join_channel(
     request: proto::JoinChannel,
@@ -3147,7 +3487,7 @@ async fn join_channel(
     session: Session,
 ) -> Result<()> {
     let channel_id = ChannelId::from_proto(request.channel_id);
-    join_channel_internal(channel_id, Box::new(response), session).await
+    join_channel_internal(channel_id, request.as_guest, Box::new(response), session).await
 }
 
 trait JoinChannelInternalResponse {
@@ -3166,9 +3506,14 @@ impl JoinChannelInternalResponse for Response<proto::JoinRoom> {
 
 async fn join_channel_internal(
     channel_id: ChannelId,
+    as_guest: bool,
     response: Box<impl JoinChannelInternalResponse>,
     session: Session,
 ) -> Result<()> {
+    // When jumping between channels, observers of the channel being left are
+    // only notified once the user has joined the new one, so they never see the
+    // user in neither channel.
+    let mut left_channel = None;
     let joined_room = {
         let mut db = session.db().await;
         // If zed quits without leaving the room, and the user re-opens zed before the
@@ -3180,13 +3525,31 @@ async fn join_channel_internal(
                 "cleaning up stale connection",
             );
             drop(db);
-            leave_room_for_session(&session, connection).await?;
+            left_channel = leave_room_for_session_internal(&session, connection).await?;
             db = session.db().await;
         }
 
-        let (joined_room, membership_updated, role) = db
-            .join_channel(channel_id, session.user_id(), session.connection_id)
-            .await?;
+        let join = if as_guest {
+            db.join_channel_as_guest(channel_id, session.user_id(), session.connection_id)
+                .await
+        } else {
+            db.join_channel(channel_id, session.user_id(), session.connection_id)
+                .await
+        };
+        let (joined_room, membership_updated, role) = match join {
+            Ok(result) => result,
+            Err(error) => {
+                if let Some((channel, room)) = left_channel {
+                    channel_updated(
+                        &channel,
+                        &room,
+                        &session.peer,
+                        &*session.connection_pool().await,
+                    );
+                }
+                return Err(error);
+            }
+        };
 
         let live_kit_connection_info =
             session
@@ -3247,14 +3610,32 @@ async fn join_channel_internal(
         joined_room
     };
 
+    let channel = joined_room
+        .channel
+        .ok_or_else(|| anyhow!("channel not returned"))?;
+    // Someone joining an empty channel is worth surfacing to its other members.
+    if joined_room.room.participants.len() == 1 {
+        channel_activity(
+            &channel,
+            session.user_id(),
+            &session.peer,
+            &*session.connection_pool().await,
+        );
+    }
     channel_updated(
-        &joined_room
-            .channel
-            .ok_or_else(|| anyhow!("channel not returned"))?,
+        &channel,
         &joined_room.room,
         &session.peer,
         &*session.connection_pool().await,
     );
+    if let Some((channel, room)) = left_channel {
+        channel_updated(
+            &channel,
+            &room,
+            &session.peer,
+            &*session.connection_pool().await,
+        );
+    }
 
     update_user_contacts(session.user_id(), &session).await?;
     Ok(())
@@ -3465,6 +3846,7 @@ async fn send_channel_message(
     let channel_id = ChannelId::from_proto(request.channel_id);
     let CreatedChannelMessage {
         message_id,
+        mentions,
         participant_connection_ids,
         notifications,
     } = session
@@ -3485,7 +3867,7 @@ async fn send_channel_message(
         sender_id: session.user_id().to_proto(),
         id: message_id.to_proto(),
         body,
-        mentions: request.mentions,
+        mentions,
         timestamp: timestamp.unix_timestamp() as u64,
         nonce: Some(nonce),
         reply_to_message_id: request.reply_to_message_id,
@@ -3581,6 +3963,7 @@ async fn update_channel_message(
     let updated_at = OffsetDateTime::now_utc();
     let UpdatedChannelMessage {
         message_id,
+        mentions,
         participant_connection_ids,
         notifications,
         reply_to_message_id,
@@ -3609,7 +3992,7 @@ async fn update_channel_message(
         sender_id: session.user_id().to_proto(),
         id: message_id.to_proto(),
         body: request.body.clone(),
-        mentions: request.mentions.clone(),
+        mentions,
         timestamp: timestamp.assume_utc().unix_timestamp() as u64,
         nonce: Some(nonce),
         reply_to_message_id: reply_to_message_id.map(|id| id.to_proto()),
@@ -3966,6 +4349,45 @@ async fn join_channel_chat(
     Ok(())
 }
 
+/// Let the others in a channel's chat know that the user started or stopped typing in it.
+async fn set_channel_typing(request: proto::SetChannelTyping, session: Session) -> Result<()> {
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let (channel, participant_connection_ids) = session
+        .db()
+        .await
+        .channel_chat_participants(channel_id, session.user_id())
+        .await?;
+    let root_id = channel.root_id();
+
+    // Only send to participants that can still see the channel, in case their role changed
+    // since they joined its chat.
+    let pool = &*session.connection_pool().await;
+    let user_connection_ids = pool
+        .user_connection_ids(session.user_id())
+        .collect::<HashSet<_>>();
+    broadcast(
+        None,
+        pool.channel_connection_ids(root_id)
+            .filter_map(|(connection_id, role)| {
+                (role.can_see_channel(channel.visibility)
+                    && participant_connection_ids.contains(&connection_id)
+                    && !user_connection_ids.contains(&connection_id))
+                .then_some(connection_id)
+            }),
+        |connection_id| {
+            session.peer.send(
+                connection_id,
+                proto::ChannelTypingUpdate {
+                    channel_id: channel_id.to_proto(),
+                    user_id: session.user_id().to_proto(),
+                    is_typing: request.is_typing,
+                },
+            )
+        },
+    );
+    Ok(())
+}
+
 /// Stop receiving chat updates for a channel
 async fn leave_channel_chat(request: proto::LeaveChannelChat, session: Session) -> Result<()> {
     let channel_id = ChannelId::from_proto(request.channel_id);
@@ -3984,18 +4406,21 @@ async fn get_channel_messages(
     session: Session,
 ) -> Result<()> {
     let channel_id = ChannelId::from_proto(request.channel_id);
+    let limit = request.limit.map_or(MESSAGE_COUNT_PER_PAGE, |limit| {
+        (limit as usize).clamp(1, MESSAGE_COUNT_PER_PAGE)
+    });
     let messages = session
         .db()
         .await
         .get_channel_messages(
             channel_id,
             session.user_id(),
-            MESSAGE_COUNT_PER_PAGE,
-            Some(MessageId::from_proto(request.before_message_id)),
+            limit,
+            request.before_message_id.map(MessageId::from_proto),
         )
         .await?;
     response.send(proto::GetChannelMessagesResponse {
-        done: messages.len() < MESSAGE_COUNT_PER_PAGE,
+        done: messages.len() < limit,
         messages,
     })?;
     Ok(())
@@ -4250,6 +4675,14 @@ fn build_update_user_channels(channels: &ChannelsForUser) -> proto::UpdateUserCh
             .collect(),
         observed_channel_buffer_version: channels.observed_buffer_versions.clone(),
         observed_channel_message_id: channels.observed_channel_messages.clone(),
+        channel_notification_levels: channels.channel_notification_levels.clone(),
+        favorite_channels: Some(proto::FavoriteChannels {
+            channel_ids: channels
+                .favorite_channel_ids
+                .iter()
+                .map(|id| id.to_proto())
+                .collect(),
+        }),
     }
 }
 
@@ -4363,6 +4796,32 @@ fn channel_updated(
     );
 }
 
+fn channel_activity(
+    channel: &db::channel::Model,
+    user_id: UserId,
+    peer: &Peer,
+    pool: &ConnectionPool,
+) {
+    let user_connection_ids = pool.user_connection_ids(user_id).collect::<HashSet<_>>();
+    broadcast(
+        None,
+        pool.channel_connection_ids(channel.root_id())
+            .filter_map(|(connection_id, role)| {
+                (role.can_see_channel(channel.visibility)
+                    && !user_connection_ids.contains(&connection_id))
+                .then_some(connection_id)
+            }),
+        |peer_id| {
+            peer.send(
+                peer_id,
+                proto::ChannelActivity {
+                    channel_id: channel.id.to_proto(),
+                },
+            )
+        },
+    );
+}
+
 async fn update_user_contacts(user_id: UserId, session: &Session) -> Result<()> {
     let db = session.db().await;
 
@@ -4399,6 +4858,23 @@ async fn update_user_contacts(user_id: UserId, session: &Session) -> Result<()>
 }
 
 async fn leave_room_for_session(session: &Session, connection_id: ConnectionId) -> Result<()> {
+    if let Some((channel, room)) = leave_room_for_session_internal(session, connection_id).await? {
+        channel_updated(
+            &channel,
+            &room,
+            &session.peer,
+            &*session.connection_pool().await,
+        );
+    }
+    Ok(())
+}
+
+/// Leaves the room, returning the channel backing it (if any) along with the
+/// room's updated state. Notifying the channel's observers is left to the caller.
+async fn leave_room_for_session_internal(
+    session: &Session,
+    connection_id: ConnectionId,
+) -> Result<Option<(db::channel::Model, proto::Room)>> {
     let mut contacts_to_update = HashSet::default();
 
     let room_id;
@@ -4424,16 +4900,7 @@ async fn leave_room_for_session(session: &Session, connection_id: ConnectionId)
 
         room_updated(&room, &session.peer);
     } else {
-        return Ok(());
-    }
-
-    if let Some(channel) = channel {
-        channel_updated(
-            &channel,
-            &room,
-            &session.peer,
-            &*session.connection_pool().await,
-        );
+        return Ok(None);
     }
 
     {
@@ -4469,7 +4936,7 @@ async fn leave_room_for_session(session: &Session, connection_id: ConnectionId)
         }
     }
 
-    Ok(())
+    Ok(channel.map(|channel| (channel, room)))
 }
 
 async fn leave_channel_buffers_for_session(session: &Session) -> Result<()> {
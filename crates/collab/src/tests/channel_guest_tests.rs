@@ -307,3 +307,111 @@ async fn test_channel_requires_zed_cla(cx_a: &mut TestAppContext, cx_b: &mut Tes
         assert!(room_b.read_with(cx_b, |room, _| room.can_use_microphone()));
     });
 }
+
+#[gpui::test]
+async fn test_join_channel_as_guest(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let user_b = client_b.user_id().unwrap();
+    let user_c = client_c.user_id().unwrap();
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_c, cx_c)],
+        )
+        .await;
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.set_channel_visibility(channel_id, proto::ChannelVisibility::Public, cx)
+        })
+        .await
+        .unwrap();
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| call.join_channel(channel_id, cx))
+        .await
+        .unwrap();
+
+    // B isn't a member of the channel, and C is a member who chooses to only listen in.
+    for (client, cx) in [(&client_b, &mut *cx_b), (&client_c, &mut *cx_c)] {
+        let active_call = cx.read(ActiveCall::global);
+        active_call
+            .update(cx, |call, cx| call.join_channel_as_guest(channel_id, cx))
+            .await
+            .unwrap();
+        executor.run_until_parked();
+
+        let room = active_call.read_with(cx, |call, _| call.room().unwrap().clone());
+        room.read_with(cx, |room, _| {
+            assert!(room.local_participant_is_guest());
+            assert!(!room.can_use_microphone());
+            assert!(!room.can_share_projects());
+        });
+        assert!(
+            room.update(cx, |room, cx| room.share_microphone(cx))
+                .await
+                .is_err()
+        );
+    }
+
+    let room_a = active_call_a.read_with(cx_a, |call, _| call.room().unwrap().clone());
+    room_a.read_with(cx_a, |room, _| {
+        assert_eq!(room.role_for_user(user_b), Some(proto::ChannelRole::Guest));
+        assert_eq!(room.role_for_user(user_c), Some(proto::ChannelRole::Guest));
+    });
+    client_a.channel_store().read_with(cx_a, |store, _| {
+        let mut participant_ids = store
+            .channel_participants(channel_id)
+            .iter()
+            .map(|user| user.id)
+            .collect::<Vec<_>>();
+        participant_ids.sort();
+        assert_eq!(
+            participant_ids,
+            [client_a.user_id().unwrap(), user_b, user_c]
+        );
+    });
+
+    // Joining as a guest doesn't change the channel memberships.
+    for (user_id, login, role, kind) in [
+        (
+            user_b,
+            "user_b",
+            proto::ChannelRole::Guest,
+            proto::channel_member::Kind::Guest,
+        ),
+        (
+            user_c,
+            "user_c",
+            proto::ChannelRole::Member,
+            proto::channel_member::Kind::Member,
+        ),
+    ] {
+        let members = client_a
+            .channel_store()
+            .update(cx_a, |store, cx| {
+                store.fuzzy_search_members(channel_id, login.to_string(), 10, cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            members
+                .iter()
+                .map(|member| (member.user.id, member.role, member.kind))
+                .collect::<Vec<_>>(),
+            [(user_id, role, kind)]
+        );
+    }
+}
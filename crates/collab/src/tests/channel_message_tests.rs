@@ -1,8 +1,17 @@
-use crate::{rpc::RECONNECT_TIMEOUT, tests::TestServer};
-use channel::{ChannelChat, ChannelMessageId, MessageParams};
+use crate::{
+    db,
+    rpc::RECONNECT_TIMEOUT,
+    tests::{TestClient, TestServer},
+};
+use channel::{
+    ChannelChat, ChannelMessageId, MessageParams, TYPING_BROADCAST_INTERVAL, TYPING_TIMEOUT,
+};
+use client::ChannelId;
 use collab_ui::chat_panel::ChatPanel;
 use gpui::{BackgroundExecutor, Entity, TestAppContext};
-use rpc::Notification;
+use rpc::{Notification, proto};
+use std::time::Duration;
+use time::OffsetDateTime;
 use workspace::dock::Panel;
 
 #[gpui::test]
@@ -723,3 +732,281 @@ async fn test_chat_editing(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
         assert_eq!(store.notification_count(), 1);
     });
 }
+
+#[gpui::test]
+async fn test_load_channel_messages(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    for (nonce, body) in ["one", "two", "three", "four", "five"]
+        .into_iter()
+        .enumerate()
+    {
+        server
+            .app_state
+            .db
+            .create_channel_message(
+                db::ChannelId::from_proto(channel_id.0),
+                db::UserId::from_proto(client_a.id()),
+                body,
+                &[],
+                OffsetDateTime::now_utc(),
+                nonce as u128,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    // Pages are loaded backwards from the latest message, each in chronological order.
+    let (bodies, before) = load_message_page(&client_b, channel_id, None, cx_b).await;
+    assert_eq!(bodies, ["four", "five"]);
+    let (bodies, before) = load_message_page(&client_b, channel_id, before, cx_b).await;
+    assert_eq!(bodies, ["two", "three"]);
+    let (bodies, before) = load_message_page(&client_b, channel_id, before, cx_b).await;
+    assert_eq!(bodies, ["one"]);
+    let (bodies, _) = load_message_page(&client_b, channel_id, before, cx_b).await;
+    assert!(bodies.is_empty());
+
+    // Pages aren't cached, so edits and removals show up when they're loaded again, even for a
+    // client that isn't in the channel's chat and so isn't told about them.
+    let (_, before_four) = load_message_page(&client_b, channel_id, None, cx_b).await;
+    let (bodies, before_two) = load_message_page(&client_b, channel_id, before_four, cx_b).await;
+    assert_eq!(bodies, ["two", "three"]);
+    let channel_chat_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    channel_chat_a
+        .update(cx_a, |chat, cx| {
+            chat.update_message(
+                before_two.unwrap(),
+                MessageParams {
+                    text: "second".into(),
+                    reply_to_message_id: None,
+                    mentions: Vec::new(),
+                },
+                cx,
+            )
+            .unwrap()
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    let (bodies, _) = load_message_page(&client_b, channel_id, before_four, cx_b).await;
+    assert_eq!(bodies, ["second", "three"]);
+
+    channel_chat_a
+        .update(cx_a, |chat, cx| {
+            chat.remove_message(before_two.unwrap(), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    let (bodies, _) = load_message_page(&client_b, channel_id, before_four, cx_b).await;
+    assert_eq!(bodies, ["one", "three"]);
+}
+
+/// Loads a page of two messages, returning their bodies and the id of the first one.
+async fn load_message_page(
+    client: &TestClient,
+    channel_id: ChannelId,
+    before: Option<u64>,
+    cx: &mut TestAppContext,
+) -> (Vec<String>, Option<u64>) {
+    let messages = client
+        .channel_store()
+        .update(cx, |store, cx| {
+            store.load_channel_messages(channel_id, before, 2, cx)
+        })
+        .await
+        .unwrap();
+    let first_id = messages.first().and_then(|message| message.id.into());
+    let bodies = messages.into_iter().map(|message| message.body).collect();
+    (bodies, first_id)
+}
+
+#[gpui::test]
+async fn test_channel_typing_indicators(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b), (&client_c, cx_c)],
+        )
+        .await;
+    // Typing is only shared with the others in the channel's chat, which user_c isn't in.
+    let _channel_chat_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    let _channel_chat_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    let typing_participants = |client: &TestClient, cx: &mut TestAppContext| {
+        client.channel_store().read_with(cx, |store, _| {
+            store
+                .typing_participants(channel_id)
+                .iter()
+                .map(|user| user.github_login.clone())
+                .collect::<Vec<_>>()
+        })
+    };
+
+    client_b.channel_store().update(cx_b, |store, cx| {
+        store.set_typing(channel_id, true, cx);
+    });
+    executor.run_until_parked();
+    assert_eq!(typing_participants(&client_a, cx_a), ["user_b"]);
+    assert!(typing_participants(&client_b, cx_b).is_empty());
+    assert!(typing_participants(&client_c, cx_c).is_empty());
+
+    // Typing refreshes the indicator, which otherwise clears after a timeout.
+    executor.advance_clock(TYPING_BROADCAST_INTERVAL);
+    client_b.channel_store().update(cx_b, |store, cx| {
+        store.set_typing(channel_id, true, cx);
+    });
+    executor.advance_clock(TYPING_TIMEOUT - TYPING_BROADCAST_INTERVAL + Duration::from_secs(1));
+    executor.run_until_parked();
+    assert_eq!(typing_participants(&client_a, cx_a), ["user_b"]);
+
+    executor.advance_clock(TYPING_BROADCAST_INTERVAL);
+    executor.run_until_parked();
+    assert!(typing_participants(&client_a, cx_a).is_empty());
+
+    // Stopping typing clears the indicator right away.
+    client_b.channel_store().update(cx_b, |store, cx| {
+        store.set_typing(channel_id, true, cx);
+    });
+    executor.run_until_parked();
+    assert_eq!(typing_participants(&client_a, cx_a), ["user_b"]);
+    client_b.channel_store().update(cx_b, |store, cx| {
+        store.set_typing(channel_id, false, cx);
+    });
+    executor.run_until_parked();
+    assert!(typing_participants(&client_a, cx_a).is_empty());
+}
+
+#[gpui::test]
+async fn test_channel_message_mentions_from_body(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b), (&client_c, cx_c)],
+        )
+        .await;
+    for (client, cx) in [(&client_b, &mut *cx_b), (&client_c, &mut *cx_c)] {
+        client
+            .channel_store()
+            .update(cx, |store, cx| {
+                store.set_channel_notifications(
+                    channel_id,
+                    proto::ChannelNotificationLevel::Mentions,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+    }
+
+    let channel_chat_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    let message_id = channel_chat_a
+        .update(cx_a, |chat, cx| {
+            chat.send_message("@user_b hello".into(), cx).unwrap()
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    channel_chat_a.read_with(cx_a, |chat, _| {
+        let message = chat.message(0);
+        assert_eq!(message.mentions, [(0..7, client_b.id())]);
+        assert!(message.mentions_user(client_b.id()));
+        assert!(!message.mentions_user(client_c.id()));
+    });
+
+    client_b.notification_store().read_with(cx_b, |store, _| {
+        assert_eq!(store.notification_count(), 2);
+        assert_eq!(
+            store.notification_at(0).unwrap().notification,
+            Notification::ChannelMessageMention {
+                message_id,
+                sender_id: client_a.id(),
+                channel_id: channel_id.0,
+            }
+        );
+    });
+    client_c.notification_store().read_with(cx_c, |store, _| {
+        assert_eq!(store.notification_count(), 1);
+        assert!(matches!(
+            store.notification_at(0).unwrap().notification,
+            Notification::ChannelInvitation { .. }
+        ));
+    });
+
+    // Mentions are also found when editing, and logins match regardless of case.
+    channel_chat_a
+        .update(cx_a, |chat, cx| {
+            chat.update_message(message_id, "hello @USER_C".into(), cx)
+                .unwrap()
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    client_c.notification_store().read_with(cx_c, |store, _| {
+        assert_eq!(store.notification_count(), 2);
+        assert_eq!(
+            store.notification_at(0).unwrap().notification,
+            Notification::ChannelMessageMention {
+                message_id,
+                sender_id: client_a.id(),
+                channel_id: channel_id.0,
+            }
+        );
+    });
+}
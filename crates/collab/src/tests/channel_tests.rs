@@ -4,15 +4,15 @@ use crate::{
     tests::{RoomParticipants, TestServer, room_participants},
 };
 use call::ActiveCall;
-use channel::{ChannelMembership, ChannelStore};
+use channel::{ChannelEvent, ChannelMembership, ChannelStore, InviteMembersError};
 use client::{ChannelId, User};
 use futures::future::try_join_all;
 use gpui::{BackgroundExecutor, Entity, SharedString, TestAppContext};
 use rpc::{
-    RECEIVE_TIMEOUT,
+    ErrorCode, ErrorExt, RECEIVE_TIMEOUT,
     proto::{self, ChannelRole},
 };
-use std::sync::Arc;
+use std::{cell::RefCell, rc::Rc, sync::Arc};
 
 #[gpui::test]
 async fn test_core_channels(
@@ -610,6 +610,9 @@ async fn test_channel_jumping(executor: BackgroundExecutor, cx_a: &mut TestAppCo
             assert_participants_eq(channels.channel_participants(rust_id), &[]);
         })
     });
+    active_call_a.read_with(cx_a, |call, cx| {
+        assert_eq!(call.channel_id(cx), Some(zed_id));
+    });
 
     active_call_a
         .update(cx_a, |active_call, cx| {
@@ -629,6 +632,66 @@ async fn test_channel_jumping(executor: BackgroundExecutor, cx_a: &mut TestAppCo
             );
         })
     });
+    active_call_a.read_with(cx_a, |call, cx| {
+        assert_eq!(call.channel_id(cx), Some(rust_id));
+    });
+
+    active_call_a
+        .update(cx_a, |active_call, cx| active_call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    active_call_a.read_with(cx_a, |call, cx| {
+        assert_eq!(call.channel_id(cx), None);
+    });
+}
+
+#[gpui::test]
+async fn test_channel_jumping_before_join_completes(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let zed_id = server
+        .make_channel("zed", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+    let rust_id = server
+        .make_channel("rust", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let (join_zed, join_rust) = active_call_a.update(cx_a, |active_call, cx| {
+        (
+            active_call.join_channel(zed_id, cx),
+            active_call.join_channel(rust_id, cx),
+        )
+    });
+    assert!(join_zed.await.unwrap().is_none());
+    let room = join_rust.await.unwrap().unwrap();
+    executor.run_until_parked();
+
+    room.read_with(cx_a, |room, _| {
+        assert_eq!(room.channel_id(), Some(rust_id));
+    });
+    active_call_a.read_with(cx_a, |call, cx| {
+        assert_eq!(call.channel_id(cx), Some(rust_id));
+    });
+    for (client, cx) in [(&client_a, &*cx_a), (&client_b, &*cx_b)] {
+        cx.read(|cx| {
+            client.channel_store().read_with(cx, |channels, _| {
+                assert_participants_eq(channels.channel_participants(zed_id), &[]);
+                assert_participants_eq(
+                    channels.channel_participants(rust_id),
+                    &[client_a.user_id().unwrap()],
+                );
+            })
+        });
+    }
 }
 
 #[gpui::test]
@@ -1055,7 +1118,7 @@ async fn test_channel_link_notifications(
     client_a
         .channel_store()
         .update(cx_a, |channel_store, cx| {
-            channel_store.move_channel(helix_channel, vim_channel, cx)
+            channel_store.move_channel(helix_channel, Some(vim_channel), cx)
         })
         .await
         .unwrap();
@@ -1378,7 +1441,7 @@ async fn test_channel_moving(
     client_a
         .channel_store()
         .update(cx_a, |channel_store, cx| {
-            channel_store.move_channel(channel_d_id, channel_b_id, cx)
+            channel_store.move_channel(channel_d_id, Some(channel_b_id), cx)
         })
         .await
         .unwrap();
@@ -1398,6 +1461,1700 @@ async fn test_channel_moving(
     );
 }
 
+#[gpui::test]
+async fn test_all_known_members(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+    cx_d: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let client_d = server.create_client(cx_d, "user_d").await;
+
+    server
+        .make_channel(
+            "channel-1",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b), (&client_c, cx_c)],
+        )
+        .await;
+    let channel_2_id = server
+        .make_channel(
+            "channel-2",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_c, cx_c)],
+        )
+        .await;
+    // User A is a member, but not an admin, of this channel.
+    server
+        .make_channel(
+            "channel-3",
+            None,
+            (&client_b, cx_b),
+            &mut [(&client_a, cx_a)],
+        )
+        .await;
+    executor.run_until_parked();
+
+    let members = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.all_known_members(cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        members
+            .iter()
+            .map(|user| user.github_login.as_str())
+            .collect::<Vec<_>>(),
+        ["user_b", "user_c"]
+    );
+
+    // Inviting a member forgets the cached members of that channel.
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.invite_member(
+                channel_2_id,
+                client_d.user_id().unwrap(),
+                proto::ChannelRole::Member,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    client_d
+        .channel_store()
+        .update(cx_d, |store, cx| {
+            store.respond_to_channel_invite(channel_2_id, true, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let members = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.all_known_members(cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        members
+            .iter()
+            .map(|user| user.github_login.as_str())
+            .collect::<Vec<_>>(),
+        ["user_b", "user_c", "user_d"]
+    );
+}
+
+#[gpui::test]
+async fn test_move_channel_to_root(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let zed_id = server
+        .make_channel("zed", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+    let editor_id = client_a
+        .channel_store()
+        .update(cx_a, |channel_store, cx| {
+            channel_store.create_channel("editor", Some(zed_id), cx)
+        })
+        .await
+        .unwrap();
+    let multibuffer_id = client_a
+        .channel_store()
+        .update(cx_a, |channel_store, cx| {
+            channel_store.create_channel("multibuffer", Some(editor_id), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // A channel can't be moved into its own descendant.
+    let error = client_a
+        .channel_store()
+        .update(cx_a, |channel_store, cx| {
+            channel_store.move_channel(editor_id, Some(multibuffer_id), cx)
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), ErrorCode::CircularNesting);
+
+    // Only admins can move channels.
+    let error = client_b
+        .channel_store()
+        .update(cx_b, |channel_store, cx| {
+            channel_store.move_channel(editor_id, None, cx)
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), ErrorCode::Forbidden);
+
+    client_a
+        .channel_store()
+        .update(cx_a, |channel_store, cx| {
+            channel_store.move_channel(editor_id, None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    for (client, cx) in [(&client_a, &*cx_a), (&client_b, &*cx_b)] {
+        assert_channels_list_shape(
+            client.channel_store(),
+            cx,
+            &[(editor_id, 0), (multibuffer_id, 1), (zed_id, 0)],
+        );
+    }
+    client_b
+        .channel_store()
+        .read_with(cx_b, |channel_store, _| {
+            assert_eq!(
+                channel_store
+                    .channel_for_id(multibuffer_id)
+                    .unwrap()
+                    .parent_path,
+                vec![editor_id]
+            );
+            assert!(!channel_store.is_channel_admin(editor_id));
+        });
+    client_a
+        .channel_store()
+        .read_with(cx_a, |channel_store, _| {
+            assert!(channel_store.is_channel_admin(editor_id));
+        });
+
+    // The moved channel is a root now, so it can't be moved to the root again.
+    let error = client_a
+        .channel_store()
+        .update(cx_a, |channel_store, cx| {
+            channel_store.move_channel(editor_id, None, cx)
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), ErrorCode::WrongMoveTarget);
+}
+
+#[gpui::test]
+async fn test_get_members_for_channels(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+
+    let channel_1 = server
+        .make_channel(
+            "channel-1",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+    let channel_2 = server
+        .make_channel(
+            "channel-2",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_c, cx_c)],
+        )
+        .await;
+    // Client A is a member, but not an admin, of this channel.
+    let channel_3 = server
+        .make_channel(
+            "channel-3",
+            None,
+            (&client_b, cx_b),
+            &mut [(&client_a, cx_a)],
+        )
+        .await;
+    executor.run_until_parked();
+
+    let members = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.get_members_for_channels(&[channel_1, channel_2, channel_3], cx)
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(members.len(), 2);
+    assert!(!members.contains_key(&channel_3));
+    assert_members_eq(
+        &members[&channel_1],
+        &[
+            (
+                client_a.user_id().unwrap(),
+                proto::ChannelRole::Admin,
+                proto::channel_member::Kind::Member,
+            ),
+            (
+                client_b.user_id().unwrap(),
+                proto::ChannelRole::Member,
+                proto::channel_member::Kind::Member,
+            ),
+        ],
+    );
+    assert_members_eq(
+        &members[&channel_2],
+        &[
+            (
+                client_a.user_id().unwrap(),
+                proto::ChannelRole::Admin,
+                proto::channel_member::Kind::Member,
+            ),
+            (
+                client_c.user_id().unwrap(),
+                proto::ChannelRole::Member,
+                proto::channel_member::Kind::Member,
+            ),
+        ],
+    );
+}
+
+#[gpui::test]
+async fn test_channel_rename(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "channel-a",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.rename(channel_id, "channel-b", cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    for (client, cx) in [(&client_a, &*cx_a), (&client_b, &*cx_b)] {
+        assert_channels(
+            client.channel_store(),
+            cx,
+            &[ExpectedChannel {
+                id: channel_id,
+                name: "channel-b".into(),
+                depth: 0,
+            }],
+        );
+    }
+
+    // Blank and overly long names are rejected, leaving the name unchanged.
+    let long_name = "a".repeat(db::MAX_CHANNEL_NAME_LENGTH + 1);
+    for name in ["", "  #", long_name.as_str()] {
+        let result = client_a
+            .channel_store()
+            .update(cx_a, |store, cx| store.rename(channel_id, name, cx))
+            .await;
+        assert!(result.is_err(), "renaming to {name:?} should fail");
+    }
+    executor.run_until_parked();
+    assert_channels(
+        client_b.channel_store(),
+        cx_b,
+        &[ExpectedChannel {
+            id: channel_id,
+            name: "channel-b".into(),
+            depth: 0,
+        }],
+    );
+}
+
+#[gpui::test]
+async fn test_channel_participants_changed_event(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let user_a = client_a.user_id().unwrap();
+    let user_b = client_b.user_id().unwrap();
+
+    let zed_id = server
+        .make_channel("zed", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    cx_b.update(|cx| {
+        let events = events.clone();
+        cx.subscribe(
+            client_b.channel_store(),
+            move |_, event: &ChannelEvent, _| {
+                if let ChannelEvent::ParticipantsChanged {
+                    channel_id,
+                    added,
+                    removed,
+                } = event
+                {
+                    assert_eq!(*channel_id, zed_id);
+                    events.borrow_mut().push((added.clone(), removed.clone()));
+                }
+            },
+        )
+        .detach();
+    });
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    active_call_a
+        .update(cx_a, |active_call, cx| active_call.join_channel(zed_id, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(
+        events.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [(vec![user_a], vec![])]
+    );
+
+    active_call_b
+        .update(cx_b, |active_call, cx| active_call.join_channel(zed_id, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(
+        events.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [(vec![user_b], vec![])]
+    );
+
+    active_call_a
+        .update(cx_a, |active_call, cx| active_call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(
+        events.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [(vec![], vec![user_a])]
+    );
+}
+
+#[gpui::test]
+async fn test_invite_channel_members(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let user_a = client_a.user_id().unwrap();
+    let user_b = client_b.user_id().unwrap();
+    let user_c = client_c.user_id().unwrap();
+
+    let channel_id = server
+        .make_channel("the-channel", None, (&client_a, cx_a), &mut [])
+        .await;
+
+    // Client A is already a member, so inviting them fails without affecting
+    // the other invites.
+    let invite = client_a.channel_store().update(cx_a, |store, cx| {
+        let invite = store.invite_members(
+            channel_id,
+            &[
+                (user_b, proto::ChannelRole::Member),
+                (user_c, proto::ChannelRole::Admin),
+                (user_a, proto::ChannelRole::Member),
+            ],
+            cx,
+        );
+        assert!(store.has_pending_channel_invite(channel_id, user_b));
+        assert!(store.has_pending_channel_invite(channel_id, user_c));
+        invite
+    });
+    let error = invite.await.unwrap_err();
+    assert_eq!(
+        error
+            .downcast_ref::<InviteMembersError>()
+            .unwrap()
+            .failed_user_ids,
+        vec![user_a]
+    );
+    executor.run_until_parked();
+
+    client_a.channel_store().read_with(cx_a, |store, _| {
+        assert!(!store.has_pending_channel_invite(channel_id, user_b));
+        assert!(!store.has_pending_channel_invite(channel_id, user_c));
+    });
+    for (client, cx) in [(&client_b, &*cx_b), (&client_c, &*cx_c)] {
+        assert_channel_invitations(
+            client.channel_store(),
+            cx,
+            &[ExpectedChannel {
+                id: channel_id,
+                name: "the-channel".into(),
+                depth: 0,
+            }],
+        );
+    }
+}
+
+#[gpui::test]
+async fn test_set_member_admin(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let user_b = client_b.user_id().unwrap();
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b), (&client_c, cx_c)],
+        )
+        .await;
+
+    // Members who aren't admins can't change roles.
+    let error = client_c
+        .channel_store()
+        .update(cx_c, |store, cx| {
+            store.set_member_admin(channel_id, user_b, true, cx)
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), ErrorCode::Forbidden);
+
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.set_member_admin(channel_id, user_b, true, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        assert!(store.is_channel_admin(channel_id));
+    });
+    let members = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.fuzzy_search_members(channel_id, "user_b".to_string(), 10, cx)
+        })
+        .await
+        .unwrap();
+    assert_members_eq(
+        &members,
+        &[(
+            user_b,
+            proto::ChannelRole::Admin,
+            proto::channel_member::Kind::Member,
+        )],
+    );
+
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.set_member_admin(channel_id, user_b, false, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        assert!(!store.is_channel_admin(channel_id));
+    });
+}
+
+#[gpui::test]
+async fn test_transfer_channel_admin(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let user_a = client_a.user_id().unwrap();
+    let user_b = client_b.user_id().unwrap();
+    let user_c = client_c.user_id().unwrap();
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.invite_member(channel_id, user_c, proto::ChannelRole::Member, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // Only admins can transfer the admin role...
+    let error = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| {
+            store.transfer_admin(channel_id, user_b, false, cx)
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), ErrorCode::Forbidden);
+
+    // ...and only to users who have joined the channel.
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.transfer_admin(channel_id, user_c, false, cx)
+        })
+        .await
+        .unwrap_err();
+
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.transfer_admin(channel_id, user_b, true, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    client_a.channel_store().read_with(cx_a, |store, _| {
+        assert!(!store.is_channel_admin(channel_id));
+    });
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        assert!(store.is_channel_admin(channel_id));
+    });
+    for (user_id, login, role) in [
+        (user_a, "user_a", proto::ChannelRole::Member),
+        (user_b, "user_b", proto::ChannelRole::Admin),
+    ] {
+        let members = client_b
+            .channel_store()
+            .update(cx_b, |store, cx| {
+                store.fuzzy_search_members(channel_id, login.to_string(), 10, cx)
+            })
+            .await
+            .unwrap();
+        assert_members_eq(
+            &members,
+            &[(user_id, role, proto::channel_member::Kind::Member)],
+        );
+    }
+}
+
+#[gpui::test]
+async fn test_nested_channel_depth(executor: BackgroundExecutor, cx_a: &mut TestAppContext) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+
+    let zed_id = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.create_channel("zed", None, cx))
+        .await
+        .unwrap();
+    let editor_id = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.create_channel("editor", Some(zed_id), cx)
+        })
+        .await
+        .unwrap();
+    let text_id = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.create_channel("text", Some(editor_id), cx)
+        })
+        .await
+        .unwrap();
+    let website_id = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.create_channel("website", Some(zed_id), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // Each channel is listed directly before its descendants.
+    assert_channels(
+        client_a.channel_store(),
+        cx_a,
+        &[
+            ExpectedChannel {
+                id: zed_id,
+                name: "zed".into(),
+                depth: 0,
+            },
+            ExpectedChannel {
+                id: editor_id,
+                name: "editor".into(),
+                depth: 1,
+            },
+            ExpectedChannel {
+                id: text_id,
+                name: "text".into(),
+                depth: 2,
+            },
+            ExpectedChannel {
+                id: website_id,
+                name: "website".into(),
+                depth: 1,
+            },
+        ],
+    );
+
+    // Channels can't be created under a parent that doesn't exist.
+    let result = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.create_channel("orphan", Some(ChannelId(u64::MAX)), cx)
+        })
+        .await;
+    assert_eq!(result.unwrap_err().error_code(), ErrorCode::NoSuchChannel);
+}
+
+#[gpui::test]
+async fn test_channel_favorites(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_1 = server
+        .make_channel("channel-1", None, (&client_a, cx_a), &mut [])
+        .await;
+    let _channel_2 = server
+        .make_channel("channel-2", None, (&client_a, cx_a), &mut [])
+        .await;
+    let channel_3 = server
+        .make_channel("channel-3", None, (&client_a, cx_a), &mut [])
+        .await;
+
+    for channel_id in [channel_3, channel_1] {
+        client_a
+            .channel_store()
+            .update(cx_a, |store, cx| store.toggle_favorite(channel_id, cx))
+            .await
+            .unwrap();
+    }
+    executor.run_until_parked();
+    assert_favorite_channels(client_a.channel_store(), cx_a, &[channel_3, channel_1]);
+
+    // Favorites are restored from the server after reconnecting.
+    server.forbid_connections();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    server.allow_connections();
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    assert_favorite_channels(client_a.channel_store(), cx_a, &[channel_3, channel_1]);
+
+    // Favorites are per user.
+    assert_favorite_channels(client_b.channel_store(), cx_b, &[]);
+}
+
+#[track_caller]
+fn assert_favorite_channels(
+    channel_store: &Entity<ChannelStore>,
+    cx: &TestAppContext,
+    expected_channels: &[ChannelId],
+) {
+    let actual = cx.read(|cx| {
+        channel_store.read_with(cx, |store, _| {
+            store
+                .favorite_channels()
+                .iter()
+                .map(|channel| channel.id)
+                .collect::<Vec<_>>()
+        })
+    });
+    assert_eq!(actual, expected_channels);
+}
+
+#[gpui::test]
+async fn test_channel_jump_is_atomic(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let user_a = client_a.user_id().unwrap();
+    let user_b = client_b.user_id().unwrap();
+
+    let zed_id = server
+        .make_channel(
+            "zed",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b), (&client_c, cx_c)],
+        )
+        .await;
+    let rust_id = server
+        .make_channel("rust", None, (&client_a, cx_a), &mut [(&client_c, cx_c)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    active_call_b
+        .update(cx_b, |active_call, cx| active_call.join_channel(zed_id, cx))
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |active_call, cx| active_call.join_channel(zed_id, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // Record, after every participant change observed by user C, which of the
+    // two channels user A appears in.
+    let observations = Rc::new(RefCell::new(Vec::new()));
+    cx_c.update(|cx| {
+        let observations = observations.clone();
+        cx.subscribe(
+            client_c.channel_store(),
+            move |channel_store, event: &ChannelEvent, cx| {
+                if let ChannelEvent::ParticipantsChanged { .. } = event {
+                    let channel_store = channel_store.read(cx);
+                    let in_channel = |channel_id| {
+                        channel_store
+                            .channel_participants(channel_id)
+                            .iter()
+                            .any(|user| user.id == user_a)
+                    };
+                    observations
+                        .borrow_mut()
+                        .push((in_channel(zed_id), in_channel(rust_id)));
+                }
+            },
+        )
+        .detach();
+    });
+
+    active_call_a
+        .update(cx_a, |active_call, cx| {
+            active_call.join_channel(rust_id, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let observations = observations.borrow();
+    assert!(!observations.is_empty());
+    assert!(
+        observations
+            .iter()
+            .all(|(in_zed, in_rust)| *in_zed || *in_rust),
+        "user A was observed in neither channel: {observations:?}"
+    );
+
+    cx_c.read(|cx| {
+        client_c.channel_store().read_with(cx, |channels, _| {
+            assert_participants_eq(channels.channel_participants(zed_id), &[user_b]);
+            assert_participants_eq(channels.channel_participants(rust_id), &[user_a]);
+        })
+    });
+}
+
+#[gpui::test]
+async fn test_decline_all_channel_invitations(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let user_b = client_b.user_id().unwrap();
+
+    let zed_id = server
+        .make_channel("zed", None, (&client_a, cx_a), &mut [])
+        .await;
+    let rust_id = server
+        .make_channel("rust", None, (&client_a, cx_a), &mut [])
+        .await;
+
+    for channel_id in [zed_id, rust_id] {
+        client_a
+            .channel_store()
+            .update(cx_a, |channel_store, cx| {
+                channel_store.invite_member(channel_id, user_b, proto::ChannelRole::Member, cx)
+            })
+            .await
+            .unwrap();
+    }
+    executor.run_until_parked();
+
+    assert_channel_invitations(
+        client_b.channel_store(),
+        cx_b,
+        &[
+            ExpectedChannel {
+                depth: 0,
+                id: zed_id,
+                name: "zed".into(),
+            },
+            ExpectedChannel {
+                depth: 0,
+                id: rust_id,
+                name: "rust".into(),
+            },
+        ],
+    );
+
+    client_b
+        .channel_store()
+        .update(cx_b, |channel_store, cx| {
+            channel_store.decline_all_invitations(cx)
+        })
+        .await
+        .unwrap();
+
+    assert_channel_invitations(client_b.channel_store(), cx_b, &[]);
+
+    executor.run_until_parked();
+    assert_channel_invitations(client_b.channel_store(), cx_b, &[]);
+    assert_channels(client_b.channel_store(), cx_b, &[]);
+    assert_members_eq(
+        &client_a
+            .channel_store()
+            .update(cx_a, |channel_store, cx| {
+                channel_store.fuzzy_search_members(zed_id, "".to_string(), 10, cx)
+            })
+            .await
+            .unwrap(),
+        &[(
+            client_a.user_id().unwrap(),
+            proto::ChannelRole::Admin,
+            proto::channel_member::Kind::Member,
+        )],
+    );
+}
+
+#[gpui::test]
+async fn test_channel_invitees_hidden_from_non_admins(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let user_a = client_a.user_id().unwrap();
+    let user_b = client_b.user_id().unwrap();
+    let user_c = client_c.user_id().unwrap();
+
+    let channel_id = server
+        .make_channel("zed", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.invite_member(channel_id, user_c, proto::ChannelRole::Member, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // The admin sees the pending invitation.
+    let members = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.fuzzy_search_members(channel_id, "".to_string(), 10, cx)
+        })
+        .await
+        .unwrap();
+    assert_members_eq(
+        &members,
+        &[
+            (
+                user_a,
+                proto::ChannelRole::Admin,
+                proto::channel_member::Kind::Member,
+            ),
+            (
+                user_b,
+                proto::ChannelRole::Member,
+                proto::channel_member::Kind::Member,
+            ),
+            (
+                user_c,
+                proto::ChannelRole::Member,
+                proto::channel_member::Kind::Invitee,
+            ),
+        ],
+    );
+
+    // Other members only see those who have accepted.
+    let members = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| {
+            store.fuzzy_search_members(channel_id, "".to_string(), 10, cx)
+        })
+        .await
+        .unwrap();
+    assert_members_eq(
+        &members,
+        &[
+            (
+                user_a,
+                proto::ChannelRole::Admin,
+                proto::channel_member::Kind::Member,
+            ),
+            (
+                user_b,
+                proto::ChannelRole::Member,
+                proto::channel_member::Kind::Member,
+            ),
+        ],
+    );
+}
+
+#[gpui::test]
+async fn test_channel_participants_resync_after_reconnect(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let user_a = client_a.user_id().unwrap();
+    let user_c = client_c.user_id().unwrap();
+
+    let zed_id = server
+        .make_channel(
+            "zed",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b), (&client_c, cx_c)],
+        )
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_c = cx_c.read(ActiveCall::global);
+
+    active_call_a
+        .update(cx_a, |active_call, cx| active_call.join_channel(zed_id, cx))
+        .await
+        .unwrap();
+    active_call_c
+        .update(cx_c, |active_call, cx| active_call.join_channel(zed_id, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    cx_b.read(|cx| {
+        client_b.channel_store().read_with(cx, |channels, _| {
+            assert_participants_eq(channels.channel_participants(zed_id), &[user_a, user_c]);
+        })
+    });
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    cx_b.update(|cx| {
+        let events = events.clone();
+        cx.subscribe(
+            client_b.channel_store(),
+            move |_, event: &ChannelEvent, _| {
+                if let ChannelEvent::ParticipantsChanged {
+                    channel_id,
+                    added,
+                    removed,
+                } = event
+                {
+                    events
+                        .borrow_mut()
+                        .push((*channel_id, added.clone(), removed.clone()));
+                }
+            },
+        )
+        .detach();
+    });
+
+    // User C leaves while user B is disconnected, so B misses the update.
+    server.forbid_connections();
+    server.disconnect_client(client_b.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT);
+
+    active_call_c
+        .update(cx_c, |active_call, cx| active_call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    server.allow_connections();
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    executor.run_until_parked();
+
+    cx_b.read(|cx| {
+        client_b.channel_store().read_with(cx, |channels, _| {
+            assert_participants_eq(channels.channel_participants(zed_id), &[user_a]);
+        })
+    });
+    assert_eq!(
+        events.borrow_mut().drain(..).collect::<Vec<_>>(),
+        [(zed_id, vec![], vec![user_c])]
+    );
+}
+
+#[gpui::test]
+async fn test_channel_max_participants(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel("zed", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.set_channel_max_participants(channel_id, Some(1), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        assert_eq!(
+            store.channel_for_id(channel_id).unwrap().max_participants,
+            Some(1)
+        );
+    });
+
+    // Limits that can't be stored are rejected rather than wrapping around.
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.set_channel_max_participants(channel_id, Some(u32::MAX), cx)
+        })
+        .await
+        .unwrap_err();
+    executor.run_until_parked();
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        assert_eq!(
+            store.channel_for_id(channel_id).unwrap().max_participants,
+            Some(1)
+        );
+    });
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+    active_call_a
+        .update(cx_a, |call, cx| call.join_channel(channel_id, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let error = active_call_b
+        .update(cx_b, |call, cx| call.join_channel(channel_id, cx))
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), ErrorCode::ChannelFull);
+    assert_eq!(error.error_tag("max_participants"), Some("1"));
+    executor.run_until_parked();
+    assert!(active_call_b.read_with(cx_b, |call, _| call.room().is_none()));
+
+    // Once there's room again, B can join.
+    active_call_a
+        .update(cx_a, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    active_call_b
+        .update(cx_b, |call, cx| call.join_channel(channel_id, cx))
+        .await
+        .unwrap();
+}
+
+#[gpui::test]
+async fn test_channel_invitation_events(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    cx_b.update(|cx| {
+        let events = events.clone();
+        cx.subscribe(
+            client_b.channel_store(),
+            move |_, event: &ChannelEvent, _| match event {
+                ChannelEvent::InvitationReceived { channel_id } => {
+                    events.borrow_mut().push(("received", *channel_id))
+                }
+                ChannelEvent::InvitationRemoved { channel_id } => {
+                    events.borrow_mut().push(("removed", *channel_id))
+                }
+                _ => {}
+            },
+        )
+        .detach();
+    });
+
+    let channel_id = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.create_channel("zed", None, cx))
+        .await
+        .unwrap();
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.invite_member(
+                channel_id,
+                client_b.user_id().unwrap(),
+                proto::ChannelRole::Member,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(events.take(), [("received", channel_id)]);
+
+    client_b
+        .channel_store()
+        .update(cx_b, |store, cx| {
+            store.respond_to_channel_invite(channel_id, false, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert_eq!(events.take(), [("removed", channel_id)]);
+}
+
+#[gpui::test]
+async fn test_channel_invitations_across_reconnect(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let user_b = client_b.user_id().unwrap();
+
+    let mut channel_ids = Vec::new();
+    for name in ["kept", "withdrawn", "new"] {
+        channel_ids.push(
+            client_a
+                .channel_store()
+                .update(cx_a, |store, cx| store.create_channel(name, None, cx))
+                .await
+                .unwrap(),
+        );
+    }
+    let [kept_id, withdrawn_id, new_id] = channel_ids[..] else {
+        unreachable!()
+    };
+    for channel_id in [kept_id, withdrawn_id] {
+        client_a
+            .channel_store()
+            .update(cx_a, |store, cx| {
+                store.invite_member(channel_id, user_b, proto::ChannelRole::Member, cx)
+            })
+            .await
+            .unwrap();
+    }
+    executor.run_until_parked();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    cx_b.update(|cx| {
+        let events = events.clone();
+        cx.subscribe(
+            client_b.channel_store(),
+            move |_, event: &ChannelEvent, _| match event {
+                ChannelEvent::InvitationReceived { channel_id } => {
+                    events.borrow_mut().push(("received", *channel_id))
+                }
+                ChannelEvent::InvitationRemoved { channel_id } => {
+                    events.borrow_mut().push(("removed", *channel_id))
+                }
+                _ => {}
+            },
+        )
+        .detach();
+    });
+
+    // Invitations change while user B is disconnected, so B misses the updates.
+    server.forbid_connections();
+    server.disconnect_client(client_b.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.remove_member(withdrawn_id, user_b, cx)
+        })
+        .await
+        .unwrap();
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.invite_member(new_id, user_b, proto::ChannelRole::Member, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // The pending invitations stay visible while reconnecting.
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        let invitation_ids = store
+            .channel_invitations()
+            .iter()
+            .map(|channel| channel.id)
+            .collect::<Vec<_>>();
+        assert_eq!(invitation_ids, [kept_id, withdrawn_id]);
+    });
+
+    server.allow_connections();
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    executor.run_until_parked();
+
+    // Only the invitations that changed are reported.
+    assert_eq!(
+        events.take(),
+        [("removed", withdrawn_id), ("received", new_id)]
+    );
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        let invitation_ids = store
+            .channel_invitations()
+            .iter()
+            .map(|channel| channel.id)
+            .collect::<Vec<_>>();
+        assert_eq!(invitation_ids, [kept_id, new_id]);
+    });
+}
+
+#[gpui::test]
+async fn test_channel_description(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.create_channel_with_description(
+                "zed",
+                Some("  Where the editor gets built  ".into()),
+                None,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.invite_member(
+                channel_id,
+                client_b.user_id().unwrap(),
+                proto::ChannelRole::Member,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // The invitee sees the description before accepting.
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        assert_eq!(
+            store.channel_invitations()[0].description.as_deref(),
+            Some("Where the editor gets built")
+        );
+    });
+
+    client_b
+        .channel_store()
+        .update(cx_b, |store, cx| {
+            store.respond_to_channel_invite(channel_id, true, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        assert_eq!(
+            store
+                .channel_for_id(channel_id)
+                .unwrap()
+                .description
+                .as_deref(),
+            Some("Where the editor gets built")
+        );
+    });
+
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.set_channel_description(channel_id, Some("The Zed editor".into()), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        assert_eq!(
+            store
+                .channel_for_id(channel_id)
+                .unwrap()
+                .description
+                .as_deref(),
+            Some("The Zed editor")
+        );
+    });
+
+    // Descriptions are a single line, and only admins can change them.
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.set_channel_description(channel_id, Some("two\nlines".into()), cx)
+        })
+        .await
+        .unwrap_err();
+    let error = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| {
+            store.set_channel_description(channel_id, Some("mine now".into()), cx)
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), ErrorCode::Forbidden);
+
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.set_channel_description(channel_id, None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        assert_eq!(store.channel_for_id(channel_id).unwrap().description, None);
+    });
+}
+
+#[gpui::test]
+async fn test_channel_activity(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let zed_id = server
+        .make_channel("zed", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    active_call_a
+        .update(cx_a, |active_call, cx| active_call.join_channel(zed_id, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    client_a.channel_store().read_with(cx_a, |store, _| {
+        assert!(!store.channel_has_activity(zed_id));
+    });
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        assert!(store.channel_has_activity(zed_id));
+    });
+
+    active_call_b
+        .update(cx_b, |active_call, cx| active_call.join_channel(zed_id, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    client_b.channel_store().read_with(cx_b, |store, _| {
+        assert!(!store.channel_has_activity(zed_id));
+    });
+}
+
+#[gpui::test]
+async fn test_create_channel_with_idempotency_key(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    // Creations with the same key that are in flight at the same time share a request.
+    let (create_1, create_2) = client_a.channel_store().update(cx_a, |store, cx| {
+        (
+            store.create_channel_with_idempotency_key("zed", None, "key-1".into(), cx),
+            store.create_channel_with_idempotency_key("zed", None, "key-1".into(), cx),
+        )
+    });
+    let channel_id = create_1.await.unwrap();
+    assert_eq!(create_2.await.unwrap(), channel_id);
+
+    // A resent request returns the channel that was already created.
+    let retried_channel_id = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.create_channel_with_idempotency_key("zed", None, "key-1".into(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(retried_channel_id, channel_id);
+    executor.run_until_parked();
+
+    client_a.channel_store().read_with(cx_a, |store, _| {
+        assert_eq!(
+            store
+                .channels()
+                .map(|channel| channel.id)
+                .collect::<Vec<_>>(),
+            [channel_id]
+        );
+    });
+
+    // Keys are scoped to the user that created the channel.
+    let channel_b_id = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| {
+            store.create_channel_with_idempotency_key("zed", None, "key-1".into(), cx)
+        })
+        .await
+        .unwrap();
+    assert_ne!(channel_b_id, channel_id);
+
+    // A resent request reports where the channel was created, even when the
+    // resent request names a different parent.
+    let subchannel_id = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.create_channel_with_idempotency_key("sub", Some(channel_id), "key-2".into(), cx)
+        })
+        .await
+        .unwrap();
+    let retried_subchannel_id = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.create_channel_with_idempotency_key("sub", None, "key-2".into(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(retried_subchannel_id, subchannel_id);
+    executor.run_until_parked();
+    client_a.channel_store().read_with(cx_a, |store, _| {
+        assert_eq!(
+            store.channel_for_id(subchannel_id).unwrap().parent_path,
+            [channel_id]
+        );
+    });
+
+    // Errors keep their error code.
+    let error = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| {
+            store.create_channel_with_idempotency_key("mine", Some(channel_id), "key-3".into(), cx)
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), ErrorCode::Forbidden);
+}
+
+#[gpui::test]
+async fn test_channel_notification_levels(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let zed_id = server
+        .make_channel("zed", None, (&client_a, cx_a), &mut [(&client_b, cx_b)])
+        .await;
+
+    client_a.channel_store().read_with(cx_a, |store, _| {
+        assert_eq!(
+            store.notification_level(zed_id),
+            proto::ChannelNotificationLevel::All
+        );
+    });
+
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.set_channel_notifications(zed_id, proto::ChannelNotificationLevel::None, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // The level is restored from the server when the client resyncs.
+    server.forbid_connections();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    server.allow_connections();
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    executor.run_until_parked();
+
+    client_a.channel_store().read_with(cx_a, |store, _| {
+        assert_eq!(
+            store.notification_level(zed_id),
+            proto::ChannelNotificationLevel::None
+        );
+    });
+
+    // Activity in a muted channel isn't reported.
+    let active_call_b = cx_b.read(ActiveCall::global);
+    active_call_b
+        .update(cx_b, |active_call, cx| active_call.join_channel(zed_id, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    client_a.channel_store().read_with(cx_a, |store, _| {
+        assert!(!store.channel_has_activity(zed_id));
+    });
+
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| {
+            store.set_channel_notifications(zed_id, proto::ChannelNotificationLevel::All, cx)
+        })
+        .await
+        .unwrap();
+    client_a.channel_store().read_with(cx_a, |store, _| {
+        assert!(store.channel_has_activity(zed_id));
+    });
+}
+
+#[gpui::test]
+async fn test_archive_channel(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_a_id = server
+        .make_channel(
+            "channel-a",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+    let channel_b_id = server
+        .make_channel(
+            "channel-b",
+            Some(channel_a_id),
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    // Members can't archive channels.
+    let error = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.archive_channel(channel_a_id, cx))
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), ErrorCode::Forbidden);
+
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.archive_channel(channel_a_id, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // Archiving a channel hides it and its descendants, but keeps them around.
+    for (client, cx) in [(&client_a, &mut *cx_a), (&client_b, &mut *cx_b)] {
+        client.channel_store().read_with(cx, |store, _| {
+            assert_eq!(store.channels().count(), 0);
+            let mut archived_channel_ids = store
+                .archived_channels()
+                .iter()
+                .map(|channel| channel.id)
+                .collect::<Vec<_>>();
+            archived_channel_ids.sort();
+            assert_eq!(archived_channel_ids, [channel_a_id, channel_b_id]);
+            assert!(store.channel_for_id(channel_a_id).unwrap().archived);
+            assert!(store.is_channel_archived(channel_b_id));
+        });
+    }
+
+    client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.unarchive_channel(channel_a_id, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    for (client, cx) in [(&client_a, &mut *cx_a), (&client_b, &mut *cx_b)] {
+        client.channel_store().read_with(cx, |store, _| {
+            assert_eq!(store.channels().count(), 2);
+            assert!(store.archived_channels().is_empty());
+            assert!(!store.is_channel_archived(channel_b_id));
+        });
+    }
+}
+
 #[derive(Debug, PartialEq)]
 struct ExpectedChannel {
     depth: usize,
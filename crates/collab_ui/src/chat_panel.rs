@@ -412,10 +412,10 @@ impl ChatPanel {
                     && duration_since_last_message < Duration::from_secs(5 * 60);
 
                 if let ChannelMessageId::Saved(id) = this_message.id {
-                    if this_message
-                        .mentions
-                        .iter()
-                        .any(|(_, user_id)| Some(*user_id) == self.client.user_id())
+                    if self
+                        .client
+                        .user_id()
+                        .is_some_and(|user_id| this_message.mentions_user(user_id))
                     {
                         active_chat.acknowledge_message(id);
                     }
@@ -435,10 +435,10 @@ impl ChatPanel {
             ChannelMessageId::Pending(id) => ("pending-message", id).into(),
         };
 
-        let mentioning_you = message
-            .mentions
-            .iter()
-            .any(|m| Some(m.1) == self.client.user_id());
+        let mentioning_you = self
+            .client
+            .user_id()
+            .is_some_and(|user_id| message.mentions_user(user_id));
 
         let message_id = match message.id {
             ChannelMessageId::Saved(id) => Some(id),
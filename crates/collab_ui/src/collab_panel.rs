@@ -358,6 +358,11 @@ impl CollabPanel {
                             });
                         }
                     }
+                    ChannelEvent::ParticipantsChanged { .. }
+                    | ChannelEvent::InvitationReceived { .. }
+                    | ChannelEvent::InvitationRemoved { .. }
+                    | ChannelEvent::ParticipantConnectionQualityChanged { .. }
+                    | ChannelEvent::TypingParticipantsChanged { .. } => {}
                 },
             ));
 
@@ -598,6 +603,9 @@ impl CollabPanel {
             let mut collapse_depth = None;
             for mat in matches {
                 let channel = channel_store.channel_at_index(mat.candidate_id).unwrap();
+                if channel_store.is_channel_archived(channel.id) {
+                    continue;
+                }
                 let depth = channel.parent_path.len();
 
                 if collapse_depth.is_none() && self.is_channel_collapsed(channel.id) {
@@ -1635,14 +1643,14 @@ impl CollabPanel {
                             "Failed to create channel",
                             window,
                             cx,
-                            |_, _, _| None,
+                            |e, _, _| create_channel_error_message(e),
                         );
                     } else {
                         create.detach_and_prompt_err(
                             "Failed to create channel",
                             window,
                             cx,
-                            |_, _, _| None,
+                            |e, _, _| create_channel_error_message(e),
                         );
                     }
                     cx.notify();
@@ -1906,7 +1914,7 @@ impl CollabPanel {
     ) {
         self.channel_store
             .update(cx, |channel_store, cx| {
-                channel_store.move_channel(channel_id, to, cx)
+                channel_store.move_channel(channel_id, Some(to), cx)
             })
             .detach_and_prompt_err("Failed to move channel", window, cx, |e, _, _| {
                 match e.error_code() {
@@ -1919,6 +1927,7 @@ impl CollabPanel {
                     ErrorCode::WrongMoveTarget => {
                         Some("You cannot move a channel into a different root channel".into())
                     }
+                    ErrorCode::ChannelTooDeep => Some(channel_too_deep_message(e)),
                     _ => None,
                 }
             })
@@ -2880,6 +2889,20 @@ impl CollabPanel {
     }
 }
 
+fn create_channel_error_message(error: &anyhow::Error) -> Option<String> {
+    match error.error_code() {
+        ErrorCode::ChannelTooDeep => Some(channel_too_deep_message(error)),
+        _ => None,
+    }
+}
+
+fn channel_too_deep_message(error: &anyhow::Error) -> String {
+    match error.error_tag("max_depth") {
+        Some(max_depth) => format!("Channels cannot be nested more than {max_depth} levels deep."),
+        None => "Channels cannot be nested this deeply.".to_string(),
+    }
+}
+
 fn render_tree_branch(
     is_last: bool,
     overdraw: bool,
@@ -366,7 +366,9 @@ impl PickerDelegate for ChannelModalDelegate {
                     Some(proto::channel_member::Kind::Invitee) => {
                         self.remove_member(selected_user.id, window, cx);
                     }
-                    Some(proto::channel_member::Kind::Member) => {}
+                    Some(
+                        proto::channel_member::Kind::Member | proto::channel_member::Kind::Guest,
+                    ) => {}
                     None => self.invite_member(selected_user, window, cx),
                 },
             }
@@ -442,6 +444,9 @@ impl PickerDelegate for ChannelModalDelegate {
                             Some(proto::channel_member::Kind::Member) => {
                                 slot.children(Some(Label::new("Member")))
                             }
+                            Some(proto::channel_member::Kind::Guest) => {
+                                slot.children(Some(Label::new("Guest")))
+                            }
                             _ => slot,
                         },
                     }
@@ -82,6 +82,7 @@ mod macos {
             .header("src/platform/mac/dispatch.h")
             .allowlist_var("_dispatch_main_q")
             .allowlist_var("_dispatch_source_type_data_add")
+            .allowlist_var("_dispatch_source_type_timer")
             .allowlist_var("DISPATCH_QUEUE_PRIORITY_HIGH")
             .allowlist_var("DISPATCH_TIME_NOW")
             .allowlist_function("dispatch_get_global_queue")
@@ -91,6 +92,7 @@ mod macos {
             .allowlist_function("dispatch_source_merge_data")
             .allowlist_function("dispatch_source_create")
             .allowlist_function("dispatch_source_set_event_handler_f")
+            .allowlist_function("dispatch_source_set_timer")
             .allowlist_function("dispatch_resume")
             .allowlist_function("dispatch_suspend")
             .allowlist_function("dispatch_source_cancel")
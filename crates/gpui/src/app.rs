@@ -1,6 +1,7 @@
 use std::{
     any::{TypeId, type_name},
     cell::{Ref, RefCell, RefMut},
+    collections::BTreeMap,
     marker::PhantomData,
     mem,
     ops::{Deref, DerefMut},
@@ -269,6 +270,7 @@ pub struct App {
     pub(crate) window_invalidators_by_entity:
         FxHashMap<EntityId, FxHashMap<WindowId, WindowInvalidator>>,
     pub(crate) tracked_entities: FxHashMap<WindowId, FxHashSet<EntityId>>,
+    pub(crate) pending_task_labels: Rc<RefCell<PendingTaskLabels>>,
     #[cfg(any(test, feature = "test-support", debug_assertions))]
     pub(crate) name: Option<&'static str>,
 }
@@ -332,6 +334,7 @@ impl App {
                 layout_id_buffer: Default::default(),
                 propagate_event: true,
                 prompt_builder: Some(PromptBuilder::Default),
+                pending_task_labels: Default::default(),
 
                 #[cfg(any(test, feature = "test-support", debug_assertions))]
                 name: None,
@@ -1065,6 +1068,17 @@ impl App {
             .spawn(async move { f(&mut cx).await })
     }
 
+    /// Returns the names of tasks spawned with [AsyncApp::spawn_labeled] that haven't completed
+    /// or been dropped yet, in the order they were spawned.
+    pub fn pending_tasks(&self) -> Vec<&'static str> {
+        self.pending_task_labels
+            .borrow()
+            .labels
+            .values()
+            .copied()
+            .collect()
+    }
+
     /// Schedules the given function to be run at the end of the current effect cycle, allowing entities
     /// that are currently on the stack to be returned to the app.
     pub fn defer(&mut self, f: impl FnOnce(&mut App) + 'static) {
@@ -1083,6 +1097,15 @@ impl App {
         &self.text_system
     }
 
+    /// Replaces the text system that windows opened afterwards shape text with.
+    #[cfg(test)]
+    pub(crate) fn set_platform_text_system(
+        &mut self,
+        text_system: Arc<dyn crate::PlatformTextSystem>,
+    ) {
+        self.text_system = Arc::new(TextSystem::new(text_system));
+    }
+
     /// Check whether a global of the given type has been assigned.
     pub fn has_global<G: Global>(&self) -> bool {
         self.globals_by_type.contains_key(&TypeId::of::<G>())
@@ -1226,6 +1249,10 @@ impl App {
 
     /// Observe the release of a entity. The callback is invoked after the entity
     /// has no more strong references but before it has been dropped.
+    ///
+    /// All release callbacks for an entity, including those registered with
+    /// [`Context::on_release`], run in the order they were registered, and are
+    /// passed the same live entity, which is dropped once the last of them returns.
     pub fn observe_release<T>(
         &self,
         handle: &Entity<T>,
@@ -1734,6 +1761,38 @@ impl AppContext for App {
     }
 }
 
+/// The names of outstanding tasks spawned with [AsyncApp::spawn_labeled].
+#[derive(Default)]
+pub(crate) struct PendingTaskLabels {
+    next_id: usize,
+    labels: BTreeMap<usize, &'static str>,
+}
+
+impl PendingTaskLabels {
+    pub(crate) fn insert(this: &Rc<RefCell<Self>>, name: &'static str) -> PendingTaskLabel {
+        let mut labels = this.borrow_mut();
+        let id = labels.next_id;
+        labels.next_id += 1;
+        labels.labels.insert(id, name);
+        PendingTaskLabel {
+            labels: this.clone(),
+            id,
+        }
+    }
+}
+
+/// Removes a task's name from [App::pending_tasks] when the task completes or is dropped.
+pub(crate) struct PendingTaskLabel {
+    labels: Rc<RefCell<PendingTaskLabels>>,
+    id: usize,
+}
+
+impl Drop for PendingTaskLabel {
+    fn drop(&mut self) {
+        self.labels.borrow_mut().labels.remove(&self.id);
+    }
+}
+
 /// These effects are processed at the end of each application update cycle.
 pub(crate) enum Effect {
     Notify {
@@ -1866,3 +1925,718 @@ impl HttpClient for NullHttpClient {
         type_name::<Self>()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::{Rc, Weak},
+        time::Duration,
+    };
+
+    use futures::{FutureExt as _, channel::mpsc};
+
+    use crate::{
+        self as gpui, AppContext, AsyncApp, AsyncWindowContext, BorrowAppContext, Context,
+        EmptyView, EventEmitter, Global, SubscriptionSet, TestAppContext, px,
+    };
+
+    struct Emitter;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Ping(usize);
+
+    impl EventEmitter<Ping> for Emitter {}
+
+    #[gpui::test]
+    fn test_same_event_type_from_different_emitters(cx: &mut TestAppContext) {
+        let emitter_a = cx.new(|_| Emitter);
+        let emitter_b = cx.new(|_| Emitter);
+
+        let received_a = Rc::new(RefCell::new(Vec::new()));
+        let received_b = Rc::new(RefCell::new(Vec::new()));
+
+        cx.update(|cx| {
+            let received_a = received_a.clone();
+            cx.subscribe(&emitter_a, move |_, event: &Ping, _| {
+                received_a.borrow_mut().push(event.0);
+            })
+            .detach();
+
+            let received_b = received_b.clone();
+            cx.subscribe(&emitter_b, move |_, event: &Ping, _| {
+                received_b.borrow_mut().push(event.0);
+            })
+            .detach();
+        });
+
+        // Emit from both entities within a single update so that both effects
+        // are flushed in the same cycle.
+        cx.update(|cx| {
+            emitter_a.update(cx, |_, cx| cx.emit(Ping(1)));
+            emitter_b.update(cx, |_, cx| cx.emit(Ping(2)));
+            emitter_a.update(cx, |_, cx| cx.emit(Ping(3)));
+        });
+
+        assert_eq!(*received_a.borrow(), vec![1, 3]);
+        assert_eq!(*received_b.borrow(), vec![2]);
+    }
+
+    #[gpui::test]
+    fn test_subscription_set(cx: &mut TestAppContext) {
+        let emitter = cx.new(|_| Emitter);
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let notifications = Rc::new(RefCell::new(0));
+
+        let mut subscriptions = SubscriptionSet::new();
+        cx.update(|cx| {
+            let events = events.clone();
+            subscriptions.push(cx.subscribe(&emitter, move |_, event: &Ping, _| {
+                events.borrow_mut().push(event.0)
+            }));
+            let notifications = notifications.clone();
+            subscriptions.push(cx.observe(&emitter, move |_, _| *notifications.borrow_mut() += 1));
+        });
+        assert_eq!(subscriptions.len(), 2);
+
+        emitter.update(cx, |_, cx| {
+            cx.emit(Ping(1));
+            cx.notify();
+        });
+        assert_eq!(*events.borrow(), vec![1]);
+        assert_eq!(*notifications.borrow(), 1);
+
+        // None of the handlers fire once the set is dropped.
+        drop(subscriptions);
+        emitter.update(cx, |_, cx| {
+            cx.emit(Ping(2));
+            cx.notify();
+        });
+        assert_eq!(*events.borrow(), vec![1]);
+        assert_eq!(*notifications.borrow(), 1);
+    }
+
+    #[gpui::test]
+    fn test_spawn_and_update(cx: &mut TestAppContext) {
+        struct Counter(usize);
+
+        let source = cx.new(|_| Counter(0));
+        let target = cx.new(|_| Counter(0));
+        source.update(cx, |_, cx| {
+            cx.spawn_and_update(
+                &target,
+                async |_, _| 5,
+                |target, value, _| target.0 += value,
+            )
+            .detach();
+        });
+        cx.run_until_parked();
+        target.read_with(cx, |target, _| assert_eq!(target.0, 5));
+
+        // Nothing is applied to a target released before the future completes.
+        let released_target = cx.new(|_| Counter(0));
+        source.update(cx, |_, cx| {
+            cx.spawn_and_update(
+                &released_target,
+                async |_, _| 1,
+                |_, _, _| panic!("target was released"),
+            )
+            .detach();
+        });
+        drop(released_target);
+        cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    fn test_background_spawn_from_async_app(cx: &mut TestAppContext) {
+        struct Counter(usize);
+
+        let counter = cx.new(|_| Counter(0));
+        cx.update(|cx| {
+            let counter = counter.clone();
+            cx.spawn(async move |cx| {
+                let sum = cx.background_spawn(async { (1..=10).sum::<usize>() }).await;
+                counter.update(cx, |counter, _| counter.0 = sum)
+            })
+            .detach();
+        });
+        cx.run_until_parked();
+        counter.read_with(cx, |counter, _| assert_eq!(counter.0, 55));
+    }
+
+    #[gpui::test]
+    fn test_nested_spawn_updates_global(cx: &mut TestAppContext) {
+        struct Count(usize);
+        impl Global for Count {}
+
+        cx.update(|cx| cx.set_global(Count(0)));
+        let task = cx.update(|cx| {
+            cx.spawn(async |cx| {
+                cx.update_global::<Count, _>(|count, _| count.0 += 1)?;
+                cx.spawn(async |cx| cx.update_global::<Count, _>(|count, _| count.0 += 1))
+                    .await?;
+                cx.update_global::<Count, _>(|count, _| count.0)
+            })
+        });
+        cx.run_until_parked();
+
+        assert_eq!(cx.update(|cx| cx.global::<Count>().0), 2);
+        assert_eq!(task.now_or_never().unwrap().unwrap(), 2);
+    }
+
+    #[gpui::test]
+    fn test_batched_update_from_async_app(cx: &mut TestAppContext) {
+        struct Counter(usize);
+
+        let counters = [
+            cx.new(|_| Counter(0)),
+            cx.new(|_| Counter(0)),
+            cx.new(|_| Counter(0)),
+        ];
+        let notifications = Rc::new(Cell::new(0));
+        let _subscriptions = cx.update(|cx| {
+            counters
+                .iter()
+                .map(|counter| {
+                    let notifications = notifications.clone();
+                    cx.observe(counter, move |_, _| {
+                        notifications.set(notifications.get() + 1)
+                    })
+                })
+                .collect::<Vec<_>>()
+        });
+
+        cx.to_async()
+            .update(|cx| {
+                for (ix, counter) in counters.iter().enumerate() {
+                    counter.update(cx, |counter, cx| {
+                        counter.0 = ix + 1;
+                        cx.notify();
+                    });
+                }
+                // Effects are only flushed once the whole batch has been applied.
+                assert_eq!(notifications.get(), 0);
+            })
+            .unwrap();
+
+        assert_eq!(notifications.get(), 3);
+        for (ix, counter) in counters.iter().enumerate() {
+            counter.read_with(cx, |counter, _| assert_eq!(counter.0, ix + 1));
+        }
+    }
+
+    #[gpui::test]
+    fn test_observe_window_bounds(cx: &mut TestAppContext) {
+        let window = cx.add_window(|_, _| EmptyView);
+        let mut async_cx = AsyncWindowContext::new_context(cx.to_async(), window.into());
+
+        let observed_bounds = Rc::new(RefCell::new(Vec::new()));
+        let subscription = async_cx
+            .observe_window_bounds({
+                let observed_bounds = observed_bounds.clone();
+                move |bounds, _| observed_bounds.borrow_mut().push(bounds)
+            })
+            .unwrap();
+
+        let size = gpui::size(px(300.), px(200.));
+        cx.simulate_window_resize(window.into(), size);
+        let bounds = cx.update(|cx| window.update(cx, |_, window, _| window.bounds()).unwrap());
+        assert_eq!(bounds.size, size);
+        assert_eq!(*observed_bounds.borrow(), vec![bounds]);
+
+        // The callback no longer fires once the subscription is dropped.
+        drop(subscription);
+        cx.simulate_window_resize(window.into(), gpui::size(px(400.), px(300.)));
+        assert_eq!(observed_bounds.borrow().len(), 1);
+    }
+
+    #[gpui::test]
+    #[should_panic(expected = "spawned task panicked")]
+    fn test_panic_in_spawned_task_is_not_lost(cx: &mut TestAppContext) {
+        let task = cx.update(|cx| cx.spawn::<_, ()>(async |_| panic!("spawned task panicked")));
+        cx.foreground_executor()
+            .spawn(async move { task.await })
+            .detach();
+        cx.run_until_parked();
+    }
+
+    #[gpui::test]
+    fn test_context_update(cx: &mut TestAppContext) {
+        struct Counter(usize);
+
+        let counter = cx.new(|_| Counter(0));
+        cx.update(|cx| {
+            let mut counter_cx = Context::new_context(cx, counter.downgrade());
+            let value = counter_cx
+                .update(|counter, _| {
+                    counter.0 += 1;
+                    counter.0
+                })
+                .unwrap();
+            assert_eq!(value, 1);
+        });
+        counter.read_with(cx, |counter, _| assert_eq!(counter.0, 1));
+
+        // Updating an entity from within its own update is detected rather than panicking.
+        let error = counter.update(cx, |counter, cx| {
+            counter.0 += 1;
+            cx.update(|_, _| ()).unwrap_err()
+        });
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "cannot update {} while it is already being updated",
+                std::any::type_name::<Counter>()
+            )
+        );
+        counter.read_with(cx, |counter, _| assert_eq!(counter.0, 2));
+    }
+
+    #[gpui::test]
+    fn test_observe_once_and_observe_while(cx: &mut TestAppContext) {
+        struct Counter(usize);
+        #[derive(Default)]
+        struct Observer {
+            once: usize,
+            while_below_three: Vec<usize>,
+        }
+
+        let counter = cx.new(|_| Counter(0));
+        let observer = cx.new(|cx| {
+            cx.observe_once(&counter, |this: &mut Observer, _, _| this.once += 1)
+                .detach();
+            cx.observe_while(&counter, |this: &mut Observer, counter, cx| {
+                let count = counter.read(cx).0;
+                this.while_below_three.push(count);
+                count < 3
+            })
+            .detach();
+            Observer::default()
+        });
+
+        for _ in 0..5 {
+            counter.update(cx, |counter, cx| {
+                counter.0 += 1;
+                cx.notify();
+            });
+        }
+
+        observer.read_with(cx, |observer, _| {
+            assert_eq!(observer.once, 1);
+            assert_eq!(observer.while_below_three, vec![1, 2, 3]);
+        });
+    }
+
+    #[gpui::test]
+    fn test_observe_if(cx: &mut TestAppContext) {
+        struct Counter(usize);
+
+        let counter = cx.new(|_| Counter(0));
+        let observer = cx.new(|cx| {
+            cx.observe_if(
+                &counter,
+                |counter| counter.0 > 3,
+                |seen: &mut Vec<usize>, counter, cx| seen.push(counter.read(cx).0),
+            )
+            .detach();
+            Vec::new()
+        });
+
+        for _ in 0..3 {
+            counter.update(cx, |counter, cx| {
+                counter.0 += 1;
+                cx.notify();
+            });
+        }
+        observer.read_with(cx, |seen, _| assert!(seen.is_empty()));
+
+        counter.update(cx, |counter, cx| {
+            counter.0 += 1;
+            cx.notify();
+        });
+        observer.read_with(cx, |seen, _| assert_eq!(*seen, vec![4]));
+    }
+
+    #[gpui::test]
+    fn test_subscribe_weak(cx: &mut TestAppContext) {
+        struct Subscriber;
+
+        let released = Rc::new(RefCell::new(Vec::new()));
+        let events = Rc::new(RefCell::new(Vec::new()));
+
+        // Dropping the emitter releases it and removes the subscription.
+        let emitter = cx.new(|_| Emitter);
+        let subscriber = cx.new(|_| Subscriber);
+        let subscription = subscriber.update(cx, |_, cx| {
+            let events = events.clone();
+            cx.subscribe_weak(&emitter, move |_, emitter, event: &Ping, _| {
+                assert!(emitter.upgrade().is_some());
+                events.borrow_mut().push(event.0);
+            })
+        });
+        emitter.update(cx, |_, cx| cx.emit(Ping(1)));
+        assert_eq!(*events.borrow(), vec![1]);
+
+        cx.update(|cx| {
+            let released = released.clone();
+            cx.observe_release(&emitter, move |_, _| released.borrow_mut().push("emitter"))
+                .detach();
+        });
+        drop(emitter);
+        cx.update(|_| {});
+        assert_eq!(*released.borrow(), vec!["emitter"]);
+        assert_eq!(Rc::strong_count(&events), 1, "subscription wasn't removed");
+        drop(subscription);
+
+        // Dropping the subscriber releases it, and the subscription is removed the next
+        // time the emitter emits.
+        let emitter = cx.new(|_| Emitter);
+        let subscription = subscriber.update(cx, |_, cx| {
+            let events = events.clone();
+            cx.subscribe_weak(&emitter, move |_, _, event: &Ping, _| {
+                events.borrow_mut().push(event.0);
+            })
+        });
+        cx.update(|cx| {
+            let released = released.clone();
+            cx.observe_release(&subscriber, move |_, _| {
+                released.borrow_mut().push("subscriber")
+            })
+            .detach();
+        });
+        drop(subscriber);
+        cx.update(|_| {});
+        assert_eq!(*released.borrow(), vec!["emitter", "subscriber"]);
+
+        emitter.update(cx, |_, cx| cx.emit(Ping(2)));
+        assert_eq!(*events.borrow(), vec![1]);
+        assert_eq!(Rc::strong_count(&events), 1, "subscription wasn't removed");
+        drop(subscription);
+    }
+
+    #[gpui::test]
+    fn test_effects_flush_in_order_after_update(cx: &mut TestAppContext) {
+        let emitter = cx.new(|_| Emitter);
+        let log = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let log = log.clone();
+            cx.subscribe(&emitter, move |_, event: &Ping, _| {
+                log.borrow_mut().push(format!("event {}", event.0))
+            })
+            .detach();
+        });
+
+        emitter.update(cx, |_, cx| {
+            cx.emit(Ping(1));
+            let deferred_log = log.clone();
+            cx.defer_update(move |_, _| deferred_log.borrow_mut().push("deferred".into()));
+            cx.emit(Ping(2));
+            assert!(log.borrow().is_empty());
+        });
+
+        // No executor is needed: effects have been flushed, in the order they were
+        // queued, once `update` returns.
+        assert_eq!(*log.borrow(), ["event 1", "deferred", "event 2"]);
+    }
+
+    #[gpui::test]
+    fn test_notifications_are_coalesced(cx: &mut TestAppContext) {
+        let emitter = cx.new(|_| Emitter);
+        let notifications = Rc::new(Cell::new(0));
+        cx.update(|cx| {
+            let notifications = notifications.clone();
+            cx.observe(&emitter, move |_, _| {
+                notifications.set(notifications.get() + 1)
+            })
+            .detach();
+        });
+
+        emitter.update(cx, |_, cx| {
+            cx.notify();
+            cx.notify();
+            cx.notify();
+        });
+        cx.run_until_parked();
+        assert_eq!(notifications.get(), 1);
+
+        // Notifying again in a later update notifies observers again.
+        emitter.update(cx, |_, cx| cx.notify());
+        cx.run_until_parked();
+        assert_eq!(notifications.get(), 2);
+    }
+
+    #[gpui::test]
+    fn test_update_default_global(cx: &mut TestAppContext) {
+        #[derive(Default)]
+        struct Count(usize);
+        impl Global for Count {}
+
+        let emitter = cx.new(|_| Emitter);
+        emitter.update(cx, |_, cx| {
+            assert!(!cx.has_global::<Count>());
+            cx.update_default_global(|count: &mut Count, _| count.0 += 1);
+        });
+        cx.to_async()
+            .update_default_global(|count: &mut Count, _| count.0 += 1)
+            .unwrap();
+        assert_eq!(cx.update(|cx| cx.global::<Count>().0), 2);
+    }
+
+    #[gpui::test]
+    fn test_release_observers_run_in_registration_order(cx: &mut TestAppContext) {
+        struct Released(Vec<&'static str>);
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let entity = cx.new(|_| Released(Vec::new()));
+        cx.update(|cx| {
+            for name in ["first", "second"] {
+                let log = log.clone();
+                cx.observe_release(&entity, move |released, _| {
+                    // Each observer sees the changes made by the ones before it.
+                    log.borrow_mut().push(released.0.clone());
+                    released.0.push(name);
+                })
+                .detach();
+            }
+        });
+        entity.update(cx, |_, cx| {
+            let log = log.clone();
+            cx.on_release(move |released, _| log.borrow_mut().push(released.0.clone()))
+                .detach();
+        });
+
+        drop(entity);
+        cx.update(|_| {});
+        assert_eq!(
+            *log.borrow(),
+            vec![vec![], vec!["first"], vec!["first", "second"]]
+        );
+    }
+
+    #[gpui::test]
+    fn test_forward_events_to(cx: &mut TestAppContext) {
+        struct Forwarder;
+
+        let emitter = cx.new(|_| Emitter);
+        let forwarder = cx.new(|_| Forwarder);
+        let (tx, mut rx) = mpsc::unbounded();
+        let _subscription = forwarder.update(cx, |_, cx| cx.forward_events_to(&emitter, tx));
+
+        emitter.update(cx, |_, cx| {
+            cx.emit(Ping(1));
+            cx.emit(Ping(2));
+        });
+        emitter.update(cx, |_, cx| cx.emit(Ping(3)));
+        assert_eq!(rx.try_next().unwrap(), Some(Ping(1)));
+        assert_eq!(rx.try_next().unwrap(), Some(Ping(2)));
+        assert_eq!(rx.try_next().unwrap(), Some(Ping(3)));
+        assert!(rx.try_next().is_err(), "channel should be empty but open");
+
+        // Releasing the emitter drops the sender, closing the channel.
+        drop(emitter);
+        cx.update(|_| {});
+        assert_eq!(rx.try_next().unwrap(), None);
+    }
+
+    #[gpui::test]
+    fn test_try_read_global(cx: &mut TestAppContext) {
+        struct Setting(&'static str);
+        impl Global for Setting {}
+
+        let async_cx = cx.to_async();
+        assert_eq!(
+            async_cx.try_read_global(|setting: &Setting, _| setting.0),
+            None
+        );
+
+        cx.update(|cx| cx.set_global(Setting("value")));
+        assert_eq!(
+            async_cx.try_read_global(|setting: &Setting, _| setting.0),
+            Some("value")
+        );
+
+        let released_cx = AsyncApp {
+            app: Weak::new(),
+            background_executor: cx.executor(),
+            foreground_executor: cx.foreground_executor().clone(),
+        };
+        assert_eq!(
+            released_cx.try_read_global(|setting: &Setting, _| setting.0),
+            None
+        );
+    }
+
+    #[gpui::test]
+    fn test_update_or_wait(cx: &mut TestAppContext) {
+        let window = cx.add_window(|_, _| EmptyView);
+        let window_id = window.window_id();
+        let async_cx = AsyncWindowContext::new_context(cx.to_async(), window.into());
+
+        // While a window is being opened, its slot is reserved but empty.
+        let opening_window = cx.update(|cx| cx.windows.get_mut(window_id).unwrap().take().unwrap());
+        let ran = Rc::new(Cell::new(false));
+        let task = cx.foreground_executor().spawn({
+            let mut async_cx = async_cx.clone();
+            let ran = ran.clone();
+            async move {
+                async_cx
+                    .update_or_wait(Duration::from_secs(1), move |_, _| ran.set(true))
+                    .await
+            }
+        });
+        cx.run_until_parked();
+        assert!(!ran.get());
+
+        cx.update(|cx| {
+            cx.windows
+                .get_mut(window_id)
+                .unwrap()
+                .replace(opening_window)
+        });
+        cx.executor().advance_clock(Duration::from_millis(16));
+        cx.run_until_parked();
+        assert!(ran.get());
+        assert!(task.now_or_never().unwrap().is_ok());
+
+        // Waiting gives up once the timeout elapses.
+        let opening_window = cx.update(|cx| cx.windows.get_mut(window_id).unwrap().take().unwrap());
+        let task = cx.foreground_executor().spawn({
+            let mut async_cx = async_cx.clone();
+            async move {
+                async_cx
+                    .update_or_wait(Duration::from_millis(100), |_, _| {
+                        panic!("window isn't available")
+                    })
+                    .await
+            }
+        });
+        cx.run_until_parked();
+        cx.executor().advance_clock(Duration::from_millis(200));
+        cx.run_until_parked();
+        assert!(task.now_or_never().unwrap().is_err());
+        cx.update(|cx| {
+            cx.windows
+                .get_mut(window_id)
+                .unwrap()
+                .replace(opening_window)
+        });
+    }
+
+    #[gpui::test]
+    fn test_spawn_labeled(cx: &mut TestAppContext) {
+        let (first_tx, first_rx) = futures::channel::oneshot::channel::<()>();
+        let (second_tx, second_rx) = futures::channel::oneshot::channel::<()>();
+        let async_cx = cx.to_async();
+        let first = async_cx.spawn_labeled("first", async move |_| first_rx.await.ok());
+        let second = async_cx.spawn_labeled("second", async move |_| second_rx.await.ok());
+        cx.run_until_parked();
+        assert_eq!(cx.update(|cx| cx.pending_tasks()), ["first", "second"]);
+
+        first_tx.send(()).unwrap();
+        cx.run_until_parked();
+        assert_eq!(first.now_or_never().unwrap(), Some(()));
+        assert_eq!(cx.update(|cx| cx.pending_tasks()), ["second"]);
+
+        // Dropping a task removes it from the list as well.
+        drop(second);
+        drop(second_tx);
+        cx.run_until_parked();
+        assert!(cx.update(|cx| cx.pending_tasks()).is_empty());
+    }
+
+    #[gpui::test]
+    fn test_spawn_with_timeout(cx: &mut TestAppContext) {
+        let async_cx = cx.to_async();
+        let task = async_cx.spawn_with_timeout(Duration::from_millis(100), async |_| 1);
+        cx.run_until_parked();
+        assert_eq!(task.now_or_never().unwrap(), Ok(1));
+
+        let (tx, mut rx) = futures::channel::oneshot::channel::<()>();
+        let task = async_cx.spawn_with_timeout(Duration::from_millis(100), async move |_| {
+            let _tx = tx;
+            futures::future::pending::<()>().await
+        });
+        cx.run_until_parked();
+        cx.executor().advance_clock(Duration::from_millis(50));
+        cx.run_until_parked();
+        assert_eq!(rx.try_recv(), Ok(None));
+
+        cx.executor().advance_clock(Duration::from_millis(50));
+        cx.run_until_parked();
+        assert_eq!(
+            task.now_or_never().unwrap(),
+            Err(gpui::TimeoutError(Duration::from_millis(100)))
+        );
+        // The future that timed out was dropped.
+        assert_eq!(rx.try_recv(), Err(futures::channel::oneshot::Canceled));
+    }
+
+    #[gpui::test]
+    fn test_async_app_windows(cx: &mut TestAppContext) {
+        let window_a = cx.add_window(|_, _| EmptyView);
+        let window_b = cx.add_window(|_, _| EmptyView);
+        let async_cx = cx.to_async();
+        let window_ids = || {
+            let mut ids = async_cx
+                .windows()
+                .unwrap()
+                .into_iter()
+                .map(|window| window.window_id())
+                .collect::<Vec<_>>();
+            ids.sort();
+            ids
+        };
+
+        let mut expected = vec![window_a.window_id(), window_b.window_id()];
+        expected.sort();
+        assert_eq!(window_ids(), expected);
+
+        window_a
+            .update(cx, |_, window, _| window.remove_window())
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(window_ids(), [window_b.window_id()]);
+    }
+
+    #[gpui::test]
+    fn test_context_spawn(cx: &mut TestAppContext) {
+        struct Counter {
+            count: usize,
+            task: Option<gpui::Task<()>>,
+        }
+
+        let counter = cx.new(|_| Counter {
+            count: 0,
+            task: None,
+        });
+        let (tx, rx) = futures::channel::oneshot::channel::<()>();
+        counter.update(cx, |_, cx| {
+            cx.spawn(async move |this, cx| {
+                rx.await.ok();
+                this.update(cx, |this, _| this.count += 1).ok();
+            })
+            .detach();
+        });
+        cx.run_until_parked();
+        counter.read_with(cx, |counter, _| assert_eq!(counter.count, 0));
+
+        tx.send(()).unwrap();
+        cx.run_until_parked();
+        counter.read_with(cx, |counter, _| assert_eq!(counter.count, 1));
+
+        // A task owned by the entity is dropped when the entity is released.
+        let (tx, mut rx) = futures::channel::oneshot::channel::<()>();
+        counter.update(cx, |counter, cx| {
+            counter.task = Some(cx.spawn(async move |_, _| {
+                let _tx = tx;
+                futures::future::pending::<()>().await
+            }));
+        });
+        cx.run_until_parked();
+        assert_eq!(rx.try_recv(), Ok(None));
+
+        drop(counter);
+        cx.run_until_parked();
+        assert_eq!(rx.try_recv(), Err(futures::channel::oneshot::Canceled));
+    }
+}
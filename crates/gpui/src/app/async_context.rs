@@ -1,14 +1,20 @@
 use crate::{
     AnyView, AnyWindowHandle, App, AppCell, AppContext, BackgroundExecutor, BorrowAppContext,
-    Entity, EventEmitter, Focusable, ForegroundExecutor, Global, PromptLevel, Render, Reservation,
-    Result, Subscription, Task, VisualContext, Window, WindowHandle,
+    Bounds, Entity, EventEmitter, Focusable, ForegroundExecutor, Global, Pixels, PromptLevel,
+    Render, Reservation, Result, Subscription, Task, VisualContext, Window, WindowHandle,
 };
 use anyhow::{Context as _, anyhow};
 use derive_more::{Deref, DerefMut};
 use futures::channel::oneshot;
-use std::{future::Future, rc::Weak};
+use std::{future::Future, rc::Weak, time::Duration};
 
-use super::{Context, WeakEntity};
+use super::{Context, PendingTaskLabels, WeakEntity};
+
+/// The error returned by a task spawned with [`AsyncApp::spawn_with_timeout`]
+/// that didn't complete in time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("task timed out after {0:?}")]
+pub struct TimeoutError(pub Duration);
 
 /// An async-friendly version of [App] with a static lifetime so it can be held across `await` points in async code.
 /// You're provided with an instance when calling [App::spawn], and you can also create one with [App::to_async].
@@ -144,7 +150,17 @@ impl AsyncApp {
         &self.foreground_executor
     }
 
+    /// Returns handles to all open windows in the application. See [`App::windows`].
+    pub fn windows(&self) -> Result<Vec<AnyWindowHandle>> {
+        let app = self.app.upgrade().context("app was released")?;
+        let lock = app.borrow();
+        Ok(lock.windows())
+    }
+
     /// Invoke the given function in the context of the app, then flush any effects produced during its invocation.
+    ///
+    /// Prefer this over several [`AppContext::update_entity`] calls when applying a batch of
+    /// changes, since the app is only borrowed once and effects are flushed once at the end.
     pub fn update<R>(&self, f: impl FnOnce(&mut App) -> R) -> Result<R> {
         let app = self
             .app
@@ -191,7 +207,14 @@ impl AsyncApp {
         lock.open_window(options, build_root_view)
     }
 
-    /// Schedule a future to be polled in the background.
+    /// Schedule a future to be polled on the main thread.
+    ///
+    /// The app isn't borrowed while the task is created, so the future is free to
+    /// update the app, or spawn further tasks, as soon as it starts running.
+    ///
+    /// A panic in the future isn't caught: it unwinds out of the executor polling
+    /// the task, which in tests means out of the call that ran the task, such as
+    /// `run_until_parked`.
     #[track_caller]
     pub fn spawn<AsyncFn, R>(&self, f: AsyncFn) -> Task<R>
     where
@@ -203,6 +226,49 @@ impl AsyncApp {
             .spawn(async move { f(&mut cx).await })
     }
 
+    /// Like [`AsyncApp::spawn`], but the task is listed under the given name in
+    /// [`App::pending_tasks`] until it completes or is dropped.
+    #[track_caller]
+    pub fn spawn_labeled<AsyncFn, R>(&self, name: &'static str, f: AsyncFn) -> Task<R>
+    where
+        AsyncFn: AsyncFnOnce(&mut AsyncApp) -> R + 'static,
+        R: 'static,
+    {
+        let label = self
+            .app
+            .upgrade()
+            .map(|app| PendingTaskLabels::insert(&app.borrow().pending_task_labels, name));
+        let mut cx = self.clone();
+        self.foreground_executor.spawn(async move {
+            let _label = label;
+            f(&mut cx).await
+        })
+    }
+
+    /// Like [`AsyncApp::spawn`], but the task resolves to a [`TimeoutError`] if the
+    /// future hasn't completed once `timeout` elapses. The future is dropped at that point.
+    #[track_caller]
+    pub fn spawn_with_timeout<AsyncFn, R>(
+        &self,
+        timeout: Duration,
+        f: AsyncFn,
+    ) -> Task<Result<R, TimeoutError>>
+    where
+        AsyncFn: AsyncFnOnce(&mut AsyncApp) -> R + 'static,
+        R: 'static,
+    {
+        use futures::FutureExt as _;
+        use smol::future::FutureExt as _;
+
+        let mut cx = self.clone();
+        let timer = self.background_executor.timer(timeout);
+        self.foreground_executor.spawn(async move {
+            async { Ok(f(&mut cx).await) }
+                .race(timer.map(|_| Err(TimeoutError(timeout))))
+                .await
+        })
+    }
+
     /// Determine whether global state of the specified type has been assigned.
     /// Returns an error if the `App` has been dropped.
     pub fn has_global<G: Global>(&self) -> Result<bool> {
@@ -229,13 +295,13 @@ impl AsyncApp {
 
     /// Reads the global state of the specified type, passing it to the given callback.
     ///
-    /// Similar to [`AsyncApp::read_global`], but returns an error instead of panicking
+    /// Similar to [`AsyncApp::read_global`], but returns `None` instead of panicking
     /// if no state of the specified type has been assigned.
     ///
-    /// Returns an error if no state of the specified type has been assigned the `App` has been dropped.
+    /// Returns `None` if no state of the specified type has been assigned or the `App` has been dropped.
     pub fn try_read_global<G: Global, R>(&self, read: impl FnOnce(&G, &App) -> R) -> Option<R> {
         let app = self.app.upgrade()?;
-        let app = app.borrow_mut();
+        let app = app.borrow();
         Some(read(app.try_global()?, &app))
     }
 
@@ -253,6 +319,20 @@ impl AsyncApp {
         Ok(app.update(|cx| cx.update_global(update)))
     }
 
+    /// A convenience method for [`BorrowAppContext::update_default_global`],
+    /// which assigns the default value of the global first if it hasn't been assigned yet.
+    pub fn update_default_global<G: Global + Default, R>(
+        &self,
+        update: impl FnOnce(&mut G, &mut App) -> R,
+    ) -> Result<R> {
+        let app = self
+            .app
+            .upgrade()
+            .ok_or_else(|| anyhow!("app was released"))?;
+        let mut app = app.borrow_mut();
+        Ok(app.update(|cx| cx.update_default_global(update)))
+    }
+
     /// Run something using this entity and cx, when the returned struct is dropped
     pub fn on_drop<T: 'static, Callback: FnOnce(&mut T, &mut Context<T>) + 'static>(
         &self,
@@ -293,6 +373,33 @@ impl AsyncWindowContext {
             .update_window(self.window, |_, window, cx| update(window, cx))
     }
 
+    /// Like [`AsyncWindowContext::update`], but if the window can't be accessed yet,
+    /// e.g. because it's still being opened, retries once per frame until `timeout`
+    /// elapses. Returns an error if the window is still unavailable by then, or if
+    /// the app has been released.
+    pub async fn update_or_wait<R>(
+        &mut self,
+        timeout: Duration,
+        update: impl FnOnce(&mut Window, &mut App) -> R,
+    ) -> Result<R> {
+        // An unavailable window can't schedule `on_next_frame` callbacks, so retries
+        // are paced by a timer of roughly one frame instead.
+        const RETRY_INTERVAL: Duration = Duration::from_millis(16);
+
+        let deadline = self.background_executor.now() + timeout;
+        let mut update = Some(update);
+        loop {
+            let error = match self.update(|window, cx| (update.take().unwrap())(window, cx)) {
+                Ok(result) => return Ok(result),
+                Err(error) => error,
+            };
+            if self.app.app.upgrade().is_none() || self.background_executor.now() >= deadline {
+                return Err(error);
+            }
+            self.background_executor.timer(RETRY_INTERVAL).await;
+        }
+    }
+
     /// A convenience method for [`App::update_window`].
     pub fn update_root<R>(
         &mut self,
@@ -308,6 +415,25 @@ impl AsyncWindowContext {
             .ok();
     }
 
+    /// Register a callback to be invoked whenever the window is resized or moved.
+    /// The callback is provided the window's new bounds.
+    pub fn observe_window_bounds(
+        &mut self,
+        mut callback: impl FnMut(Bounds<Pixels>, &mut App) + 'static,
+    ) -> Result<Subscription> {
+        self.window.update(self, |_, window, _| {
+            let (subscription, activate) = window.bounds_observers.insert(
+                (),
+                Box::new(move |window, cx| {
+                    callback(window.bounds(), cx);
+                    true
+                }),
+            );
+            activate();
+            subscription
+        })
+    }
+
     /// A convenience method for [`App::global`].
     pub fn read_global<G: Global, R>(
         &mut self,
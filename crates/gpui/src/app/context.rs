@@ -3,9 +3,9 @@ use crate::{
     FocusHandle, FocusOutEvent, Focusable, Global, KeystrokeObserver, Reservation, SubscriberSet,
     Subscription, Task, WeakEntity, WeakFocusHandle, Window, WindowHandle,
 };
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use derive_more::{Deref, DerefMut};
-use futures::FutureExt;
+use futures::{FutureExt, channel::mpsc};
 use std::{
     any::{Any, TypeId},
     borrow::{Borrow, BorrowMut},
@@ -47,6 +47,29 @@ impl<'a, T: 'static> Context<'a, T> {
         self.entity_state.clone()
     }
 
+    /// Updates the entity belonging to this context, then flushes any effects produced
+    /// during the update.
+    ///
+    /// Returns an error instead of panicking if the entity is already being updated
+    /// further up the stack, which is the case within its own update callbacks.
+    pub fn update<R>(&mut self, update: impl FnOnce(&mut T, &mut Context<T>) -> R) -> Result<R> {
+        let entity = self.entity();
+        self.app.update(|cx| {
+            let mut lease = cx.entities.try_lease(&entity).with_context(|| {
+                format!(
+                    "cannot update {} while it is already being updated",
+                    std::any::type_name::<T>()
+                )
+            })?;
+            let result = update(
+                &mut lease,
+                &mut Context::new_context(cx, entity.downgrade()),
+            );
+            cx.entities.end_lease(lease);
+            Ok(result)
+        })
+    }
+
     /// Arranges for the given function to be called whenever [`Context::notify`] is
     /// called with the given entity.
     pub fn observe<W>(
@@ -69,6 +92,68 @@ impl<'a, T: 'static> Context<'a, T> {
         })
     }
 
+    /// Like [`Context::observe`], but the callback returns whether to keep observing.
+    /// Once it returns `false`, the observer is removed and won't be called again.
+    pub fn observe_while<W>(
+        &mut self,
+        entity: &Entity<W>,
+        mut on_notify: impl FnMut(&mut T, Entity<W>, &mut Context<T>) -> bool + 'static,
+    ) -> Subscription
+    where
+        T: 'static,
+        W: 'static,
+    {
+        let this = self.weak_entity();
+        self.app.observe_internal(entity, move |e, cx| {
+            this.update(cx, |this, cx| on_notify(this, e, cx))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Like [`Context::observe`], but the callback is only called for the next
+    /// notification, after which the observer is removed.
+    pub fn observe_once<W>(
+        &mut self,
+        entity: &Entity<W>,
+        on_notify: impl FnOnce(&mut T, Entity<W>, &mut Context<T>) + 'static,
+    ) -> Subscription
+    where
+        T: 'static,
+        W: 'static,
+    {
+        let mut on_notify = Some(on_notify);
+        self.observe_while(entity, move |this, e, cx| {
+            if let Some(on_notify) = on_notify.take() {
+                on_notify(this, e, cx);
+            }
+            false
+        })
+    }
+
+    /// Like [`Context::observe`], but the callback is only called for notifications
+    /// after which `predicate` holds for the observed entity's state.
+    pub fn observe_if<W>(
+        &mut self,
+        entity: &Entity<W>,
+        predicate: impl Fn(&W) -> bool + 'static,
+        mut on_notify: impl FnMut(&mut T, Entity<W>, &mut Context<T>) + 'static,
+    ) -> Subscription
+    where
+        T: 'static,
+        W: 'static,
+    {
+        let this = self.weak_entity();
+        self.app.observe_internal(entity, move |e, cx| {
+            let Some(this) = this.upgrade() else {
+                return false;
+            };
+            if predicate(e.read(cx)) {
+                this.update(cx, |this, cx| on_notify(this, e, cx));
+            }
+            true
+        })
+    }
+
     /// Subscribe to an event type from another entity
     pub fn subscribe<T2, Evt>(
         &mut self,
@@ -91,6 +176,46 @@ impl<'a, T: 'static> Context<'a, T> {
         })
     }
 
+    /// Like [`Context::subscribe`], but the callback is given a weak handle to the emitter.
+    ///
+    /// The subscription keeps neither this entity nor the emitter alive, and is removed
+    /// as soon as either of them is released.
+    pub fn subscribe_weak<T2, Evt>(
+        &mut self,
+        entity: &Entity<T2>,
+        mut on_event: impl FnMut(&mut T, WeakEntity<T2>, &Evt, &mut Context<T>) + 'static,
+    ) -> Subscription
+    where
+        T: 'static,
+        T2: 'static + EventEmitter<Evt>,
+        Evt: 'static,
+    {
+        let this = self.weak_entity();
+        self.app.subscribe_internal(entity, move |e, event, cx| {
+            let e = e.downgrade();
+            this.update(cx, |this, cx| on_event(this, e, event, cx))
+                .is_ok()
+        })
+    }
+
+    /// Forward every event of the given type emitted by another entity to a channel.
+    ///
+    /// The sender is dropped, and forwarding stops, when the emitter is released, when
+    /// the subscription is dropped, or once the receiver has been closed.
+    pub fn forward_events_to<T2, Evt>(
+        &mut self,
+        entity: &Entity<T2>,
+        tx: mpsc::UnboundedSender<Evt>,
+    ) -> Subscription
+    where
+        T2: 'static + EventEmitter<Evt>,
+        Evt: 'static + Clone,
+    {
+        self.app.subscribe_internal(entity, move |_, event, _| {
+            tx.unbounded_send(event.clone()).is_ok()
+        })
+    }
+
     /// Subscribe to an event type from ourself
     pub fn subscribe_self<Evt>(
         &mut self,
@@ -192,13 +317,16 @@ impl<'a, T: 'static> Context<'a, T> {
     }
 
     /// Tell GPUI that this entity has changed and observers of it should be notified.
+    ///
+    /// Calling this several times before effects are flushed only notifies observers once.
     pub fn notify(&mut self) {
         self.app.notify(self.entity_state.entity_id);
     }
 
     /// Spawn the future returned by the given function.
     /// The function is provided a weak handle to the entity owned by this context and a context that can be held across await points.
-    /// The returned task must be held or detached.
+    /// The returned task must be held or detached. Storing it on the entity ties the task to the
+    /// entity's lifetime, since the task is cancelled when the entity is released and drops it.
     #[track_caller]
     pub fn spawn<AsyncFn, R>(&self, f: AsyncFn) -> Task<R>
     where
@@ -210,6 +338,31 @@ impl<'a, T: 'static> Context<'a, T> {
         self.app.spawn(async move |cx| f(this, cx).await)
     }
 
+    /// Spawn the future returned by the given function, then apply its output to another entity.
+    /// The function is provided the same arguments as in [`Context::spawn`]. If `target` has been
+    /// released by the time the future completes, its output is dropped and `then` is not called.
+    #[track_caller]
+    pub fn spawn_and_update<U, AsyncFn, R>(
+        &self,
+        target: &Entity<U>,
+        f: AsyncFn,
+        then: impl FnOnce(&mut U, R, &mut Context<U>) + 'static,
+    ) -> Task<()>
+    where
+        T: 'static,
+        U: 'static,
+        AsyncFn: AsyncFnOnce(WeakEntity<T>, &mut AsyncApp) -> R + 'static,
+        R: 'static,
+    {
+        let target = target.downgrade();
+        self.spawn(async move |this, cx| {
+            let output = f(this, cx).await;
+            target
+                .update(cx, |target, cx| then(target, output, cx))
+                .ok();
+        })
+    }
+
     /// Convenience method for accessing view state in an event callback.
     ///
     /// Many GPUI callbacks take the form of `Fn(&E, &mut Window, &mut AppContext)`,
@@ -254,6 +407,18 @@ impl<'a, T: 'static> Context<'a, T> {
         window.on_next_frame(move |window, cx| view.update(cx, |view, cx| f(view, window, cx)));
     }
 
+    /// Schedules the given function to update this entity at the end of the current effect cycle,
+    /// after the effects already queued, such as emitted events and notifications, have been handled.
+    ///
+    /// Effects are flushed synchronously once the outermost update returns, so tests can assert on
+    /// their outcome right after calling `update`, without running the executor.
+    pub fn defer_update(&mut self, f: impl FnOnce(&mut T, &mut Context<T>) + 'static) {
+        let this = self.weak_entity();
+        self.app.defer(move |cx| {
+            this.update(cx, f).ok();
+        });
+    }
+
     /// Schedules the given function to be run at the end of the current effect cycle, allowing entities
     /// that are currently on the stack to be returned to the app.
     pub fn defer_in(
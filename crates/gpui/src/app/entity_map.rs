@@ -106,20 +106,22 @@ impl EntityMap {
     /// Move an entity to the stack.
     #[track_caller]
     pub fn lease<'a, T>(&mut self, pointer: &'a Entity<T>) -> Lease<'a, T> {
+        self.try_lease(pointer)
+            .unwrap_or_else(|| double_lease_panic::<T>("update"))
+    }
+
+    /// Move an entity to the stack, returning `None` if it's already on the stack.
+    pub fn try_lease<'a, T>(&mut self, pointer: &'a Entity<T>) -> Option<Lease<'a, T>> {
         self.assert_valid_context(pointer);
         let mut accessed_entities = self.accessed_entities.borrow_mut();
         accessed_entities.insert(pointer.entity_id);
 
-        let entity = Some(
-            self.entities
-                .remove(pointer.entity_id)
-                .unwrap_or_else(|| double_lease_panic::<T>("update")),
-        );
-        Lease {
-            entity,
+        let entity = self.entities.remove(pointer.entity_id)?;
+        Some(Lease {
+            entity: Some(entity),
             pointer,
             entity_type: PhantomData,
-        }
+        })
     }
 
     /// Returns an entity after moving it to the stack.
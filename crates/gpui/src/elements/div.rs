@@ -16,12 +16,12 @@
 //! constructed by combining these two systems into an all-in-one element.
 
 use crate::{
-    Action, AnyDrag, AnyElement, AnyTooltip, AnyView, App, Bounds, ClickEvent, DispatchPhase,
-    Element, ElementId, Entity, FocusHandle, Global, GlobalElementId, Hitbox, HitboxId,
-    IntoElement, IsZero, KeyContext, KeyDownEvent, KeyUpEvent, LayoutId, ModifiersChangedEvent,
-    MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, Pixels, Point,
-    Render, ScrollWheelEvent, SharedString, Size, Style, StyleRefinement, Styled, Task, TooltipId,
-    Visibility, Window, point, px, size,
+    Action, AnyDrag, AnyElement, AnyTooltip, AnyView, App, BackgroundTag, Bounds, ClickEvent,
+    DispatchPhase, Element, ElementId, Entity, Fill, FocusHandle, Global, GlobalElementId, Hitbox,
+    HitboxId, Hsla, IntoElement, IsZero, KeyContext, KeyDownEvent, KeyUpEvent, LayoutId,
+    ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
+    ParentElement, Pixels, Point, Render, Rgba, ScrollWheelEvent, SharedString, Size, Style,
+    StyleRefinement, Styled, Task, TooltipId, Visibility, Window, point, px, size,
 };
 use collections::HashMap;
 use refineable::Refineable;
@@ -35,13 +35,13 @@ use std::{
     mem,
     rc::Rc,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use taffy::style::Overflow;
 use util::ResultExt;
 
 const DRAG_THRESHOLD: f64 = 2.;
-const TOOLTIP_SHOW_DELAY: Duration = Duration::from_millis(500);
+pub(crate) const TOOLTIP_SHOW_DELAY: Duration = Duration::from_millis(500);
 const HOVERABLE_TOOLTIP_HIDE_DELAY: Duration = Duration::from_millis(500);
 
 /// The styling information for a given group.
@@ -537,6 +537,13 @@ impl Interactivity {
         });
     }
 
+    /// Show this element's tooltip after the mouse has hovered over it for the given duration,
+    /// rather than the default delay.
+    /// The imperative API equivalent to [`StatefulInteractiveElement::tooltip_delay`]
+    pub fn tooltip_delay(&mut self, delay: Duration) {
+        self.tooltip_delay = Some(delay);
+    }
+
     /// Block the mouse from interacting with this element or any of its children
     /// The imperative API equivalent to [`InteractiveElement::occlude`]
     pub fn occlude_mouse(&mut self) {
@@ -556,6 +563,13 @@ pub trait InteractiveElement: Sized {
         self
     }
 
+    /// Give this element an accessible name, which describes it to assistive technologies in
+    /// place of its visible content. See [`Window::accessibility_nodes`].
+    fn aria_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.interactivity().aria_label = Some(label.into());
+        self
+    }
+
     /// Assign this element an ID, so that it can be used with interactivity
     fn id(mut self, id: impl Into<ElementId>) -> Stateful<Self> {
         self.interactivity().element_id = Some(id.into());
@@ -973,6 +987,18 @@ pub trait StatefulInteractiveElement: InteractiveElement {
         self
     }
 
+    /// Animate changes to the background color of this element, such as when its hover or
+    /// active styles start or stop applying, over the given duration.
+    ///
+    /// Only solid backgrounds are animated; other changes to the background apply immediately.
+    fn transition_fill(mut self, duration: Duration) -> Self
+    where
+        Self: Sized,
+    {
+        self.interactivity().fill_transition = Some(duration);
+        self
+    }
+
     /// Set the given styles to be applied when this element's group is active.
     fn group_active(
         mut self,
@@ -1058,6 +1084,14 @@ pub trait StatefulInteractiveElement: InteractiveElement {
         self.interactivity().hoverable_tooltip(build_tooltip);
         self
     }
+
+    /// Show this element's tooltip after the mouse has hovered over it for the given duration,
+    /// rather than the default delay.
+    /// The fluent API equivalent to [`Interactivity::tooltip_delay`]
+    fn tooltip_delay(mut self, delay: Duration) -> Self {
+        self.interactivity().tooltip_delay(delay);
+        self
+    }
 }
 
 /// A trait for providing focus related APIs to interactive elements
@@ -1164,6 +1198,45 @@ impl Div {
 /// bounds of the children after the layout phase is complete.
 pub struct DivFrameState {
     child_layout_ids: SmallVec<[LayoutId; 2]>,
+    /// The indices of the children sorted by z-index, or empty if none of them has one and they
+    /// are painted in the order they were added.
+    child_paint_order: SmallVec<[usize; 2]>,
+}
+
+impl DivFrameState {
+    fn new(child_layout_ids: SmallVec<[LayoutId; 2]>, window: &Window) -> Self {
+        let mut child_paint_order = SmallVec::new();
+        if child_layout_ids
+            .iter()
+            .any(|layout_id| window.layout_z_index(*layout_id).is_some())
+        {
+            child_paint_order.extend(0..child_layout_ids.len());
+            // The sort is stable, so children with equal z-indices keep their order.
+            child_paint_order.sort_by_key(|ix: &usize| {
+                window
+                    .layout_z_index(child_layout_ids[*ix])
+                    .unwrap_or_default()
+            });
+        }
+        Self {
+            child_layout_ids,
+            child_paint_order,
+        }
+    }
+
+    fn for_each_child_in_paint_order(
+        &self,
+        children: &mut [AnyElement],
+        mut f: impl FnMut(&mut AnyElement),
+    ) {
+        if self.child_paint_order.is_empty() {
+            children.iter_mut().for_each(f);
+        } else {
+            for ix in &self.child_paint_order {
+                f(&mut children[*ix]);
+            }
+        }
+    }
 }
 
 impl Styled for Div {
@@ -1211,7 +1284,7 @@ impl Element for Div {
                         window.request_layout(style, child_layout_ids.iter().copied(), cx)
                     })
                 });
-        (layout_id, DivFrameState { child_layout_ids })
+        (layout_id, DivFrameState::new(child_layout_ids, window))
     }
 
     fn prepaint(
@@ -1268,9 +1341,9 @@ impl Element for Div {
             cx,
             |_style, scroll_offset, hitbox, window, cx| {
                 window.with_element_offset(scroll_offset, |window| {
-                    for child in &mut self.children {
+                    request_layout.for_each_child_in_paint_order(&mut self.children, |child| {
                         child.prepaint(window, cx);
-                    }
+                    });
                 });
 
                 if let Some(listener) = self.prepaint_listener.as_ref() {
@@ -1286,7 +1359,7 @@ impl Element for Div {
         &mut self,
         global_id: Option<&GlobalElementId>,
         bounds: Bounds<Pixels>,
-        _request_layout: &mut Self::RequestLayoutState,
+        request_layout: &mut Self::RequestLayoutState,
         hitbox: &mut Option<Hitbox>,
         window: &mut Window,
         cx: &mut App,
@@ -1298,9 +1371,9 @@ impl Element for Div {
             window,
             cx,
             |_style, window, cx| {
-                for child in &mut self.children {
-                    child.paint(window, cx);
-                }
+                request_layout.for_each_child_in_paint_order(&mut self.children, |child| {
+                    child.paint(window, cx)
+                });
             },
         );
     }
@@ -1343,6 +1416,7 @@ pub struct Interactivity {
     pub(crate) group_hover_style: Option<GroupStyle>,
     pub(crate) active_style: Option<Box<StyleRefinement>>,
     pub(crate) group_active_style: Option<GroupStyle>,
+    pub(crate) fill_transition: Option<Duration>,
     pub(crate) drag_over_styles: Vec<(
         TypeId,
         Box<dyn Fn(&dyn Any, &mut Window, &mut App) -> StyleRefinement>,
@@ -1362,7 +1436,9 @@ pub struct Interactivity {
     pub(crate) drag_listener: Option<(Arc<dyn Any>, DragListener)>,
     pub(crate) hover_listener: Option<Box<dyn Fn(&bool, &mut Window, &mut App)>>,
     pub(crate) tooltip_builder: Option<TooltipBuilder>,
+    pub(crate) tooltip_delay: Option<Duration>,
     pub(crate) occlude_mouse: bool,
+    pub(crate) aria_label: Option<SharedString>,
 
     #[cfg(debug_assertions)]
     pub(crate) location: Option<core::panic::Location<'static>>,
@@ -1500,6 +1576,7 @@ impl Interactivity {
             || self.tracked_focus_handle.is_some()
             || self.hover_style.is_some()
             || self.group_hover_style.is_some()
+            || self.fill_transition.is_some()
             || self.hover_listener.is_some()
             || !self.mouse_up_listeners.is_empty()
             || !self.mouse_down_listeners.is_empty()
@@ -1603,6 +1680,10 @@ impl Interactivity {
                     return ((), element_state);
                 }
 
+                if let Some(label) = self.aria_label.clone() {
+                    window.insert_accessibility_node(label, bounds);
+                }
+
                 window.with_element_opacity(style.opacity, |window| {
                     style.paint(bounds, window, cx, |window: &mut Window, cx: &mut App| {
                         window.with_text_style(style.text_style().cloned(), |window| {
@@ -2044,6 +2125,7 @@ impl Interactivity {
                 register_tooltip_mouse_handlers(
                     &active_tooltip,
                     self.tooltip_id,
+                    self.tooltip_delay.unwrap_or(TOOLTIP_SHOW_DELAY),
                     build_tooltip,
                     check_is_hovered,
                     check_is_hovered_during_prepaint,
@@ -2296,10 +2378,55 @@ impl Interactivity {
                     style.refine(active_style)
                 }
             }
+            drop(clicked_state);
+
+            // Only the style computed for painting has the hover styles applied.
+            if let Some(duration) = self.fill_transition.filter(|_| hitbox.is_some()) {
+                Self::apply_fill_transition(&mut style, duration, element_state, window, cx);
+            }
         }
 
         style
     }
+
+    fn apply_fill_transition(
+        style: &mut Style,
+        duration: Duration,
+        element_state: &mut InteractiveElementState,
+        window: &mut Window,
+        cx: &App,
+    ) {
+        let Some(Fill::Color(background)) = style.background.as_mut() else {
+            element_state.fill_transition = None;
+            return;
+        };
+        if background.tag != BackgroundTag::Solid {
+            element_state.fill_transition = None;
+            return;
+        }
+
+        let now = cx.background_executor().now();
+        let target = background.solid;
+        let transition = element_state.fill_transition.get_or_insert(FillTransition {
+            from: target,
+            to: target,
+            start: now,
+        });
+        if transition.to != target {
+            // Start from whatever is currently painted, so that reversing a transition
+            // midway doesn't jump.
+            *transition = FillTransition {
+                from: transition.color_at(now, duration),
+                to: target,
+                start: now,
+            };
+        }
+
+        background.solid = transition.color_at(now, duration);
+        if background.solid != target {
+            window.request_animation_frame();
+        }
+    }
 }
 
 /// The per-frame state of an interactive element. Used for tracking stateful interactions like clicks
@@ -2312,6 +2439,41 @@ pub struct InteractiveElementState {
     pub(crate) pending_mouse_down: Option<Rc<RefCell<Option<MouseDownEvent>>>>,
     pub(crate) scroll_offset: Option<Rc<RefCell<Point<Pixels>>>>,
     pub(crate) active_tooltip: Option<Rc<RefCell<Option<ActiveTooltip>>>>,
+    pub(crate) fill_transition: Option<FillTransition>,
+}
+
+/// An in-progress change of an element's background color, see
+/// [`StatefulInteractiveElement::transition_fill`].
+#[derive(Clone, Copy)]
+pub(crate) struct FillTransition {
+    from: Hsla,
+    to: Hsla,
+    start: Instant,
+}
+
+impl FillTransition {
+    fn color_at(&self, now: Instant, duration: Duration) -> Hsla {
+        let progress = if duration.is_zero() {
+            1.
+        } else {
+            (now.saturating_duration_since(self.start).as_secs_f32() / duration.as_secs_f32())
+                .min(1.)
+        };
+        if progress >= 1. {
+            return self.to;
+        }
+
+        let from = Rgba::from(self.from);
+        let to = Rgba::from(self.to);
+        let mix = |from: f32, to: f32| from + (to - from) * progress;
+        Rgba {
+            r: mix(from.r, to.r),
+            g: mix(from.g, to.g),
+            b: mix(from.b, to.b),
+            a: mix(from.a, to.a),
+        }
+        .into()
+    }
 }
 
 /// Whether or not the element or a group that contains it is clicked by the mouse.
@@ -2390,6 +2552,7 @@ pub(crate) fn set_tooltip_on_window(
 pub(crate) fn register_tooltip_mouse_handlers(
     active_tooltip: &Rc<RefCell<Option<ActiveTooltip>>>,
     tooltip_id: Option<TooltipId>,
+    show_delay: Duration,
     build_tooltip: Rc<dyn Fn(&mut Window, &mut App) -> Option<(AnyView, bool)>>,
     check_is_hovered: Rc<dyn Fn(&Window) -> bool>,
     check_is_hovered_during_prepaint: Rc<dyn Fn(&Window) -> bool>,
@@ -2402,6 +2565,7 @@ pub(crate) fn register_tooltip_mouse_handlers(
         move |_: &MouseMoveEvent, phase, window, cx| {
             handle_tooltip_mouse_move(
                 &active_tooltip,
+                show_delay,
                 &build_tooltip,
                 &check_is_hovered,
                 &check_is_hovered_during_prepaint,
@@ -2444,6 +2608,7 @@ pub(crate) fn register_tooltip_mouse_handlers(
 /// gets occluded after display, it will stick around until the mouse exits the hover bounds.
 fn handle_tooltip_mouse_move(
     active_tooltip: &Rc<RefCell<Option<ActiveTooltip>>>,
+    show_delay: Duration,
     build_tooltip: &Rc<dyn Fn(&mut Window, &mut App) -> Option<(AnyView, bool)>>,
     check_is_hovered: &Rc<dyn Fn(&Window) -> bool>,
     check_is_hovered_during_prepaint: &Rc<dyn Fn(&Window) -> bool>,
@@ -2494,7 +2659,7 @@ fn handle_tooltip_mouse_move(
                 let build_tooltip = build_tooltip.clone();
                 let check_is_hovered_during_prepaint = check_is_hovered_during_prepaint.clone();
                 async move |cx| {
-                    cx.background_executor().timer(TOOLTIP_SHOW_DELAY).await;
+                    cx.background_executor().timer(show_delay).await;
                     cx.update(|window, cx| {
                         let new_tooltip =
                             build_tooltip(window, cx).map(|(view, tooltip_is_hoverable)| {
@@ -2998,3 +3163,322 @@ impl ScrollHandle {
         self.0.borrow().child_bounds.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        self as gpui, BoxShadow, Context, Corners, Edges, Modifiers, ScrollDelta, TestAppContext,
+        VisualTestContext, blue, red,
+    };
+
+    struct FillTransitionView;
+
+    impl Render for FillTransitionView {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .id("fill")
+                .size(px(100.))
+                .bg(red())
+                .hover(|style| style.bg(blue()))
+                .transition_fill(Duration::from_millis(100))
+        }
+    }
+
+    fn painted_fill(cx: &mut VisualTestContext) -> Rgba {
+        cx.update(|window, _| {
+            assert_eq!(window.rendered_frame.scene.quads.len(), 1);
+            window.rendered_frame.scene.quads[0].background.solid.into()
+        })
+    }
+
+    fn advance_frame(cx: &mut VisualTestContext, duration: Duration) {
+        cx.executor().advance_clock(duration);
+        cx.update(|window, _| window.refresh());
+    }
+
+    #[gpui::test]
+    fn test_fill_transition(cx: &mut TestAppContext) {
+        let (_, cx) = cx.add_window_view(|_, _| FillTransitionView);
+        assert_eq!(painted_fill(cx), red().into());
+
+        cx.simulate_mouse_move(point(px(50.), px(50.)), None, Modifiers::none());
+        assert_eq!(painted_fill(cx), red().into());
+
+        advance_frame(cx, Duration::from_millis(50));
+        let fill = painted_fill(cx);
+        assert!((fill.r - 0.5).abs() < 0.01, "{fill:?}");
+        assert!((fill.b - 0.5).abs() < 0.01, "{fill:?}");
+
+        advance_frame(cx, Duration::from_millis(60));
+        assert_eq!(painted_fill(cx), blue().into());
+
+        // Leaving the element animates back to the base fill.
+        cx.simulate_mouse_move(point(px(150.), px(150.)), None, Modifiers::none());
+        advance_frame(cx, Duration::from_millis(25));
+        let fill = painted_fill(cx);
+        assert!((fill.r - 0.25).abs() < 0.01, "{fill:?}");
+        assert!((fill.b - 0.75).abs() < 0.01, "{fill:?}");
+        advance_frame(cx, Duration::from_millis(100));
+        assert_eq!(painted_fill(cx), red().into());
+    }
+
+    struct BorderView;
+
+    impl Render for BorderView {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .id("card")
+                .size(px(100.))
+                .border_2()
+                .border_color(red())
+                .rounded(px(6.))
+                .hover(|style| style.border_color(blue()))
+        }
+    }
+
+    #[gpui::test]
+    fn test_hover_border(cx: &mut TestAppContext) {
+        let (_, cx) = cx.add_window_view(|_, _| BorderView);
+        let painted_border = |cx: &mut VisualTestContext| {
+            cx.update(|window, _| {
+                let scale_factor = window.scale_factor();
+                let quad = &window.rendered_frame.scene.quads[0];
+                assert_eq!(quad.border_widths, Edges::all(px(2.).scale(scale_factor)));
+                assert_eq!(quad.corner_radii, Corners::all(px(6.).scale(scale_factor)));
+                Rgba::from(quad.border_color)
+            })
+        };
+        assert_eq!(painted_border(cx), red().into());
+
+        cx.simulate_mouse_move(point(px(50.), px(50.)), None, Modifiers::none());
+        assert_eq!(painted_border(cx), blue().into());
+
+        cx.simulate_mouse_move(point(px(150.), px(150.)), None, Modifiers::none());
+        assert_eq!(painted_border(cx), red().into());
+    }
+
+    struct ShadowView;
+
+    impl Render for ShadowView {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div().size(px(50.)).bg(red()).shadow(smallvec::smallvec![
+                BoxShadow {
+                    color: blue(),
+                    offset: point(px(0.), px(4.)),
+                    blur_radius: px(8.),
+                    spread_radius: px(0.),
+                },
+                BoxShadow {
+                    color: red(),
+                    offset: point(px(2.), px(8.)),
+                    blur_radius: px(16.),
+                    spread_radius: px(1.),
+                },
+            ])
+        }
+    }
+
+    #[gpui::test]
+    fn test_box_shadows(cx: &mut TestAppContext) {
+        let (_, cx) = cx.add_window_view(|_, _| ShadowView);
+        cx.update(|window, _| {
+            let scale_factor = window.scale_factor();
+            let scene = &window.rendered_frame.scene;
+            assert_eq!(scene.shadows.len(), 2);
+            assert_eq!(scene.quads.len(), 1);
+
+            let (first, second) = (&scene.shadows[0], &scene.shadows[1]);
+            assert_eq!(
+                first.bounds,
+                Bounds::new(point(px(0.), px(4.)), size(px(50.), px(50.))).scale(scale_factor)
+            );
+            assert_eq!(first.blur_radius, px(8.).scale(scale_factor));
+            assert_eq!(
+                second.bounds,
+                Bounds::new(point(px(1.), px(7.)), size(px(52.), px(52.))).scale(scale_factor)
+            );
+            assert_eq!(second.blur_radius, px(16.).scale(scale_factor));
+
+            // Shadows are painted in the order they were given, behind the element's fill.
+            assert!(first.order < second.order);
+            assert!(second.order < scene.quads[0].order);
+        });
+    }
+
+    struct TooltipView;
+
+    impl Render for TooltipView {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div().debug_selector(|| "tooltip".into()).size(px(20.))
+        }
+    }
+
+    struct TooltipTargetView;
+
+    impl Render for TooltipTargetView {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .id("target")
+                .size(px(100.))
+                .tooltip(|_, cx| cx.new(|_| TooltipView).into())
+                .tooltip_delay(Duration::from_millis(200))
+        }
+    }
+
+    #[gpui::test]
+    fn test_tooltip_delay(cx: &mut TestAppContext) {
+        let (_, cx) = cx.add_window_view(|_, _| TooltipTargetView);
+        cx.simulate_mouse_move(point(px(50.), px(50.)), None, Modifiers::none());
+
+        advance_frame(cx, Duration::from_millis(150));
+        assert_eq!(cx.debug_bounds("tooltip"), None);
+
+        // The tooltip is shown next to the mouse once the delay has passed.
+        advance_frame(cx, Duration::from_millis(100));
+        let tooltip_bounds = cx.debug_bounds("tooltip").unwrap();
+        assert_eq!(tooltip_bounds.origin, point(px(51.), px(51.)));
+
+        cx.simulate_mouse_move(point(px(150.), px(150.)), None, Modifiers::none());
+        advance_frame(cx, Duration::ZERO);
+        assert_eq!(cx.debug_bounds("tooltip"), None);
+    }
+
+    struct ZIndexView;
+
+    impl Render for ZIndexView {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .relative()
+                .size(px(100.))
+                .child(div().absolute().size(px(50.)).bg(red()).z_index(1))
+                .child(div().absolute().size(px(50.)).bg(blue()))
+        }
+    }
+
+    #[gpui::test]
+    fn test_z_index(cx: &mut TestAppContext) {
+        let (_, cx) = cx.add_window_view(|_, _| ZIndexView);
+        cx.update(|window, _| {
+            let quads = &window.rendered_frame.scene.quads;
+            assert_eq!(quads.len(), 2);
+            let painted_over = quads
+                .iter()
+                .max_by_key(|quad| quad.order)
+                .map(|quad| Rgba::from(quad.background.solid));
+            // The first child is painted on top because of its higher z-index.
+            assert_eq!(painted_over, Some(red().into()));
+        });
+    }
+
+    struct ScrollView;
+
+    impl Render for ScrollView {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div().child(
+                div()
+                    .id("list")
+                    .debug_selector(|| "list".into())
+                    .flex()
+                    .flex_col()
+                    .w(px(100.))
+                    .h(px(100.))
+                    .overflow_y_scroll()
+                    .children((0..10).map(|ix| {
+                        div()
+                            .debug_selector(move || format!("item-{ix}"))
+                            .h(px(30.))
+                            .flex_none()
+                            .bg(red())
+                    })),
+            )
+        }
+    }
+
+    fn scroll(cx: &mut VisualTestContext, delta_y: f32) {
+        cx.simulate_event(ScrollWheelEvent {
+            position: point(px(50.), px(50.)),
+            delta: ScrollDelta::Pixels(point(px(0.), px(delta_y))),
+            ..Default::default()
+        });
+    }
+
+    fn item_y(cx: &mut VisualTestContext, ix: usize) -> Pixels {
+        cx.update(|window, _| {
+            window.rendered_frame.debug_bounds[&format!("item-{ix}")]
+                .origin
+                .y
+        })
+    }
+
+    #[gpui::test]
+    fn test_overflow_y_scroll(cx: &mut TestAppContext) {
+        let (_, cx) = cx.add_window_view(|_, _| ScrollView);
+        cx.simulate_mouse_move(point(px(50.), px(50.)), None, Modifiers::none());
+        assert_eq!(item_y(cx, 0), px(0.));
+        assert_eq!(item_y(cx, 9), px(270.));
+
+        scroll(cx, -50.);
+        assert_eq!(item_y(cx, 0), px(-50.));
+        assert_eq!(item_y(cx, 9), px(220.));
+
+        // The children are clipped to the bounds of the list.
+        let list_bounds = cx.debug_bounds("list").unwrap();
+        cx.update(|window, _| {
+            let content_mask = list_bounds.scale(window.scale_factor());
+            let quads = &window.rendered_frame.scene.quads;
+            assert!(!quads.is_empty());
+            for quad in quads {
+                assert_eq!(quad.content_mask.bounds, content_mask);
+            }
+        });
+
+        // Scrolling stops once the last child is at the bottom of the list...
+        scroll(cx, -1000.);
+        assert_eq!(item_y(cx, 0), px(-200.));
+        assert_eq!(item_y(cx, 9), px(70.));
+
+        // ...and once the first child is back at the top.
+        scroll(cx, 1000.);
+        assert_eq!(item_y(cx, 0), px(0.));
+    }
+
+    struct WrapView;
+
+    impl Render for WrapView {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .flex()
+                .flex_wrap()
+                .w(px(100.))
+                .children((0..5).map(|ix| {
+                    div()
+                        .debug_selector(move || format!("tag-{ix}"))
+                        .w(px(40.))
+                        .h(px(10.))
+                        .flex_none()
+                }))
+        }
+    }
+
+    #[gpui::test]
+    fn test_flex_wrap(cx: &mut TestAppContext) {
+        let (_, cx) = cx.add_window_view(|_, _| WrapView);
+
+        // Two 40px children fit in each 100px row, so five of them take up three rows.
+        let origins = ["tag-0", "tag-1", "tag-2", "tag-3", "tag-4"]
+            .into_iter()
+            .map(|selector| cx.debug_bounds(selector).unwrap().origin)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            origins,
+            [
+                point(px(0.), px(0.)),
+                point(px(40.), px(0.)),
+                point(px(0.), px(10.)),
+                point(px(40.), px(10.)),
+                point(px(0.), px(20.)),
+            ]
+        );
+    }
+}
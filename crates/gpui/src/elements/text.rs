@@ -1,10 +1,12 @@
 use crate::{
-    ActiveTooltip, AnyView, App, Bounds, DispatchPhase, Element, ElementId, GlobalElementId,
-    HighlightStyle, Hitbox, IntoElement, LayoutId, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
-    Pixels, Point, SharedString, Size, TextOverflow, TextRun, TextStyle, TooltipId, WhiteSpace,
-    Window, WrappedLine, WrappedLineLayout, register_tooltip_mouse_handlers, set_tooltip_on_window,
+    ActiveTooltip, AnyView, App, BorderStyle, Bounds, ContentMask, DecorationRun, DispatchPhase,
+    Element, ElementId, GlobalElementId, HighlightStyle, Hitbox, IntoElement, LayoutId,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, OffsetMap, Pixels, Point, SharedString, Size,
+    TOOLTIP_SHOW_DELAY, TextAlign, TextOverflow, TextRun, TextStyle, TextStyleRefinement,
+    TooltipId, WhiteSpace, Window, WrappedLine, WrappedLineLayout, outline,
+    register_tooltip_mouse_handlers, set_tooltip_on_window, size,
 };
-use anyhow::anyhow;
+use anyhow::{Context as _, anyhow};
 use smallvec::SmallVec;
 use std::{
     cell::{Cell, RefCell},
@@ -30,7 +32,7 @@ impl Element for &'static str {
         cx: &mut App,
     ) -> (LayoutId, Self::RequestLayoutState) {
         let mut state = TextLayout::default();
-        let layout_id = state.layout(SharedString::from(*self), None, window, cx);
+        let layout_id = state.layout(SharedString::from(*self), None, None, false, window, cx);
         (layout_id, state)
     }
 
@@ -91,7 +93,7 @@ impl Element for SharedString {
         cx: &mut App,
     ) -> (LayoutId, Self::RequestLayoutState) {
         let mut state = TextLayout::default();
-        let layout_id = state.layout(self.clone(), None, window, cx);
+        let layout_id = state.layout(self.clone(), None, None, false, window, cx);
         (layout_id, state)
     }
 
@@ -129,6 +131,7 @@ impl IntoElement for SharedString {
 
 /// Renders text with runs of different styles.
 ///
+/// Each `\n` in the text starts a new line, in addition to any soft wraps.
 /// Callers are responsible for setting the correct style for each run.
 /// For text with a uniform style, you can usually avoid calling this constructor
 /// and just pass text directly.
@@ -136,6 +139,11 @@ pub struct StyledText {
     text: SharedString,
     runs: Option<Vec<TextRun>>,
     delayed_highlights: Option<Vec<(Range<usize>, HighlightStyle)>>,
+    align: Option<TextAlign>,
+    overflow: Option<TextOverflow>,
+    max_lines: Option<usize>,
+    fade_last_line: bool,
+    aria_label: Option<SharedString>,
     layout: TextLayout,
 }
 
@@ -146,6 +154,11 @@ impl StyledText {
             text: text.into(),
             runs: None,
             delayed_highlights: None,
+            align: None,
+            overflow: None,
+            max_lines: None,
+            fade_last_line: false,
+            aria_label: None,
             layout: TextLayout::default(),
         }
     }
@@ -215,6 +228,99 @@ impl StyledText {
         self.runs = Some(runs);
         self
     }
+
+    /// Align each line within the bounds of this element, overriding the alignment of the
+    /// inherited text style.
+    pub fn align(mut self, align: TextAlign) -> Self {
+        self.align = Some(align);
+        self
+    }
+
+    /// Keep this text on a single line, truncating it as described by `overflow` when it's
+    /// wider than the bounds of this element.
+    pub fn overflow(mut self, overflow: TextOverflow) -> Self {
+        self.overflow = Some(overflow);
+        self
+    }
+
+    /// Show at most the given number of visual lines, including soft wraps. The height of the
+    /// element is clamped to fit them and the remaining lines are not painted.
+    pub fn max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines);
+        self
+    }
+
+    /// When the text is clamped by [`Self::max_lines`], fade the last visible line out to
+    /// transparent instead of cutting it off.
+    pub fn fade_last_line(mut self, fade_last_line: bool) -> Self {
+        self.fade_last_line = fade_last_line;
+        self
+    }
+
+    /// Give this text an accessible name to describe it to assistive technologies in place of
+    /// the text itself. See [`Window::accessibility_nodes`].
+    pub fn aria_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.aria_label = Some(label.into());
+        self
+    }
+
+    /// Measure the size that the given text takes up when laid out with the given style, wrapping
+    /// it at `max_width` if the style allows it. Unlike laying out an element, this doesn't store
+    /// any state, so it can be used to size a parent before its children are laid out.
+    pub fn measure(
+        text: impl Into<SharedString>,
+        style: &TextStyle,
+        max_width: Option<Pixels>,
+        window: &Window,
+        cx: &App,
+    ) -> Size<Pixels> {
+        let text = text.into();
+        let font_size = style.font_size.to_pixels(window.rem_size());
+        let line_height = style
+            .line_height
+            .to_pixels(font_size.into(), window.rem_size());
+        let wrap_width = max_width.filter(|_| style.white_space == WhiteSpace::Normal);
+        let (truncate_width, ellipsis) = match style.text_overflow {
+            Some(TextOverflow::Ellipsis(ellipsis)) => (
+                max_width.map(|width| width * style.line_clamp.unwrap_or(1)),
+                Some(ellipsis),
+            ),
+            None => (None, None),
+        };
+
+        let runs = [style.to_run(text.len())];
+        match shape_lines(
+            text,
+            &runs,
+            style,
+            font_size,
+            wrap_width,
+            truncate_width,
+            ellipsis,
+            None,
+            false,
+            window,
+            cx,
+        ) {
+            Ok((lines, ..)) => lines_size(&lines, line_height, None).0,
+            Err(error) => {
+                log::error!("{error:#}");
+                Size::default()
+            }
+        }
+    }
+
+    fn text_style(&self) -> Option<TextStyleRefinement> {
+        if self.align.is_none() && self.overflow.is_none() {
+            return None;
+        }
+        Some(TextStyleRefinement {
+            text_align: self.align,
+            text_overflow: self.overflow,
+            white_space: self.overflow.map(|_| WhiteSpace::Nowrap),
+            ..Default::default()
+        })
+    }
 }
 
 impl Element for StyledText {
@@ -237,7 +343,16 @@ impl Element for StyledText {
             })
         });
 
-        let layout_id = self.layout.layout(self.text.clone(), runs, window, cx);
+        let layout_id = window.with_text_style(self.text_style(), |window| {
+            self.layout.layout(
+                self.text.clone(),
+                runs,
+                self.max_lines,
+                self.fade_last_line,
+                window,
+                cx,
+            )
+        });
         (layout_id, ())
     }
 
@@ -255,13 +370,23 @@ impl Element for StyledText {
     fn paint(
         &mut self,
         _id: Option<&GlobalElementId>,
-        _bounds: Bounds<Pixels>,
+        bounds: Bounds<Pixels>,
         _: &mut Self::RequestLayoutState,
         _: &mut Self::PrepaintState,
         window: &mut Window,
         cx: &mut App,
     ) {
-        self.layout.paint(&self.text, window, cx)
+        if let Some(label) = self.aria_label.clone() {
+            window.insert_accessibility_node(label, bounds);
+        }
+
+        // A clamped line that soft wraps past the last visible line is cut off at the bounds.
+        let content_mask = self.max_lines.map(|_| ContentMask { bounds });
+        window.with_content_mask(content_mask, |window| {
+            window.with_text_style(self.text_style(), |window| {
+                self.layout.paint(&self.text, window, cx)
+            })
+        })
     }
 }
 
@@ -283,6 +408,14 @@ struct TextLayoutInner {
     wrap_width: Option<Pixels>,
     size: Option<Size<Pixels>>,
     bounds: Option<Bounds<Pixels>>,
+    wrapped: bool,
+    line_count: usize,
+    /// Maps offsets into the laid out lines, which tab expansion and hyphenation rewrite, back
+    /// to the text.
+    offsets: OffsetMap,
+    /// Whether the text couldn't be shaped, in which case a placeholder box is
+    /// painted in its place.
+    shaping_failed: bool,
 }
 
 impl TextLayout {
@@ -290,6 +423,8 @@ impl TextLayout {
         &self,
         text: SharedString,
         runs: Option<Vec<TextRun>>,
+        max_lines: Option<usize>,
+        fade_last_line: bool,
         window: &mut Window,
         _: &mut App,
     ) -> LayoutId {
@@ -299,7 +434,7 @@ impl TextLayout {
             .line_height
             .to_pixels(font_size.into(), window.rem_size());
 
-        let mut runs = if let Some(runs) = runs {
+        let runs = if let Some(runs) = runs {
             runs
         } else {
             vec![text_style.to_run(text.len())]
@@ -343,47 +478,53 @@ impl TextLayout {
                     }
                 }
 
-                let mut line_wrapper = cx.text_system().line_wrapper(text_style.font(), font_size);
-                let text = if let Some(truncate_width) = truncate_width {
-                    line_wrapper.truncate_line(text.clone(), truncate_width, ellipsis, &mut runs)
-                } else {
-                    text.clone()
-                };
-
-                let Some(lines) = window
-                    .text_system()
-                    .shape_text(
-                        text,
-                        font_size,
-                        &runs,
-                        wrap_width,            // Wrap if we know the width.
-                        text_style.line_clamp, // Limit the number of lines if line_clamp is set.
-                    )
-                    .log_err()
-                else {
-                    element_state.0.borrow_mut().replace(TextLayoutInner {
-                        lines: Default::default(),
-                        line_height,
-                        wrap_width,
-                        size: Some(Size::default()),
-                        bounds: None,
-                    });
-                    return Size::default();
+                let (lines, wrapped, offsets) = match shape_lines(
+                    text.clone(),
+                    &runs,
+                    &text_style,
+                    font_size,
+                    wrap_width,
+                    truncate_width,
+                    ellipsis,
+                    max_lines,
+                    fade_last_line,
+                    window,
+                    cx,
+                ) {
+                    Ok(shaped) => shaped,
+                    Err(error) => {
+                        // The measured size can't carry the error, so log what failed to
+                        // shape and reserve a line for a placeholder, so the failure is
+                        // visible instead of the text silently disappearing.
+                        log::error!("{error:#}");
+                        let placeholder_size = size(line_height, line_height);
+                        element_state.0.borrow_mut().replace(TextLayoutInner {
+                            lines: Default::default(),
+                            line_height,
+                            wrap_width,
+                            size: Some(placeholder_size),
+                            bounds: None,
+                            wrapped: false,
+                            line_count: 1,
+                            offsets: OffsetMap::default(),
+                            shaping_failed: true,
+                        });
+                        return placeholder_size;
+                    }
                 };
 
-                let mut size: Size<Pixels> = Size::default();
-                for line in &lines {
-                    let line_size = line.size(line_height);
-                    size.height += line_size.height;
-                    size.width = size.width.max(line_size.width).ceil();
-                }
+                let (size, line_count) = lines_size(&lines, line_height, max_lines);
 
                 element_state.0.borrow_mut().replace(TextLayoutInner {
+                    wrapped,
+                    line_count,
+                    offsets,
                     lines,
                     line_height,
                     wrap_width,
                     size: Some(size),
                     bounds: None,
+                    shaping_failed: false,
                 });
 
                 size
@@ -413,9 +554,14 @@ impl TextLayout {
             .ok_or_else(|| anyhow!("prepaint has not been performed on {:?}", text))
             .unwrap();
 
+        let text_style = window.text_style();
+        if element_state.shaping_failed {
+            window.paint_quad(outline(bounds, text_style.color, BorderStyle::Solid));
+            return;
+        }
+
         let line_height = element_state.line_height;
         let mut line_origin = bounds.origin;
-        let text_style = window.text_style();
         for line in &element_state.lines {
             line.paint_background(
                 line_origin,
@@ -440,7 +586,24 @@ impl TextLayout {
     }
 
     /// Get the byte index into the input of the pixel position.
-    pub fn index_for_position(&self, mut position: Point<Pixels>) -> Result<usize, usize> {
+    pub fn index_for_position(&self, position: Point<Pixels>) -> Result<usize, usize> {
+        self.index_for_position_inner(position, false)
+    }
+
+    /// Get the byte index of the character boundary closest to the pixel position, which is
+    /// where a cursor placed at that position should go.
+    ///
+    /// Like [`Self::index_for_position`], this returns `Err` with the nearest index when the
+    /// position is outside of the text.
+    pub fn closest_index_for_position(&self, position: Point<Pixels>) -> Result<usize, usize> {
+        self.index_for_position_inner(position, true)
+    }
+
+    fn index_for_position_inner(
+        &self,
+        position: Point<Pixels>,
+        closest: bool,
+    ) -> Result<usize, usize> {
         let element_state = self.0.borrow();
         let element_state = element_state
             .as_ref()
@@ -463,14 +626,26 @@ impl TextLayout {
                 line_start_ix += line.len() + 1;
             } else {
                 let position_within_line = position - line_origin;
-                match line.index_for_position(position_within_line, line_height) {
-                    Ok(index_within_line) => return Ok(line_start_ix + index_within_line),
-                    Err(index_within_line) => return Err(line_start_ix + index_within_line),
-                }
+                let index_within_line = if closest {
+                    line.closest_index_for_position(position_within_line, line_height)
+                } else {
+                    line.index_for_position(position_within_line, line_height)
+                };
+                let offsets = &element_state.offsets;
+                return match index_within_line {
+                    Ok(index_within_line) => {
+                        Ok(offsets.to_original(line_start_ix + index_within_line, closest))
+                    }
+                    Err(index_within_line) => {
+                        Err(offsets.to_original(line_start_ix + index_within_line, closest))
+                    }
+                };
             }
         }
 
-        Err(line_start_ix.saturating_sub(1))
+        Err(element_state
+            .offsets
+            .to_original(line_start_ix.saturating_sub(1), closest))
     }
 
     /// Get the pixel position for the given byte index.
@@ -483,6 +658,7 @@ impl TextLayout {
             .bounds
             .expect("prepaint has not been performed");
         let line_height = element_state.line_height;
+        let index = element_state.offsets.from_original(index);
 
         let mut line_origin = bounds.origin;
         let mut line_start_ix = 0;
@@ -514,6 +690,7 @@ impl TextLayout {
             .bounds
             .expect("prepaint has not been performed");
         let line_height = element_state.line_height;
+        let index = element_state.offsets.from_original(index);
 
         let mut line_origin = bounds.origin;
         let mut line_start_ix = 0;
@@ -534,6 +711,47 @@ impl TextLayout {
         None
     }
 
+    /// Get the byte index into the input of the character boundary closest to the given x
+    /// offset within the given line, measured from the start of the line before soft wrapping.
+    pub fn index_for_x(&self, line_ix: usize, x: Pixels) -> Option<usize> {
+        let element_state = self.0.borrow();
+        let element_state = element_state
+            .as_ref()
+            .expect("measurement has not been performed");
+
+        let mut line_start_ix = 0;
+        for line in element_state.lines.iter().take(line_ix) {
+            line_start_ix += line.len() + 1;
+        }
+        let line = element_state.lines.get(line_ix)?;
+        let index = line_start_ix + line.unwrapped_layout.closest_index_for_x(x);
+        Some(element_state.offsets.to_original(index, true))
+    }
+
+    /// Get the x offset of the character at the given byte index, measured from the start of
+    /// its line before soft wrapping. Indices within a glyph cluster, such as a ligature, snap
+    /// to the start of the cluster.
+    pub fn x_for_index(&self, index: usize) -> Option<Pixels> {
+        let element_state = self.0.borrow();
+        let element_state = element_state
+            .as_ref()
+            .expect("measurement has not been performed");
+        let index = element_state.offsets.from_original(index);
+
+        let mut line_start_ix = 0;
+        for line in &element_state.lines {
+            let line_end_ix = line_start_ix + line.len();
+            if index <= line_end_ix {
+                let layout = &line.unwrapped_layout;
+                let ix_within_line = layout.cluster_start_for_index(index - line_start_ix);
+                return Some(layout.x_for_index(ix_within_line));
+            }
+            line_start_ix = line_end_ix + 1;
+        }
+
+        None
+    }
+
     /// The bounds of this layout.
     pub fn bounds(&self) -> Bounds<Pixels> {
         self.0.borrow().as_ref().unwrap().bounds.unwrap()
@@ -544,17 +762,65 @@ impl TextLayout {
         self.0.borrow().as_ref().unwrap().line_height
     }
 
-    /// The text for this layout.
-    pub fn text(&self) -> String {
+    /// Whether any line of this layout was soft wrapped to fit within its width.
+    pub fn wrapped(&self) -> bool {
         self.0
             .borrow()
             .as_ref()
-            .unwrap()
+            .expect("measurement has not been performed")
+            .wrapped
+    }
+
+    /// The number of visual lines in this layout, including soft wraps.
+    pub fn line_count(&self) -> usize {
+        self.0
+            .borrow()
+            .as_ref()
+            .expect("measurement has not been performed")
+            .line_count
+    }
+
+    /// The distance from the top of this layout to the baseline of its first visual line,
+    /// or `None` if the layout contains no lines.
+    pub fn first_baseline(&self) -> Option<Pixels> {
+        let element_state = self.0.borrow();
+        let element_state = element_state
+            .as_ref()
+            .expect("measurement has not been performed");
+        let line = element_state.lines.first()?;
+        Some(line.baseline(element_state.line_height))
+    }
+
+    /// The distance from the top of this layout to the baseline of its last visual line,
+    /// including soft wraps, or `None` if the layout contains no lines.
+    pub fn last_baseline(&self) -> Option<Pixels> {
+        let element_state = self.0.borrow();
+        let element_state = element_state
+            .as_ref()
+            .expect("measurement has not been performed");
+        let line_height = element_state.line_height;
+        let last_line = element_state.lines.last()?;
+
+        let mut line_origin_y = Pixels::ZERO;
+        for line in &element_state.lines {
+            line_origin_y += line.size(line_height).height;
+        }
+        // Step back to the top of the last visual line of the last wrapped line.
+        line_origin_y -= line_height;
+        Some(line_origin_y + last_line.baseline(line_height))
+    }
+
+    /// The text for this layout.
+    pub fn text(&self) -> String {
+        let element_state = self.0.borrow();
+        let element_state = element_state.as_ref().unwrap();
+        let text = element_state
             .lines
             .iter()
             .map(|s| s.text.to_string())
             .collect::<Vec<_>>()
-            .join("\n")
+            .join("\n");
+        element_state.offsets.original_text(&text)
     }
 
     /// The text for this layout (with soft-wraps as newlines)
@@ -577,6 +843,171 @@ impl TextLayout {
     }
 }
 
+/// Shapes the text into lines the way [`TextLayout`] lays it out, and reports whether any
+/// of them was soft wrapped, including lines that were then dropped by `max_lines`, along
+/// with how the text was rewritten to lay it out.
+fn shape_lines(
+    mut text: SharedString,
+    runs: &[TextRun],
+    text_style: &TextStyle,
+    font_size: Pixels,
+    wrap_width: Option<Pixels>,
+    truncate_width: Option<Pixels>,
+    ellipsis: Option<&str>,
+    max_lines: Option<usize>,
+    fade_last_line: bool,
+    window: &Window,
+    cx: &App,
+) -> anyhow::Result<(SmallVec<[WrappedLine; 1]>, bool, OffsetMap)> {
+    // Tab expansion, truncation and hyphenation adjust the runs to match the text they
+    // produce, so work on a copy to keep repeated measurements from compounding.
+    let mut runs = runs.to_vec();
+    // The line wrapper panics if the font can't be resolved, so check it beforehand.
+    let font = text_style.font();
+    cx.text_system()
+        .try_resolve_font(&font)
+        .with_context(|| format!("failed to shape text {text:?}"))?;
+    let mut line_wrapper = cx.text_system().line_wrapper(font, font_size);
+    let mut offsets = OffsetMap::default();
+    if let Some(tab_size) = text_style.tab_size {
+        text = line_wrapper.expand_tabs(text, tab_size, &mut runs, &mut offsets);
+    }
+    if let Some(truncate_width) = truncate_width {
+        let truncated =
+            line_wrapper.truncate_line(text.clone(), truncate_width, ellipsis, &mut runs);
+        if truncated != text {
+            offsets.truncate(truncated.len() - ellipsis.map_or(0, str::len));
+        }
+        text = truncated;
+    }
+    // Hyphenation breaks words with hard newlines, but they still count as soft wrapping.
+    let mut hyphenated = false;
+    if text_style.hyphenate {
+        if let Some(wrap_width) = wrap_width {
+            let hyphenated_text =
+                line_wrapper.hyphenate_line(text.clone(), wrap_width, &mut runs, &mut offsets);
+            hyphenated = hyphenated_text != text;
+            text = hyphenated_text;
+        }
+    }
+
+    // Lines beyond `max_lines` are dropped after shaping rather than by the line clamp,
+    // which stops wrapping early, so that wrapping in the hidden lines is still detected.
+    let mut lines = window
+        .text_system()
+        .shape_text(
+            text.clone(),
+            font_size,
+            &runs,
+            wrap_width,            // Wrap if we know the width.
+            text_style.line_clamp, // Limit the number of lines if line_clamp is set.
+        )
+        .with_context(|| format!("failed to shape text {text:?}"))?;
+    let wrapped = hyphenated || lines.iter().any(|line| !line.wrap_boundaries().is_empty());
+
+    if let Some(max_lines) = max_lines {
+        clamp_lines(&mut lines, max_lines, fade_last_line);
+    }
+    Ok((lines, wrapped, offsets))
+}
+
+/// The size taken up by the given lines, and the number of visual lines among them.
+fn lines_size(
+    lines: &[WrappedLine],
+    line_height: Pixels,
+    max_lines: Option<usize>,
+) -> (Size<Pixels>, usize) {
+    let mut size: Size<Pixels> = Size::default();
+    let mut line_count = 0;
+    for line in lines {
+        let line_size = line.size(line_height);
+        size.height += line_size.height;
+        size.width = size.width.max(line_size.width).ceil();
+        line_count += line.wrap_boundaries().len() + 1;
+    }
+    if let Some(max_lines) = max_lines {
+        size.height = size.height.min(line_height * max_lines as f32);
+        line_count = line_count.min(max_lines);
+    }
+    (size, line_count)
+}
+
+/// Drops the lines that start below the first `max_lines` visual lines and, if requested,
+/// fades out the last visual line that remains visible.
+fn clamp_lines(lines: &mut SmallVec<[WrappedLine; 1]>, max_lines: usize, fade_last_line: bool) {
+    let mut visual_lines = 0;
+    let mut kept_lines = 0;
+    for line in lines.iter() {
+        if visual_lines >= max_lines {
+            break;
+        }
+        visual_lines += line.wrap_boundaries().len() + 1;
+        kept_lines += 1;
+    }
+    let was_clamped = kept_lines < lines.len() || visual_lines > max_lines;
+    lines.truncate(kept_lines);
+
+    if fade_last_line && was_clamped {
+        if let Some(last_line) = lines.last_mut() {
+            let hidden_rows = visual_lines.saturating_sub(max_lines);
+            let row = last_line.wrap_boundaries().len() - hidden_rows;
+            fade_row(last_line, row);
+        }
+    }
+}
+
+/// Splits the decoration runs covering the given visual row of the line so that the row's
+/// characters fade linearly from their own color at its start to transparent at its end.
+fn fade_row(line: &mut WrappedLine, row: usize) {
+    let layout = &line.layout.unwrapped_layout;
+    let boundary_index = |row: usize| {
+        line.wrap_boundaries()
+            .get(row)
+            .map(|boundary| layout.runs[boundary.run_ix].glyphs[boundary.glyph_ix].index)
+    };
+    let row_start = row.checked_sub(1).and_then(boundary_index).unwrap_or(0);
+    let row_end = boundary_index(row).unwrap_or(line.len());
+    let start_x = layout.x_for_index(row_start);
+    let row_width = layout.x_for_index(row_end) - start_x;
+    if row_width <= Pixels::ZERO {
+        return;
+    }
+
+    let mut decoration_runs = SmallVec::<[DecorationRun; 32]>::new();
+    let mut run_start = 0;
+    for run in &line.decoration_runs {
+        let run_end = run_start + run.len as usize;
+        let mut ix = run_start;
+        let fade_start = row_start.clamp(run_start, run_end);
+        let fade_end = row_end.clamp(run_start, run_end);
+        if fade_start > ix {
+            decoration_runs.push(DecorationRun {
+                len: (fade_start - ix) as u32,
+                ..run.clone()
+            });
+            ix = fade_start;
+        }
+        for (offset, c) in line.text[fade_start..fade_end].char_indices() {
+            let char_ix = fade_start + offset;
+            let progress = (layout.x_for_index(char_ix) - start_x) / row_width;
+            decoration_runs.push(DecorationRun {
+                len: c.len_utf8() as u32,
+                color: run.color.opacity(1. - progress),
+                ..run.clone()
+            });
+            ix = char_ix + c.len_utf8();
+        }
+        if run_end > ix {
+            decoration_runs.push(DecorationRun {
+                len: (run_end - ix) as u32,
+                ..run.clone()
+            });
+        }
+        run_start = run_end;
+    }
+    line.decoration_runs = decoration_runs;
+}
+
 /// A text element that can be interacted with.
 pub struct InteractiveText {
     element_id: ElementId,
@@ -837,6 +1268,7 @@ impl Element for InteractiveText {
                     register_tooltip_mouse_handlers(
                         &active_tooltip,
                         self.tooltip_id,
+                        TOOLTIP_SHOW_DELAY,
                         build_tooltip,
                         check_is_hovered,
                         check_is_hovered_during_prepaint,
@@ -859,3 +1291,350 @@ impl IntoElement for InteractiveText {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as gpui, AvailableSpace, HighlightStyle, ParentElement as _, Pixels, Size,
+        Styled as _, StyledText, TabSize, TestAppContext, TestTextSystem, TextLayout, TextOverflow,
+        TextStyle, black, div, point, px, red, size,
+    };
+    use std::sync::Arc;
+
+    fn measured_size(layout: &TextLayout) -> Size<Pixels> {
+        layout.0.borrow().as_ref().unwrap().size.unwrap()
+    }
+
+    fn line_count(layout: &TextLayout) -> usize {
+        layout.0.borrow().as_ref().unwrap().lines.len()
+    }
+
+    #[gpui::test]
+    fn test_text_with_newlines(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let text = StyledText::new("a\nb\nc");
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), AvailableSpace::min_size(), |_, _| {
+            text
+        });
+
+        assert_eq!(line_count(&layout), 3);
+        assert_eq!(layout.text(), "a\nb\nc");
+        assert_eq!(measured_size(&layout).height, layout.line_height() * 3.);
+    }
+
+    #[gpui::test]
+    fn test_text_wrapping_is_reported(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let text = StyledText::new("hello world");
+        let layout = text.layout().clone();
+        let available_space = size(px(70.).into(), AvailableSpace::MinContent);
+        cx.draw(point(px(0.), px(0.)), available_space, |_, _| text);
+
+        assert_eq!(layout.wrapped_text(), "hello \nworld");
+        assert!(layout.wrapped());
+        assert_eq!(layout.line_count(), 2);
+
+        // Hard line breaks are counted, but don't count as wrapping.
+        let text = StyledText::new("a\nb");
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), available_space, |_, _| text);
+        assert!(!layout.wrapped());
+        assert_eq!(layout.line_count(), 2);
+
+        // Wrapping is reported even when the wrapped rows are hidden by `max_lines`.
+        let text = StyledText::new("hello world").max_lines(1);
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), available_space, |_, _| text);
+        assert!(layout.wrapped());
+        assert_eq!(layout.line_count(), 1);
+
+        let text = StyledText::new("a\nhello world").max_lines(1);
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), available_space, |_, _| text);
+        assert!(layout.wrapped());
+        assert_eq!(layout.line_count(), 1);
+    }
+
+    #[gpui::test]
+    fn test_baselines(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let text = StyledText::new("hello\nworld");
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), AvailableSpace::min_size(), |_, _| {
+            div().text_size(px(10.)).line_height(px(20.)).child(text)
+        });
+
+        // The test font has an ascent of 1.025em and a descent of -0.275em, and lines are
+        // centered vertically within their line height.
+        assert_eq!(layout.line_height(), px(20.));
+        assert_eq!(layout.first_baseline(), Some(px(16.5)));
+        assert_eq!(layout.last_baseline(), Some(px(36.5)));
+    }
+
+    #[gpui::test]
+    fn test_tab_expansion(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let text = StyledText::new("a\tb");
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), AvailableSpace::min_size(), |_, _| {
+            div().tab_size(TabSize::Spaces(4)).child(text)
+        });
+
+        // The tab is expanded to the spaces up to the first tab stop, so "b" starts in column 4.
+        // Indices still refer to the text as given, with the tab in it.
+        assert_eq!(layout.text(), "a\tb");
+        let char_width = layout.0.borrow().as_ref().unwrap().lines[0].width() / 5.;
+        assert_eq!(layout.x_for_index(2), Some(char_width * 4.));
+        assert_eq!(
+            layout.position_for_index(2),
+            Some(point(char_width * 4., px(0.)))
+        );
+    }
+
+    #[gpui::test]
+    fn test_hyphenation(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let text = StyledText::new("abcdefghijkl");
+        let layout = text.layout().clone();
+        let available_space = size(px(72.).into(), AvailableSpace::MinContent);
+        cx.draw(point(px(0.), px(0.)), available_space, |_, _| {
+            div().hyphens_auto().child(text)
+        });
+
+        // The word is broken after "abcdef", which leaves room for the hyphen.
+        assert_eq!(layout.wrapped_text(), "abcdef-\nghijkl");
+        assert!(layout.wrapped());
+        assert_eq!(layout.line_count(), 2);
+
+        // The inserted hyphen and newline aren't part of the text, so indices skip them.
+        assert_eq!(layout.text(), "abcdefghijkl");
+        let char_width = layout.0.borrow().as_ref().unwrap().lines[1].width() / 6.;
+        let line_height = layout.line_height();
+        assert_eq!(layout.x_for_index(6), Some(px(0.)));
+        assert_eq!(
+            layout.position_for_index(6),
+            Some(point(px(0.), line_height))
+        );
+        let position = point(char_width * 0.4, line_height * 1.5);
+        assert_eq!(layout.index_for_position(position), Ok(6));
+        let position = point(char_width * 6.9, line_height * 0.5);
+        assert_eq!(layout.closest_index_for_position(position), Ok(6));
+    }
+
+    #[gpui::test]
+    fn test_index_for_position_after_tab(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let text = StyledText::new("a\tbc");
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), AvailableSpace::min_size(), |_, _| {
+            div().tab_size(TabSize::Spaces(4)).child(text)
+        });
+        let char_width = layout.0.borrow().as_ref().unwrap().lines[0].width() / 6.;
+        let y = layout.line_height() * 0.5;
+
+        // Characters after the tab are hit at their index in the text, not in the expanded one.
+        let position = point(char_width * 5.2, y);
+        assert_eq!(layout.index_for_position(position), Ok(3));
+        assert_eq!(layout.closest_index_for_position(position), Ok(3));
+
+        // Anywhere within the tab's spaces hits the tab, and the cursor goes to the nearer side.
+        let position = point(char_width * 1.4, y);
+        assert_eq!(layout.index_for_position(position), Ok(1));
+        assert_eq!(layout.closest_index_for_position(position), Ok(1));
+        let position = point(char_width * 3.4, y);
+        assert_eq!(layout.index_for_position(position), Ok(1));
+        assert_eq!(layout.closest_index_for_position(position), Ok(2));
+        assert_eq!(layout.index_for_x(0, char_width * 3.4), Some(2));
+    }
+
+    #[gpui::test]
+    fn test_max_lines(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let text = StyledText::new("a\nbcd\ne\nf\ng").max_lines(2);
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), AvailableSpace::min_size(), |_, _| {
+            text
+        });
+
+        assert_eq!(line_count(&layout), 2);
+        assert_eq!(layout.line_count(), 2);
+        assert_eq!(layout.text(), "a\nbcd");
+        assert_eq!(measured_size(&layout).height, layout.line_height() * 2.);
+
+        // Fading spreads the last visible line over one run per character, each more
+        // transparent than the one before.
+        let text = StyledText::new("a\nbcd\ne\nf\ng")
+            .max_lines(2)
+            .fade_last_line(true);
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), AvailableSpace::min_size(), |_, _| {
+            text
+        });
+        let element_state = layout.0.borrow();
+        let lines = &element_state.as_ref().unwrap().lines;
+        let alphas = |line_ix: usize| {
+            lines[line_ix]
+                .decoration_runs
+                .iter()
+                .map(|run| (run.len, (run.color.a * 100.).round() / 100.))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(alphas(0), [(1, 1.)]);
+        assert_eq!(alphas(1), [(1, 1.), (1, 0.67), (1, 0.33)]);
+    }
+
+    #[gpui::test]
+    fn test_measure(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        for (max_width, available_space) in [
+            (None, AvailableSpace::min_size()),
+            (
+                Some(px(70.)),
+                size(px(70.).into(), AvailableSpace::MinContent),
+            ),
+        ] {
+            let text = StyledText::new("hello world");
+            let layout = text.layout().clone();
+            cx.draw(point(px(0.), px(0.)), available_space, |_, _| text);
+
+            let measured = cx.update(|window, cx| {
+                StyledText::measure("hello world", &window.text_style(), max_width, window, cx)
+            });
+            assert_eq!(measured, measured_size(&layout));
+        }
+    }
+
+    #[gpui::test]
+    fn test_aria_label(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let text = StyledText::new("×").aria_label("Close");
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), AvailableSpace::min_size(), |_, _| {
+            text
+        });
+
+        let nodes = cx.update(|window, _| window.next_frame.accessibility_nodes.clone());
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].label.as_ref(), "Close");
+        assert_eq!(nodes[0].bounds, layout.bounds());
+    }
+
+    #[gpui::test]
+    fn test_text_overflow_ellipsis(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let text = StyledText::new("hello world").overflow(TextOverflow::Ellipsis("…"));
+        let layout = text.layout().clone();
+        let available_space = size(px(50.).into(), AvailableSpace::MinContent);
+        cx.draw(point(px(0.), px(0.)), available_space, |_, _| text);
+
+        assert_eq!(line_count(&layout), 1);
+        let text = layout.text();
+        assert!(text.ends_with('…'), "{text:?}");
+        assert!(text.len() < "hello world".len());
+        assert!(measured_size(&layout).width <= px(50.));
+        assert_eq!(measured_size(&layout).height, layout.line_height());
+    }
+
+    #[gpui::test]
+    fn test_text_with_highlights(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let text = StyledText::new("hello world").with_default_highlights(
+            &TextStyle::default(),
+            [(
+                6..11,
+                HighlightStyle {
+                    color: Some(red()),
+                    ..Default::default()
+                },
+            )],
+        );
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), AvailableSpace::min_size(), |_, _| {
+            text
+        });
+
+        // The unhighlighted prefix takes on the default style.
+        let runs = layout.0.borrow().as_ref().unwrap().lines[0]
+            .decoration_runs
+            .iter()
+            .map(|run| (run.len, run.color))
+            .collect::<Vec<_>>();
+        assert_eq!(runs, [(6, black()), (5, red())]);
+    }
+
+    #[gpui::test]
+    fn test_empty_text(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let text = StyledText::new("");
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), AvailableSpace::min_size(), |_, _| {
+            text
+        });
+
+        // Empty text still occupies a line, so that empty labels don't collapse.
+        assert_eq!(line_count(&layout), 1);
+        assert_eq!(
+            measured_size(&layout),
+            Size {
+                width: px(0.),
+                height: layout.line_height(),
+            }
+        );
+    }
+
+    #[gpui::test]
+    fn test_index_for_position(cx: &mut TestAppContext) {
+        let cx = cx.add_empty_window();
+        let text = StyledText::new("hello\nworld");
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), AvailableSpace::min_size(), |_, _| {
+            text
+        });
+
+        // Each character of the test text system is 0.6em wide.
+        let char_width = layout.0.borrow().as_ref().unwrap().lines[0].width() / 5.;
+        let line_height = layout.line_height();
+        let second_line = line_height * 1.5;
+
+        // Two thirds of the way across the "r" of "world".
+        let position = point(char_width * 2.67, second_line);
+        assert_eq!(layout.index_for_position(position), Ok(8));
+        assert_eq!(layout.closest_index_for_position(position), Ok(9));
+
+        let position = point(char_width * 0.2, line_height * 0.5);
+        assert_eq!(layout.index_for_position(position), Ok(0));
+        assert_eq!(layout.closest_index_for_position(position), Ok(0));
+
+        // Positions beside the text report the nearest index as an error.
+        let position = point(char_width * 8., second_line);
+        assert_eq!(layout.index_for_position(position), Err(11));
+        assert_eq!(layout.closest_index_for_position(position), Err(11));
+        let position = point(px(0.), px(-1.));
+        assert_eq!(layout.closest_index_for_position(position), Err(0));
+    }
+
+    #[gpui::test]
+    fn test_text_that_fails_to_shape(cx: &mut TestAppContext) {
+        let text_system = Arc::new(TestTextSystem::default());
+        text_system.fail_font_lookups();
+        cx.update(|cx| cx.set_platform_text_system(text_system));
+        let cx = cx.add_empty_window();
+        let text = StyledText::new("hello\nworld");
+        let layout = text.layout().clone();
+        cx.draw(point(px(0.), px(0.)), AvailableSpace::min_size(), |_, _| {
+            text
+        });
+
+        // A placeholder a line tall is laid out instead of the text collapsing to nothing.
+        let line_height = layout.line_height();
+        assert_eq!(measured_size(&layout), size(line_height, line_height));
+        assert_eq!(line_count(&layout), 0);
+        assert!(layout.0.borrow().as_ref().unwrap().shaping_failed);
+        assert_eq!(
+            layout.bounds().size,
+            size(line_height, line_height),
+            "the placeholder should be painted where the text would be"
+        );
+    }
+}
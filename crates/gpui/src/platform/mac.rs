@@ -3,6 +3,7 @@
 mod dispatcher;
 mod display;
 mod display_link;
+mod display_reconfiguration;
 mod events;
 mod screen_capture;
 
@@ -45,6 +46,7 @@ use std::{
 pub(crate) use dispatcher::*;
 pub(crate) use display::*;
 pub(crate) use display_link::*;
+pub(crate) use display_reconfiguration::AdaptiveDisplayLink;
 pub(crate) use platform::*;
 pub(crate) use window::*;
 
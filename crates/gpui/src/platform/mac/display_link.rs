@@ -1,19 +1,313 @@
+use super::display_id_for_screen;
 use crate::{
     dispatch_get_main_queue,
     dispatch_sys::{
-        _dispatch_source_type_data_add, dispatch_resume, dispatch_set_context,
+        _dispatch_source_type_data_add, _dispatch_source_type_timer, DISPATCH_QUEUE_PRIORITY_HIGH,
+        DISPATCH_TIME_NOW, dispatch_get_global_queue, dispatch_resume, dispatch_set_context,
         dispatch_source_cancel, dispatch_source_create, dispatch_source_merge_data,
-        dispatch_source_set_event_handler_f, dispatch_source_t, dispatch_suspend,
+        dispatch_source_set_event_handler_f, dispatch_source_set_timer, dispatch_source_t,
+        dispatch_suspend, dispatch_time,
     },
 };
 use anyhow::Result;
-use core_graphics::display::CGDirectDisplayID;
-use std::ffi::c_void;
+use cocoa::base::{id, nil};
+use core_graphics::display::{CGDirectDisplayID, CGDisplay};
+use objc::{class, msg_send, sel, sel_impl};
+use parking_lot::{Condvar, Mutex};
+use std::{
+    ffi::c_void,
+    sync::atomic::{AtomicI64, AtomicU32, AtomicU64, Ordering},
+};
 use util::ResultExt;
 
+pub(crate) use sys::{CVSMPTETime, CVTimeStamp, Timecode};
+
+/// The rate at which frames are delivered when no CoreVideo display link could
+/// be created, until [`DisplayLink::set_fallback_refresh_rate`] changes it.
+pub const DEFAULT_FALLBACK_REFRESH_RATE: u32 = 60;
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+/// Drives frame callbacks from a CoreVideo display link bound to one display.
+///
+/// Some environments, like virtualized macOS, can't create display links. In
+/// that case frames are driven by a timer on a global dispatch queue instead,
+/// at [`DEFAULT_FALLBACK_REFRESH_RATE`] unless configured otherwise.
+///
+/// The display link invokes its output callback on a dedicated CoreVideo thread,
+/// so nothing reachable from that callback may assume it runs on the main thread.
+/// Only `DisplayLinkState`, whose fields are atomics or lock-protected, is shared
+/// with that thread. The callback passed to [`DisplayLink::new`] and its `data`
+/// pointer are never touched there: each frame is forwarded through a dispatch
+/// source, and they are only ever invoked on the main queue.
 pub struct DisplayLink {
-    display_link: sys::DisplayLink,
-    frame_requests: dispatch_source_t,
+    clock: FrameClock,
+    frame_requests: Option<dispatch_source_t>,
+    // Boxed so its address stays stable while the display link thread holds a pointer to it.
+    state: Box<DisplayLinkState>,
+}
+
+/// What invokes the frame callback.
+enum FrameClock {
+    DisplayLink(sys::DisplayLink),
+    /// A dispatch timer standing in for a display link that couldn't be created.
+    /// Dispatch sources are created suspended, so it starts out stopped.
+    FallbackTimer {
+        timer: dispatch_source_t,
+        running: bool,
+    },
+}
+
+/// Where the display link thread delivers frames.
+enum FrameTarget {
+    /// Frames are forwarded to the main queue through a dispatch source.
+    Dispatch(dispatch_source_t),
+    /// Frames are passed to a closure on the display link thread, so tests can
+    /// observe them without a main queue.
+    #[cfg(test)]
+    Closure(Mutex<Box<dyn FnMut(&CVTimeStamp) + Send>>),
+}
+
+/// State shared with the CoreVideo display link thread.
+struct DisplayLinkState {
+    target: FrameTarget,
+    /// The maximum number of frames per second to forward, or 0 for no cap.
+    fps_cap: AtomicU32,
+    /// Accumulated video time since the last forwarded frame, in units of the
+    /// timestamp's `video_time_scale`, multiplied by `fps_cap` so that the target
+    /// frame period doesn't have to be rounded.
+    elapsed_video_time: AtomicI64,
+    /// Tracks callbacks running on the display link thread, so that stopping can
+    /// wait for them before this state is freed.
+    callbacks: CallbackGate,
+    stats: FramePacing,
+    /// The refresh period of the fallback timer, in nanoseconds.
+    fallback_refresh_period: AtomicI64,
+    /// The number of frames the fallback timer has delivered, from which it
+    /// derives each frame's video time.
+    fallback_frames: AtomicI64,
+}
+
+/// Frame pacing counters for a [`DisplayLink`], returned by [`DisplayLink::stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DisplayLinkStats {
+    /// The number of times the display link invoked its output callback.
+    pub callbacks: u64,
+    /// The estimated number of vsyncs for which no callback was invoked, e.g.
+    /// because the display link thread was descheduled.
+    pub skipped_vsyncs: u64,
+}
+
+/// Counts callbacks and estimates skipped vsyncs from the gaps between
+/// consecutive output timestamps.
+struct FramePacing {
+    callbacks: AtomicU64,
+    skipped_vsyncs: AtomicU64,
+    /// The video time of the last timestamp with a valid video time and refresh
+    /// period, or `i64::MIN` if there's none to compare the next one against.
+    last_video_time: AtomicI64,
+}
+
+impl Default for FramePacing {
+    fn default() -> Self {
+        Self {
+            callbacks: AtomicU64::new(0),
+            skipped_vsyncs: AtomicU64::new(0),
+            last_video_time: AtomicI64::new(i64::MIN),
+        }
+    }
+}
+
+impl FramePacing {
+    fn record(&self, time: Option<&CVTimeStamp>) {
+        self.callbacks.fetch_add(1, Ordering::Relaxed);
+
+        let valid = sys::kCVTimeStampVideoTimeValid | sys::kCVTimeStampVideoRefreshPeriodValid;
+        let Some(time) =
+            time.filter(|time| time.flags & valid == valid && time.video_refresh_period > 0)
+        else {
+            // Without a valid video time, the gap to the next timestamp can't be
+            // attributed to skipped vsyncs, so start over from the next valid one.
+            self.last_video_time.store(i64::MIN, Ordering::Relaxed);
+            return;
+        };
+
+        let last_video_time = self
+            .last_video_time
+            .swap(time.video_time, Ordering::Relaxed);
+        if last_video_time == i64::MIN || time.video_time <= last_video_time {
+            return;
+        }
+
+        // Round to the nearest number of refresh periods, since video times jitter
+        // slightly around the nominal period.
+        let period = time.video_refresh_period;
+        let periods = (time.video_time - last_video_time + period / 2) / period;
+        if periods > 1 {
+            self.skipped_vsyncs
+                .fetch_add(periods as u64 - 1, Ordering::Relaxed);
+        }
+    }
+
+    /// Forgets the last timestamp, e.g. because the display link was paused or
+    /// moved to a display with another refresh rate.
+    fn reset_last_video_time(&self) {
+        self.last_video_time.store(i64::MIN, Ordering::Relaxed);
+    }
+
+    fn stats(&self) -> DisplayLinkStats {
+        DisplayLinkStats {
+            callbacks: self.callbacks.load(Ordering::Relaxed),
+            skipped_vsyncs: self.skipped_vsyncs.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A gate that callbacks pass through while the display link is running.
+///
+/// Closing the gate blocks until every callback that already entered has left,
+/// and callbacks arriving afterwards return without doing any work.
+#[derive(Default)]
+struct CallbackGate {
+    state: Mutex<CallbackGateState>,
+    idle: Condvar,
+}
+
+#[derive(Default)]
+struct CallbackGateState {
+    open: bool,
+    in_flight: usize,
+}
+
+struct CallbackGuard<'a>(&'a CallbackGate);
+
+impl CallbackGate {
+    fn open(&self) {
+        self.state.lock().open = true;
+    }
+
+    /// Returns `None` if the gate is closed, in which case the callback must not run.
+    fn enter(&self) -> Option<CallbackGuard<'_>> {
+        let mut state = self.state.lock();
+        if !state.open {
+            return None;
+        }
+        state.in_flight += 1;
+        Some(CallbackGuard(self))
+    }
+
+    /// Closes the gate and waits for any in-flight callbacks to complete.
+    fn close(&self) {
+        let mut state = self.state.lock();
+        state.open = false;
+        while state.in_flight > 0 {
+            self.idle.wait(&mut state);
+        }
+    }
+}
+
+impl Drop for CallbackGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock();
+        state.in_flight -= 1;
+        if state.in_flight == 0 {
+            self.0.idle.notify_all();
+        }
+    }
+}
+
+impl DisplayLinkState {
+    /// Returns whether a frame with the given timestamp should be forwarded to the main thread.
+    fn should_forward_frame(&self, time: &CVTimeStamp) -> bool {
+        let fps_cap = self.fps_cap.load(Ordering::Relaxed);
+        if fps_cap == 0
+            || time.flags & sys::kCVTimeStampVideoRefreshPeriodValid == 0
+            || time.video_time_scale <= 0
+            || time.video_refresh_period <= 0
+        {
+            return true;
+        }
+
+        // Both periods are scaled by the cap: a frame is due once the elapsed time
+        // reaches `video_time_scale / fps_cap`, which usually isn't a whole number.
+        let refresh_period = time.video_refresh_period * fps_cap as i64;
+        let target_period = time.video_time_scale as i64;
+        if target_period <= refresh_period {
+            return true;
+        }
+
+        // Carry the remainder over to the next frame instead of resetting, so that
+        // caps that don't evenly divide the display rate still average out to the
+        // requested rate rather than rounding down to a lower one.
+        let elapsed = self.elapsed_video_time.load(Ordering::Relaxed) + refresh_period;
+        if elapsed >= target_period {
+            // Clamp the carry so a stall can never queue up a burst of frames.
+            let carry = (elapsed - target_period).min(refresh_period);
+            self.elapsed_video_time.store(carry, Ordering::Relaxed);
+            true
+        } else {
+            self.elapsed_video_time.store(elapsed, Ordering::Relaxed);
+            false
+        }
+    }
+}
+
+unsafe extern "C" fn display_link_callback(
+    _display_link_out: *mut sys::CVDisplayLink,
+    _current_time: *const CVTimeStamp,
+    output_time: *const CVTimeStamp,
+    _flags_in: i64,
+    _flags_out: *mut i64,
+    state: *mut c_void,
+) -> i32 {
+    unsafe {
+        let state = &*(state as *const DisplayLinkState);
+        deliver_frame(state, output_time.as_ref());
+        0
+    }
+}
+
+unsafe extern "C" fn fallback_timer_callback(state: *mut c_void) {
+    unsafe {
+        let state = &*(state as *const DisplayLinkState);
+        let refresh_period = state.fallback_refresh_period.load(Ordering::Relaxed);
+        let frame = state.fallback_frames.fetch_add(1, Ordering::Relaxed);
+        let output_time = CVTimeStamp {
+            version: 0,
+            video_time_scale: NANOS_PER_SECOND as i32,
+            video_time: frame * refresh_period,
+            host_time: 0,
+            rate_scalar: 1.0,
+            video_refresh_period: refresh_period,
+            smpte_time: Default::default(),
+            flags: sys::kCVTimeStampVideoTimeValid | sys::kCVTimeStampVideoRefreshPeriodValid,
+            reserved: 0,
+        };
+        deliver_frame(state, Some(&output_time));
+    }
+}
+
+unsafe fn deliver_frame(state: &DisplayLinkState, output_time: Option<&CVTimeStamp>) {
+    unsafe {
+        let Some(_guard) = state.callbacks.enter() else {
+            return;
+        };
+        state.stats.record(output_time);
+        let forward = output_time.is_none_or(|output_time| state.should_forward_frame(output_time));
+        if forward {
+            match &state.target {
+                FrameTarget::Dispatch(frame_requests) => {
+                    dispatch_source_merge_data(*frame_requests, 1);
+                }
+                #[cfg(test)]
+                FrameTarget::Closure(callback) => {
+                    if let Some(output_time) = output_time {
+                        (callback.lock())(output_time);
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl DisplayLink {
@@ -22,21 +316,6 @@ impl DisplayLink {
         data: *mut c_void,
         callback: unsafe extern "C" fn(*mut c_void),
     ) -> Result<DisplayLink> {
-        unsafe extern "C" fn display_link_callback(
-            _display_link_out: *mut sys::CVDisplayLink,
-            _current_time: *const sys::CVTimeStamp,
-            _output_time: *const sys::CVTimeStamp,
-            _flags_in: i64,
-            _flags_out: *mut i64,
-            frame_requests: *mut c_void,
-        ) -> i32 {
-            unsafe {
-                let frame_requests = frame_requests as dispatch_source_t;
-                dispatch_source_merge_data(frame_requests, 1);
-                0
-            }
-        }
-
         unsafe {
             let frame_requests = dispatch_source_create(
                 &_dispatch_source_type_data_add,
@@ -52,49 +331,658 @@ impl DisplayLink {
             );
             dispatch_source_set_event_handler_f(frame_requests, Some(callback));
 
-            let display_link = sys::DisplayLink::new(
+            Self::with_target(
                 display_id,
-                display_link_callback,
-                frame_requests as *mut c_void,
-            )?;
+                FrameTarget::Dispatch(frame_requests),
+                Some(frame_requests),
+            )
+        }
+    }
 
-            Ok(Self {
-                display_link,
-                frame_requests,
-            })
+    /// Creates a display link that calls `callback` with the output timestamp of
+    /// every frame. The callback runs on the display link thread, not the main
+    /// thread, which is why it must be `Send`, and is dropped along with the
+    /// display link. Windows use [`DisplayLink::new`], so this is only for tests.
+    #[cfg(test)]
+    pub fn with_callback(
+        display_id: CGDirectDisplayID,
+        callback: impl FnMut(&CVTimeStamp) + Send + 'static,
+    ) -> Result<DisplayLink> {
+        Self::with_target(
+            display_id,
+            FrameTarget::Closure(Mutex::new(Box::new(callback))),
+            None,
+        )
+    }
+
+    fn with_target(
+        display_id: CGDirectDisplayID,
+        target: FrameTarget,
+        frame_requests: Option<dispatch_source_t>,
+    ) -> Result<DisplayLink> {
+        Self::with_target_and_clock(target, frame_requests, |user_info| unsafe {
+            sys::DisplayLink::new(display_id, display_link_callback, user_info)
+        })
+    }
+
+    fn with_target_and_clock(
+        target: FrameTarget,
+        frame_requests: Option<dispatch_source_t>,
+        create_display_link: impl FnOnce(*mut c_void) -> Result<sys::DisplayLink>,
+    ) -> Result<DisplayLink> {
+        let state = Box::new(DisplayLinkState {
+            target,
+            fps_cap: AtomicU32::new(0),
+            elapsed_video_time: AtomicI64::new(0),
+            callbacks: CallbackGate::default(),
+            stats: FramePacing::default(),
+            fallback_refresh_period: AtomicI64::new(
+                NANOS_PER_SECOND / DEFAULT_FALLBACK_REFRESH_RATE as i64,
+            ),
+            fallback_frames: AtomicI64::new(0),
+        });
+        let user_info = &*state as *const DisplayLinkState as *mut c_void;
+        let clock = match create_display_link(user_info) {
+            Ok(display_link) => FrameClock::DisplayLink(display_link),
+            Err(error) => {
+                log::warn!(
+                    "{error:#}, falling back to a {DEFAULT_FALLBACK_REFRESH_RATE}Hz timer for frames"
+                );
+                unsafe {
+                    let timer = dispatch_source_create(
+                        &_dispatch_source_type_timer,
+                        0,
+                        0,
+                        dispatch_get_global_queue(
+                            DISPATCH_QUEUE_PRIORITY_HIGH.try_into().unwrap(),
+                            0,
+                        ),
+                    );
+                    anyhow::ensure!(!timer.is_null(), "could not create fallback frame timer");
+                    dispatch_set_context(
+                        crate::dispatch_sys::dispatch_object_t { _ds: timer },
+                        user_info,
+                    );
+                    dispatch_source_set_event_handler_f(timer, Some(fallback_timer_callback));
+                    FrameClock::FallbackTimer {
+                        timer,
+                        running: false,
+                    }
+                }
+            }
+        };
+
+        let mut this = Self {
+            clock,
+            frame_requests,
+            state,
+        };
+        this.set_fallback_refresh_rate(DEFAULT_FALLBACK_REFRESH_RATE);
+        Ok(this)
+    }
+
+    /// Whether frames are driven by a timer because no display link could be created.
+    #[cfg(test)]
+    pub fn is_fallback(&self) -> bool {
+        matches!(self.clock, FrameClock::FallbackTimer { .. })
+    }
+
+    /// Sets the rate at which the fallback timer delivers frames. Has no effect
+    /// if a display link could be created.
+    pub fn set_fallback_refresh_rate(&mut self, refresh_rate: u32) {
+        let FrameClock::FallbackTimer { timer, .. } = self.clock else {
+            return;
+        };
+        let refresh_period = NANOS_PER_SECOND / refresh_rate.max(1) as i64;
+        self.state
+            .fallback_refresh_period
+            .store(refresh_period, Ordering::Relaxed);
+        self.state.elapsed_video_time.store(0, Ordering::Relaxed);
+        self.state.stats.reset_last_video_time();
+        unsafe {
+            dispatch_source_set_timer(
+                timer,
+                dispatch_time(DISPATCH_TIME_NOW as u64, refresh_period),
+                refresh_period as u64,
+                // Allow some coalescing, but not enough to visibly drop frames.
+                (refresh_period / 10) as u64,
+            );
+        }
+    }
+
+    /// Creates and starts a display link for the display that the application's
+    /// main window is on, falling back to the main display if there's no main window.
+    pub fn start_for_active_display(
+        data: *mut c_void,
+        callback: unsafe extern "C" fn(*mut c_void),
+    ) -> Result<DisplayLink> {
+        let display_id = unsafe { active_display_id() };
+        let mut display_link = Self::new(display_id, data, callback)?;
+        display_link.start()?;
+        Ok(display_link)
+    }
+
+    /// The display the link is bound to, or `None` if frames are driven by the
+    /// fallback timer, which isn't bound to any display.
+    pub fn current_display(&self) -> Option<CGDirectDisplayID> {
+        match &self.clock {
+            FrameClock::DisplayLink(display_link) => Some(display_link.current_display()),
+            FrameClock::FallbackTimer { .. } => None,
+        }
+    }
+
+    /// Moves the display link to another display, e.g. when its window moves to a
+    /// different screen. This can be called while the link is running, and frames
+    /// keep being delivered without a restart.
+    pub fn set_current_display(&mut self, display_id: CGDirectDisplayID) -> Result<()> {
+        if let FrameClock::DisplayLink(display_link) = &mut self.clock {
+            unsafe { display_link.set_current_display(display_id)? };
         }
+        // The new display may refresh at a different rate.
+        self.state.elapsed_video_time.store(0, Ordering::Relaxed);
+        self.state.stats.reset_last_video_time();
+        Ok(())
+    }
+
+    /// Frame pacing counters accumulated since the display link was created.
+    pub fn stats(&self) -> DisplayLinkStats {
+        self.state.stats.stats()
+    }
+
+    /// Limit the rate at which frame callbacks are delivered, e.g. to render at 30fps
+    /// on a 60Hz display. The underlying display link keeps running at the display's
+    /// refresh rate and frames in between are skipped. Pass `None` to remove the cap.
+    #[cfg(test)]
+    pub fn set_fps_cap(&mut self, target: Option<u32>) {
+        self.state
+            .fps_cap
+            .store(target.unwrap_or(0), Ordering::Relaxed);
+        self.state.elapsed_video_time.store(0, Ordering::Relaxed);
     }
 
     pub fn start(&mut self) -> Result<()> {
+        // Vsyncs that pass while the link is stopped aren't skipped.
+        self.state.stats.reset_last_video_time();
+        self.state.callbacks.open();
         unsafe {
-            dispatch_resume(crate::dispatch_sys::dispatch_object_t {
-                _ds: self.frame_requests,
-            });
-            self.display_link.start()?;
+            if let Some(frame_requests) = self.frame_requests {
+                dispatch_resume(crate::dispatch_sys::dispatch_object_t {
+                    _ds: frame_requests,
+                });
+            }
+            match &mut self.clock {
+                FrameClock::DisplayLink(display_link) => {
+                    if !display_link.is_running() {
+                        display_link.start()?;
+                    }
+                }
+                FrameClock::FallbackTimer { timer, running } => {
+                    if !*running {
+                        dispatch_resume(crate::dispatch_sys::dispatch_object_t { _ds: *timer });
+                        *running = true;
+                    }
+                }
+            }
         }
         Ok(())
     }
 
+    /// Whether the display link is currently running.
+    #[cfg(test)]
+    pub fn is_running(&self) -> bool {
+        match &self.clock {
+            FrameClock::DisplayLink(display_link) => display_link.is_running(),
+            FrameClock::FallbackTimer { running, .. } => *running,
+        }
+    }
+
+    /// Stops the display link. Once this returns, no callback is running on the
+    /// display link thread and none will run until the link is started again.
     pub fn stop(&mut self) -> Result<()> {
-        unsafe {
-            dispatch_suspend(crate::dispatch_sys::dispatch_object_t {
-                _ds: self.frame_requests,
-            });
-            self.display_link.stop()?;
+        let result = unsafe {
+            if let Some(frame_requests) = self.frame_requests {
+                dispatch_suspend(crate::dispatch_sys::dispatch_object_t {
+                    _ds: frame_requests,
+                });
+            }
+            match &mut self.clock {
+                FrameClock::DisplayLink(display_link) => display_link.stop(),
+                FrameClock::FallbackTimer { timer, running } => {
+                    if *running {
+                        dispatch_suspend(crate::dispatch_sys::dispatch_object_t { _ds: *timer });
+                        *running = false;
+                    }
+                    Ok(())
+                }
+            }
+        };
+        // Wait for a callback that was already in progress when the display link
+        // stopped, even if stopping failed, since the state it reads is freed on drop.
+        self.state.callbacks.close();
+        result
+    }
+}
+
+/// Returns the display that the application's main window is on, or the main
+/// display if there's no main window.
+pub(super) unsafe fn active_display_id() -> CGDirectDisplayID {
+    unsafe {
+        let app: id = msg_send![class!(NSApplication), sharedApplication];
+        let window: id = msg_send![app, mainWindow];
+        if window != nil {
+            let screen: id = msg_send![window, screen];
+            if screen != nil {
+                return display_id_for_screen(screen);
+            }
         }
-        Ok(())
+        CGDisplay::main().id
     }
 }
 
 impl Drop for DisplayLink {
     fn drop(&mut self) {
+        // `stop` waits for in-flight callbacks, so the display link and the state
+        // its callback reads can be released safely afterwards.
         self.stop().log_err();
-        unsafe {
-            dispatch_source_cancel(self.frame_requests);
+        if let Some(frame_requests) = self.frame_requests {
+            unsafe {
+                dispatch_source_cancel(frame_requests);
+            }
+        }
+        if let FrameClock::FallbackTimer { timer, .. } = self.clock {
+            unsafe {
+                dispatch_source_cancel(timer);
+                // A cancelled source doesn't invoke its handler anymore, but it has to
+                // be resumed to balance the suspension from `stop`.
+                dispatch_resume(crate::dispatch_sys::dispatch_object_t { _ds: timer });
+            }
         }
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::{
+        CallbackGate, DisplayLink, DisplayLinkState, DisplayLinkStats, FramePacing, FrameTarget,
+        Timecode, fallback_timer_callback, sys,
+    };
+    use anyhow::anyhow;
+    use core_graphics::display::CGDisplay;
+    use parking_lot::Mutex;
+    use std::{
+        ffi::c_void,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, AtomicI64, AtomicU32, AtomicUsize, Ordering},
+            mpsc,
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn test_callback_gate_waits_for_in_flight_callbacks() {
+        let gate = Arc::new(CallbackGate::default());
+        assert!(gate.enter().is_none(), "callbacks can't run before opening");
+
+        gate.open();
+        let callback_finished = Arc::new(AtomicBool::new(false));
+        let (entered_tx, entered_rx) = mpsc::channel();
+        let callback = thread::spawn({
+            let gate = gate.clone();
+            let callback_finished = callback_finished.clone();
+            move || {
+                let _guard = gate.enter().unwrap();
+                entered_tx.send(()).unwrap();
+                thread::sleep(Duration::from_millis(50));
+                callback_finished.store(true, Ordering::SeqCst);
+            }
+        });
+
+        entered_rx.recv().unwrap();
+        gate.close();
+        assert!(
+            callback_finished.load(Ordering::SeqCst),
+            "close returned while a callback was still running"
+        );
+        assert!(gate.enter().is_none(), "callbacks can't run after closing");
+        callback.join().unwrap();
+
+        gate.open();
+        assert!(gate.enter().is_some());
+    }
+
+    #[test]
+    #[ignore = "requires a display and real time to pass"]
+    fn test_display_link_with_callback() {
+        let frames = Arc::new(AtomicUsize::new(0));
+        let mut display_link = DisplayLink::with_callback(CGDisplay::main().id, {
+            let frames = frames.clone();
+            move |_| {
+                frames.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .unwrap();
+
+        display_link.start().unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while frames.load(Ordering::SeqCst) < 3 {
+            assert!(
+                Instant::now() < deadline,
+                "display link didn't deliver frames"
+            );
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        display_link.stop().unwrap();
+        let frames_after_stop = frames.load(Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(frames.load(Ordering::SeqCst), frames_after_stop);
+
+        // Dropping the display link releases the callback and everything it captured.
+        drop(display_link);
+        assert_eq!(Arc::strong_count(&frames), 1);
+    }
+
+    #[test]
+    fn test_fallback_timer_when_display_link_creation_fails() {
+        let frames = Arc::new(Mutex::new(Vec::new()));
+        let mut display_link = DisplayLink::with_target_and_clock(
+            FrameTarget::Closure(Mutex::new(Box::new({
+                let frames = frames.clone();
+                move |time: &sys::CVTimeStamp| {
+                    frames.lock().push((time.video_time, time.refresh_rate()));
+                }
+            }))),
+            None,
+            |_| Err(anyhow!("could not create display link, code: -6661")),
+        )
+        .unwrap();
+        assert!(display_link.is_fallback());
+        assert!(!display_link.is_running());
+
+        // Fire the timer's handler directly rather than waiting on the timer, so
+        // the delivered frames don't depend on scheduling.
+        display_link.set_fallback_refresh_rate(100);
+        display_link.state.callbacks.open();
+        let state = &*display_link.state as *const DisplayLinkState as *mut c_void;
+        for _ in 0..3 {
+            unsafe { fallback_timer_callback(state) };
+        }
+        assert_eq!(
+            *frames.lock(),
+            [
+                (0, Some(100.0)),
+                (10_000_000, Some(100.0)),
+                (20_000_000, Some(100.0))
+            ]
+        );
+        assert_eq!(
+            display_link.stats(),
+            DisplayLinkStats {
+                callbacks: 3,
+                skipped_vsyncs: 0
+            }
+        );
+
+        // Capping the rate at half the timer's skips every other frame.
+        display_link.set_fps_cap(Some(50));
+        for _ in 0..4 {
+            unsafe { fallback_timer_callback(state) };
+        }
+        assert_eq!(
+            frames.lock()[3..],
+            [(40_000_000, Some(100.0)), (60_000_000, Some(100.0))]
+        );
+        display_link.set_fps_cap(None);
+
+        display_link.state.callbacks.close();
+        unsafe { fallback_timer_callback(state) };
+        assert_eq!(frames.lock().len(), 5);
+
+        display_link.start().unwrap();
+        assert!(display_link.is_running());
+        display_link.stop().unwrap();
+        assert!(!display_link.is_running());
+
+        drop(display_link);
+        assert_eq!(Arc::strong_count(&frames), 1);
+    }
+
+    #[test]
+    #[ignore = "requires a display"]
+    fn test_display_link_is_running() {
+        let mut display_link = DisplayLink::with_callback(CGDisplay::main().id, |_| {}).unwrap();
+        assert!(!display_link.is_running());
+
+        for _ in 0..2 {
+            display_link.start().unwrap();
+            assert!(display_link.is_running());
+            display_link.stop().unwrap();
+            assert!(!display_link.is_running());
+        }
+    }
+
+    fn timestamp(video_time_scale: i32, video_refresh_period: i64, flags: u64) -> sys::CVTimeStamp {
+        sys::CVTimeStamp {
+            version: 0,
+            video_time_scale,
+            video_time: 0,
+            host_time: 0,
+            rate_scalar: 1.0,
+            video_refresh_period,
+            smpte_time: Default::default(),
+            flags,
+            reserved: 0,
+        }
+    }
+
+    fn smpte_time(
+        time_type: u32,
+        hours: i16,
+        minutes: i16,
+        seconds: i16,
+        frames: i16,
+    ) -> sys::CVSMPTETime {
+        sys::CVSMPTETime {
+            time_type,
+            flags: sys::kCVSMPTETimeValid | sys::kCVSMPTETimeRunning,
+            hours,
+            minutes,
+            seconds,
+            frames,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_non_drop_frame_timecode() {
+        let timecode = smpte_time(sys::kCVSMPTETimeType25, 1, 2, 3, 4)
+            .to_timecode()
+            .unwrap();
+        assert_eq!(
+            timecode,
+            Timecode {
+                hours: 1,
+                minutes: 2,
+                seconds: 3,
+                frames: 4,
+                frames_per_second: 25,
+                drop_frame: false,
+            }
+        );
+        assert_eq!(timecode.frame_number(), (3600 + 120 + 3) * 25 + 4);
+
+        // Nothing is skipped at minute boundaries without drop-frame.
+        let timecode = smpte_time(sys::kCVSMPTETimeType2997, 0, 1, 0, 0)
+            .to_timecode()
+            .unwrap();
+        assert!(!timecode.drop_frame);
+        assert_eq!(timecode.frame_number(), 1800);
+
+        assert_eq!(
+            smpte_time(sys::kCVSMPTETimeType25, 0, 0, 0, 25).to_timecode(),
+            None
+        );
+        let invalid = sys::CVSMPTETime {
+            flags: sys::kCVSMPTETimeRunning,
+            ..smpte_time(sys::kCVSMPTETimeType25, 1, 2, 3, 4)
+        };
+        assert_eq!(invalid.to_timecode(), None);
+    }
+
+    #[test]
+    fn test_drop_frame_timecode() {
+        for time_type in [sys::kCVSMPTETimeType2997Drop, sys::kCVSMPTETimeType30Drop] {
+            // The last frame of the first minute is followed by 00:01:00;02.
+            let last = smpte_time(time_type, 0, 0, 59, 29).to_timecode().unwrap();
+            assert!(last.drop_frame);
+            assert_eq!(last.frame_number(), 1799);
+            assert_eq!(smpte_time(time_type, 0, 1, 0, 0).to_timecode(), None);
+            assert_eq!(smpte_time(time_type, 0, 1, 0, 1).to_timecode(), None);
+            let next = smpte_time(time_type, 0, 1, 0, 2).to_timecode().unwrap();
+            assert_eq!(next.frame_number(), 1800);
+
+            // No labels are dropped on every tenth minute.
+            let tenth_minute = smpte_time(time_type, 0, 10, 0, 0).to_timecode().unwrap();
+            assert_eq!(tenth_minute.frame_number(), 17982);
+
+            // One hour of drop-frame timecode is 107,892 frames, which at 29.97fps
+            // is an hour of wall clock time.
+            let hour = smpte_time(time_type, 1, 0, 0, 0).to_timecode().unwrap();
+            assert_eq!(hour.frame_number(), 107_892);
+        }
+    }
+
+    #[test]
+    fn test_frame_pacing_stats() {
+        let valid = sys::kCVTimeStampVideoTimeValid | sys::kCVTimeStampVideoRefreshPeriodValid;
+        let at = |video_time, flags| sys::CVTimeStamp {
+            video_time,
+            ..timestamp(600, 10, flags)
+        };
+
+        let pacing = FramePacing::default();
+        for video_time in [0, 10, 21, 29] {
+            pacing.record(Some(&at(video_time, valid)));
+        }
+        assert_eq!(
+            pacing.stats(),
+            DisplayLinkStats {
+                callbacks: 4,
+                skipped_vsyncs: 0
+            }
+        );
+
+        // Two vsyncs pass between 29 and 60 without a callback.
+        pacing.record(Some(&at(60, valid)));
+        pacing.record(Some(&at(70, valid)));
+        assert_eq!(
+            pacing.stats(),
+            DisplayLinkStats {
+                callbacks: 6,
+                skipped_vsyncs: 2
+            }
+        );
+
+        // Timestamps without a valid video time are counted, but gaps around them
+        // aren't attributed to skipped vsyncs.
+        pacing.record(Some(&at(0, sys::kCVTimeStampHostTimeValid)));
+        pacing.record(None);
+        pacing.record(Some(&at(200, valid)));
+        pacing.record(Some(&at(210, valid)));
+        assert_eq!(
+            pacing.stats(),
+            DisplayLinkStats {
+                callbacks: 10,
+                skipped_vsyncs: 2
+            }
+        );
+
+        pacing.reset_last_video_time();
+        pacing.record(Some(&at(500, valid)));
+        assert_eq!(pacing.stats().skipped_vsyncs, 2);
+    }
+
+    #[test]
+    fn test_should_forward_frame() {
+        let state = DisplayLinkState {
+            target: FrameTarget::Closure(Mutex::new(Box::new(|_: &sys::CVTimeStamp| {}))),
+            fps_cap: AtomicU32::new(0),
+            elapsed_video_time: AtomicI64::new(0),
+            callbacks: CallbackGate::default(),
+            stats: FramePacing::default(),
+            fallback_refresh_period: AtomicI64::new(0),
+            fallback_frames: AtomicI64::new(0),
+        };
+        let valid = sys::kCVTimeStampVideoTimeValid | sys::kCVTimeStampVideoRefreshPeriodValid;
+        // A 60Hz display.
+        let frame = timestamp(600, 10, valid);
+        let forwarded_frames = |fps_cap: u32, time: &sys::CVTimeStamp, count: usize| {
+            state.fps_cap.store(fps_cap, Ordering::Relaxed);
+            state.elapsed_video_time.store(0, Ordering::Relaxed);
+            (0..count)
+                .map(|_| state.should_forward_frame(time))
+                .collect::<Vec<_>>()
+        };
+
+        // Without a cap, or with one at or above the refresh rate, every frame is forwarded.
+        for fps_cap in [0, 60, 120] {
+            assert_eq!(forwarded_frames(fps_cap, &frame, 4), [true; 4]);
+        }
+
+        assert_eq!(
+            forwarded_frames(30, &frame, 6),
+            [false, true, false, true, false, true]
+        );
+
+        // A cap that doesn't divide the refresh rate carries the remainder over, so
+        // the forwarded rate stays close to the cap.
+        assert_eq!(
+            forwarded_frames(45, &frame, 8),
+            [false, true, true, true, false, true, true, true]
+        );
+        // Ten seconds at 60Hz never forward more than ten seconds' worth of frames.
+        for fps_cap in [7, 24, 45, 59] {
+            let forwarded = forwarded_frames(fps_cap, &frame, 600);
+            let forwarded_count = forwarded.iter().filter(|forward| **forward).count();
+            assert!(
+                forwarded_count <= fps_cap as usize * 10,
+                "{forwarded_count} frames forwarded with a cap of {fps_cap}fps"
+            );
+        }
+        let forwarded = forwarded_frames(45, &frame, 600);
+        assert_eq!(forwarded.iter().filter(|forward| **forward).count(), 450);
+
+        // Frames with an unknown refresh period can't be paced, so they're forwarded.
+        for time in [
+            timestamp(600, 10, sys::kCVTimeStampVideoTimeValid),
+            timestamp(0, 10, valid),
+            timestamp(600, 0, valid),
+        ] {
+            assert_eq!(forwarded_frames(30, &time, 2), [true; 2]);
+        }
+    }
+
+    #[test]
+    fn test_refresh_rate() {
+        let valid = sys::kCVTimeStampVideoTimeValid | sys::kCVTimeStampVideoRefreshPeriodValid;
+        assert_eq!(timestamp(600, 10, valid).refresh_rate(), Some(60.0));
+
+        // A 120Hz ProMotion display, with a nanosecond time scale.
+        let refresh_rate = timestamp(1_000_000_000, 8_333_333, valid)
+            .refresh_rate()
+            .unwrap();
+        assert!((refresh_rate - 120.0).abs() < 0.001, "{refresh_rate}");
+
+        assert_eq!(
+            timestamp(600, 10, sys::kCVTimeStampVideoTimeValid).refresh_rate(),
+            None
+        );
+        assert_eq!(timestamp(600, 0, valid).refresh_rate(), None);
+    }
+}
+
 mod sys {
     //! Derived from display-link crate under the following license:
     //! <https://github.com/BrainiumLLC/display-link/blob/master/LICENSE-MIT>
@@ -157,6 +1045,20 @@ mod sys {
     pub const kCVTimeStampIsInterlaced: CVTimeStampFlags =
         kCVTimeStampTopField | kCVTimeStampBottomField;
 
+    impl CVTimeStamp {
+        /// The display's refresh rate in frames per second, or `None` if the
+        /// timestamp doesn't carry a valid refresh period.
+        pub fn refresh_rate(&self) -> Option<f64> {
+            if self.flags & kCVTimeStampVideoRefreshPeriodValid == 0
+                || self.video_time_scale <= 0
+                || self.video_refresh_period <= 0
+            {
+                return None;
+            }
+            Some(self.video_time_scale as f64 / self.video_refresh_period as f64)
+        }
+    }
+
     #[repr(C)]
     #[derive(Clone, Copy, Default)]
     pub(crate) struct CVSMPTETime {
@@ -187,6 +1089,74 @@ mod sys {
     pub const kCVSMPTETimeValid: CVSMPTETimeFlags = 1 << 0;
     pub const kCVSMPTETimeRunning: CVSMPTETimeFlags = 1 << 1;
 
+    /// A SMPTE timecode parsed from a [`CVSMPTETime`].
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub(crate) struct Timecode {
+        pub hours: u32,
+        pub minutes: u32,
+        pub seconds: u32,
+        pub frames: u32,
+        /// The number of frames labeled in each second, e.g. 30 for 29.97fps.
+        pub frames_per_second: u32,
+        /// Whether frame labels 0 and 1 are skipped at the start of every minute
+        /// that isn't a multiple of ten, to keep 29.97fps timecode in step with
+        /// wall clock time.
+        pub drop_frame: bool,
+    }
+
+    impl Timecode {
+        /// The number of frames since 00:00:00:00, accounting for the frame
+        /// labels that drop-frame timecode skips.
+        pub fn frame_number(&self) -> u64 {
+            let total_minutes = 60 * self.hours as u64 + self.minutes as u64;
+            let total_seconds = 60 * total_minutes + self.seconds as u64;
+            let frames = total_seconds * self.frames_per_second as u64 + self.frames as u64;
+            if self.drop_frame {
+                frames - 2 * (total_minutes - total_minutes / 10)
+            } else {
+                frames
+            }
+        }
+    }
+
+    impl CVSMPTETime {
+        /// Parses the timecode, returning `None` if it isn't marked valid or its
+        /// fields don't describe a frame that exists for its time type.
+        pub fn to_timecode(&self) -> Option<Timecode> {
+            if self.flags & kCVSMPTETimeValid == 0 {
+                return None;
+            }
+
+            let (frames_per_second, drop_frame) = match self.time_type {
+                kCVSMPTETimeType24 => (24, false),
+                kCVSMPTETimeType25 => (25, false),
+                kCVSMPTETimeType30Drop | kCVSMPTETimeType2997Drop => (30, true),
+                kCVSMPTETimeType30 | kCVSMPTETimeType2997 => (30, false),
+                kCVSMPTETimeType60 | kCVSMPTETimeType5994 => (60, false),
+                _ => return None,
+            };
+
+            let hours = u32::try_from(self.hours).ok()?;
+            let minutes = u32::try_from(self.minutes).ok().filter(|&m| m < 60)?;
+            let seconds = u32::try_from(self.seconds).ok().filter(|&s| s < 60)?;
+            let frames = u32::try_from(self.frames)
+                .ok()
+                .filter(|&f| f < frames_per_second)?;
+            if drop_frame && seconds == 0 && frames < 2 && minutes % 10 != 0 {
+                return None;
+            }
+
+            Some(Timecode {
+                hours,
+                minutes,
+                seconds,
+                frames,
+                frames_per_second,
+                drop_frame,
+            })
+        }
+    }
+
     pub type CVDisplayLinkOutputCallback = unsafe extern "C" fn(
         display_link_out: *mut CVDisplayLink,
         // A pointer to the current timestamp. This represents the timestamp when the callback is called.
@@ -212,6 +1182,7 @@ mod sys {
             display_link: &mut DisplayLinkRef,
             display_id: u32,
         ) -> i32;
+        pub fn CVDisplayLinkGetCurrentCGDisplay(display_link: &DisplayLinkRef) -> u32;
         pub fn CVDisplayLinkSetOutputCallback(
             display_link: &mut DisplayLinkRef,
             callback: CVDisplayLinkOutputCallback,
@@ -219,6 +1190,7 @@ mod sys {
         ) -> i32;
         pub fn CVDisplayLinkStart(display_link: &mut DisplayLinkRef) -> i32;
         pub fn CVDisplayLinkStop(display_link: &mut DisplayLinkRef) -> i32;
+        pub fn CVDisplayLinkIsRunning(display_link: &DisplayLinkRef) -> u8;
         pub fn CVDisplayLinkRelease(display_link: *mut CVDisplayLink);
         pub fn CVDisplayLinkRetain(display_link: *mut CVDisplayLink) -> *mut CVDisplayLink;
     }
@@ -254,6 +1226,29 @@ mod sys {
     }
 
     impl DisplayLinkRef {
+        /// Apple docs: [CVDisplayLinkSetCurrentCGDisplay](https://developer.apple.com/documentation/corevideo/1456768-cvdisplaylinksetcurrentcgdisplay?language=objc)
+        pub unsafe fn set_current_display(&mut self, display_id: CGDirectDisplayID) -> Result<()> {
+            unsafe {
+                let code = CVDisplayLinkSetCurrentCGDisplay(self, display_id);
+                anyhow::ensure!(
+                    code == 0,
+                    "could not assign display to display link, code: {}",
+                    code
+                );
+                Ok(())
+            }
+        }
+
+        /// Apple docs: [CVDisplayLinkGetCurrentCGDisplay](https://developer.apple.com/documentation/corevideo/cvdisplaylinkgetcurrentcgdisplay(_:)?language=objc)
+        pub fn current_display(&self) -> CGDirectDisplayID {
+            unsafe { CVDisplayLinkGetCurrentCGDisplay(self) }
+        }
+
+        /// Apple docs: [CVDisplayLinkIsRunning](https://developer.apple.com/documentation/corevideo/1457188-cvdisplaylinkisrunning?language=objc)
+        pub fn is_running(&self) -> bool {
+            unsafe { CVDisplayLinkIsRunning(self) != 0 }
+        }
+
         /// Apple docs: [CVDisplayLinkStart](https://developer.apple.com/documentation/corevideo/1457193-cvdisplaylinkstart?language=objc)
         pub unsafe fn start(&mut self) -> Result<()> {
             unsafe {
@@ -0,0 +1,209 @@
+//! Keeps display links running across display reconfigurations.
+//!
+//! A `CVDisplayLink` bound to a display that gets unplugged or disabled silently
+//! stops delivering frames. [`DisplayLink::start_adaptive`] registers for
+//! CoreGraphics reconfiguration notifications and moves the link to the active
+//! display when that happens. Windows drive their frames with one of these.
+//!
+//! Apple docs: [CGDisplayRegisterReconfigurationCallback](https://developer.apple.com/documentation/coregraphics/1455336-cgdisplayregisterreconfiguration?language=objc)
+#![allow(non_upper_case_globals)]
+
+use super::{
+    DisplayLink,
+    display_link::{DisplayLinkStats, active_display_id},
+};
+use anyhow::Result;
+use core_graphics::display::{CGDirectDisplayID, CGDisplay};
+use std::{
+    cell::{Cell, RefCell},
+    ffi::c_void,
+};
+use util::ResultExt;
+
+pub(crate) type CGDisplayChangeSummaryFlags = u32;
+
+pub(crate) const kCGDisplayBeginConfigurationFlag: CGDisplayChangeSummaryFlags = 1 << 0;
+pub(crate) const kCGDisplayRemoveFlag: CGDisplayChangeSummaryFlags = 1 << 5;
+pub(crate) const kCGDisplayDisabledFlag: CGDisplayChangeSummaryFlags = 1 << 9;
+
+type CGDisplayReconfigurationCallBack = unsafe extern "C" fn(
+    display: CGDirectDisplayID,
+    flags: CGDisplayChangeSummaryFlags,
+    user_info: *mut c_void,
+);
+
+#[link(name = "CoreGraphics", kind = "framework")]
+unsafe extern "C" {
+    fn CGDisplayRegisterReconfigurationCallback(
+        callback: CGDisplayReconfigurationCallBack,
+        user_info: *mut c_void,
+    ) -> i32;
+    fn CGDisplayRemoveReconfigurationCallback(
+        callback: CGDisplayReconfigurationCallBack,
+        user_info: *mut c_void,
+    ) -> i32;
+}
+
+/// Returns whether a display link bound to a display that received a
+/// reconfiguration event with the given flags has to be restarted.
+///
+/// Only the notification sent after the reconfiguration completes is considered,
+/// since the display is still usable when the one announcing it arrives.
+pub(crate) fn should_restart(flags: CGDisplayChangeSummaryFlags) -> bool {
+    flags & kCGDisplayBeginConfigurationFlag == 0
+        && flags & (kCGDisplayRemoveFlag | kCGDisplayDisabledFlag) != 0
+}
+
+/// A display link that follows the active display when the display it's bound
+/// to goes away. Created with [`DisplayLink::start_adaptive`].
+pub struct AdaptiveDisplayLink {
+    // Boxed so its address stays stable while it's registered for reconfiguration callbacks.
+    state: Box<AdaptiveDisplayLinkState>,
+}
+
+struct AdaptiveDisplayLinkState {
+    display_link: RefCell<DisplayLink>,
+    display_id: Cell<CGDirectDisplayID>,
+}
+
+impl DisplayLink {
+    /// Creates and starts a display link for `display_id`, or like
+    /// [`DisplayLink::start_for_active_display`] if it's `None`. If the display the
+    /// link is bound to is removed or disabled, the link is stopped and restarted on
+    /// whichever display is active afterwards.
+    pub fn start_adaptive(
+        display_id: Option<CGDirectDisplayID>,
+        data: *mut c_void,
+        callback: unsafe extern "C" fn(*mut c_void),
+    ) -> Result<AdaptiveDisplayLink> {
+        let display_link = match display_id {
+            Some(display_id) => {
+                let mut display_link = Self::new(display_id, data, callback)?;
+                display_link.start()?;
+                display_link
+            }
+            None => Self::start_for_active_display(data, callback)?,
+        };
+        // A fallback timer isn't bound to a display, so watch the one it stands in for.
+        let display_id = display_id
+            .or_else(|| display_link.current_display())
+            .unwrap_or_else(|| unsafe { active_display_id() });
+
+        let state = Box::new(AdaptiveDisplayLinkState {
+            display_link: RefCell::new(display_link),
+            display_id: Cell::new(display_id),
+        });
+        unsafe {
+            let code = CGDisplayRegisterReconfigurationCallback(
+                display_reconfiguration_callback,
+                &*state as *const AdaptiveDisplayLinkState as *mut c_void,
+            );
+            anyhow::ensure!(
+                code == 0,
+                "could not register display reconfiguration callback, code: {}",
+                code
+            );
+        }
+        Ok(AdaptiveDisplayLink { state })
+    }
+}
+
+impl AdaptiveDisplayLink {
+    /// Moves the link to another display without restarting it, see
+    /// [`DisplayLink::set_current_display`].
+    pub fn set_current_display(&mut self, display_id: CGDirectDisplayID) -> Result<()> {
+        self.state
+            .display_link
+            .borrow_mut()
+            .set_current_display(display_id)?;
+        self.state.display_id.set(display_id);
+        Ok(())
+    }
+
+    /// Frame pacing counters for the underlying link, see [`DisplayLink::stats`].
+    pub fn stats(&self) -> DisplayLinkStats {
+        self.state.display_link.borrow().stats()
+    }
+}
+
+impl AdaptiveDisplayLinkState {
+    fn restart_on_active_display(&self) -> Result<()> {
+        let removed_display_id = self.display_id.get();
+        let mut display_id = unsafe { active_display_id() };
+        // The main window may not have moved off the removed screen yet.
+        if display_id == removed_display_id {
+            display_id = CGDisplay::main().id;
+        }
+
+        let mut display_link = self.display_link.borrow_mut();
+        display_link.stop()?;
+        display_link.set_current_display(display_id)?;
+        display_link.start()?;
+        self.display_id.set(display_id);
+        log::info!("moved display link from display {removed_display_id} to display {display_id}");
+        Ok(())
+    }
+}
+
+// CoreGraphics delivers reconfiguration callbacks on the main thread, where
+// `AdaptiveDisplayLink` is owned, so the state doesn't need to be synchronized.
+unsafe extern "C" fn display_reconfiguration_callback(
+    display: CGDirectDisplayID,
+    flags: CGDisplayChangeSummaryFlags,
+    user_info: *mut c_void,
+) {
+    let state = unsafe { &*(user_info as *const AdaptiveDisplayLinkState) };
+    log::debug!("display {display} reconfigured, flags: {flags:#x}");
+    if display == state.display_id.get() && should_restart(flags) {
+        state.restart_on_active_display().log_err();
+    }
+}
+
+impl Drop for AdaptiveDisplayLink {
+    fn drop(&mut self) {
+        unsafe {
+            CGDisplayRemoveReconfigurationCallback(
+                display_reconfiguration_callback,
+                &*self.state as *const AdaptiveDisplayLinkState as *mut c_void,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const kCGDisplayMovedFlag: CGDisplayChangeSummaryFlags = 1 << 1;
+    const kCGDisplaySetMainFlag: CGDisplayChangeSummaryFlags = 1 << 2;
+    const kCGDisplaySetModeFlag: CGDisplayChangeSummaryFlags = 1 << 3;
+    const kCGDisplayAddFlag: CGDisplayChangeSummaryFlags = 1 << 4;
+    const kCGDisplayEnabledFlag: CGDisplayChangeSummaryFlags = 1 << 8;
+    const kCGDisplayMirrorFlag: CGDisplayChangeSummaryFlags = 1 << 10;
+    const kCGDisplayUnMirrorFlag: CGDisplayChangeSummaryFlags = 1 << 11;
+    const kCGDisplayDesktopShapeChangedFlag: CGDisplayChangeSummaryFlags = 1 << 12;
+
+    #[test]
+    fn test_should_restart() {
+        assert!(should_restart(kCGDisplayRemoveFlag));
+        assert!(should_restart(kCGDisplayDisabledFlag));
+        assert!(should_restart(
+            kCGDisplayRemoveFlag | kCGDisplayDisabledFlag | kCGDisplaySetMainFlag
+        ));
+
+        // The notification announcing a change arrives before the display goes away.
+        assert!(!should_restart(
+            kCGDisplayBeginConfigurationFlag | kCGDisplayRemoveFlag
+        ));
+
+        // The display is still there, so the link keeps running.
+        assert!(!should_restart(0));
+        assert!(!should_restart(kCGDisplayAddFlag | kCGDisplayEnabledFlag));
+        assert!(!should_restart(kCGDisplaySetModeFlag));
+        assert!(!should_restart(kCGDisplayMovedFlag | kCGDisplaySetMainFlag));
+        assert!(!should_restart(
+            kCGDisplayMirrorFlag | kCGDisplayUnMirrorFlag
+        ));
+        assert!(!should_restart(kCGDisplayDesktopShapeChangedFlag));
+    }
+}
@@ -1,10 +1,10 @@
 use super::{BoolExt, MacDisplay, NSRange, NSStringExt, ns_string, renderer};
 use crate::{
-    AnyWindowHandle, Bounds, DisplayLink, ExternalPaths, FileDropEvent, ForegroundExecutor,
-    KeyDownEvent, Keystroke, Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent,
-    MouseMoveEvent, MouseUpEvent, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput,
-    PlatformWindow, Point, PromptLevel, RequestFrameOptions, ScaledPixels, Size, Timer,
-    WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowKind, WindowParams,
+    AdaptiveDisplayLink, AnyWindowHandle, Bounds, DisplayLink, ExternalPaths, FileDropEvent,
+    ForegroundExecutor, KeyDownEvent, Keystroke, Modifiers, ModifiersChangedEvent, MouseButton,
+    MouseDownEvent, MouseMoveEvent, MouseUpEvent, Pixels, PlatformAtlas, PlatformDisplay,
+    PlatformInput, PlatformWindow, Point, PromptLevel, RequestFrameOptions, ScaledPixels, Size,
+    Timer, WindowAppearance, WindowBackgroundAppearance, WindowBounds, WindowKind, WindowParams,
     platform::PlatformInputHandler, point, px, size,
 };
 use block::ConcreteBlock;
@@ -334,7 +334,7 @@ struct MacWindowState {
     executor: ForegroundExecutor,
     native_window: id,
     native_view: NonNull<Object>,
-    display_link: Option<DisplayLink>,
+    display_link: Option<AdaptiveDisplayLink>,
     renderer: renderer::Renderer,
     request_frame_callback: Option<Box<dyn FnMut(RequestFrameOptions)>>,
     event_callback: Option<Box<dyn FnMut(PlatformInput) -> crate::DispatchEventResult>>,
@@ -421,17 +421,25 @@ impl MacWindowState {
                 return;
             }
         }
-        let display_id = unsafe { display_id_for_screen(self.native_window.screen()) };
-        if let Some(mut display_link) =
-            DisplayLink::new(display_id, self.native_view.as_ptr() as *mut c_void, step).log_err()
-        {
-            display_link.start().log_err();
-            self.display_link = Some(display_link);
-        }
+        let data = self.native_view.as_ptr() as *mut c_void;
+        let screen = unsafe { self.native_window.screen() };
+        // A window that isn't on any screen has no display of its own to sync to,
+        // so it follows the active display.
+        let display_id = (screen != nil).then(|| unsafe { display_id_for_screen(screen) });
+        self.display_link = DisplayLink::start_adaptive(display_id, data, step).log_err();
     }
 
     fn stop_display_link(&mut self) {
-        self.display_link = None;
+        if let Some(display_link) = self.display_link.take() {
+            let stats = display_link.stats();
+            if stats.skipped_vsyncs > 0 {
+                log::debug!(
+                    "display link skipped {} of {} vsyncs",
+                    stats.skipped_vsyncs,
+                    stats.callbacks + stats.skipped_vsyncs
+                );
+            }
+        }
     }
 
     fn is_maximized(&self) -> bool {
@@ -1585,7 +1593,23 @@ extern "C" fn window_did_move(this: &Object, _: Sel, _: id) {
 extern "C" fn window_did_change_screen(this: &Object, _: Sel, _: id) {
     let window_state = unsafe { get_window_state(this) };
     let mut lock = window_state.as_ref().lock();
-    lock.start_display_link();
+    let screen = unsafe { lock.native_window.screen() };
+    if screen == nil {
+        lock.start_display_link();
+        return;
+    }
+    let display_id = unsafe { display_id_for_screen(screen) };
+    // Retarget a running display link rather than recreating it, so that no frames
+    // are dropped while the window moves between displays.
+    let retargeted = lock.display_link.as_mut().is_some_and(|display_link| {
+        display_link
+            .set_current_display(display_id)
+            .log_err()
+            .is_some()
+    });
+    if !retargeted {
+        lock.start_display_link();
+    }
 }
 
 extern "C" fn window_did_change_key_status(this: &Object, selector: Sel, _: id) {
@@ -2064,7 +2088,7 @@ where
     }
 }
 
-unsafe fn display_id_for_screen(screen: id) -> CGDirectDisplayID {
+pub(crate) unsafe fn display_id_for_screen(screen: id) -> CGDirectDisplayID {
     unsafe {
         let device_description = NSScreen::deviceDescription(screen);
         let screen_number_key: id = NSString::alloc(nil).init_str("NSScreenNumber");
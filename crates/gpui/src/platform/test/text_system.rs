@@ -0,0 +1,79 @@
+use crate::{
+    Bounds, DevicePixels, Font, FontId, FontMetrics, FontRun, GlyphId, LineLayout, NoopTextSystem,
+    Pixels, PlatformTextSystem, RenderGlyphParams, Size,
+};
+use anyhow::{Result, anyhow};
+use std::{
+    borrow::Cow,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering::SeqCst},
+};
+
+/// A text system that shapes text like [`NoopTextSystem`], but lets tests observe how often
+/// lines are shaped and make font lookups fail.
+#[derive(Default)]
+pub(crate) struct TestTextSystem {
+    layout_line_count: AtomicUsize,
+    fail_font_lookups: AtomicBool,
+}
+
+impl TestTextSystem {
+    /// The number of lines that have been shaped so far.
+    pub fn layout_line_count(&self) -> usize {
+        self.layout_line_count.load(SeqCst)
+    }
+
+    /// Makes every subsequent font lookup fail, as if no font could be resolved.
+    pub fn fail_font_lookups(&self) {
+        self.fail_font_lookups.store(true, SeqCst);
+    }
+}
+
+impl PlatformTextSystem for TestTextSystem {
+    fn add_fonts(&self, fonts: Vec<Cow<'static, [u8]>>) -> Result<()> {
+        NoopTextSystem.add_fonts(fonts)
+    }
+
+    fn all_font_names(&self) -> Vec<String> {
+        NoopTextSystem.all_font_names()
+    }
+
+    fn font_id(&self, descriptor: &Font) -> Result<FontId> {
+        if self.fail_font_lookups.load(SeqCst) {
+            return Err(anyhow!("no font named {:?}", descriptor.family));
+        }
+        NoopTextSystem.font_id(descriptor)
+    }
+
+    fn font_metrics(&self, font_id: FontId) -> FontMetrics {
+        NoopTextSystem.font_metrics(font_id)
+    }
+
+    fn typographic_bounds(&self, font_id: FontId, glyph_id: GlyphId) -> Result<Bounds<f32>> {
+        NoopTextSystem.typographic_bounds(font_id, glyph_id)
+    }
+
+    fn advance(&self, font_id: FontId, glyph_id: GlyphId) -> Result<Size<f32>> {
+        NoopTextSystem.advance(font_id, glyph_id)
+    }
+
+    fn glyph_for_char(&self, font_id: FontId, ch: char) -> Option<GlyphId> {
+        NoopTextSystem.glyph_for_char(font_id, ch)
+    }
+
+    fn glyph_raster_bounds(&self, params: &RenderGlyphParams) -> Result<Bounds<DevicePixels>> {
+        NoopTextSystem.glyph_raster_bounds(params)
+    }
+
+    fn rasterize_glyph(
+        &self,
+        params: &RenderGlyphParams,
+        raster_bounds: Bounds<DevicePixels>,
+    ) -> Result<(Size<DevicePixels>, Vec<u8>)> {
+        NoopTextSystem.rasterize_glyph(params, raster_bounds)
+    }
+
+    fn layout_line(&self, text: &str, font_size: Pixels, runs: &[FontRun]) -> LineLayout {
+        self.layout_line_count.fetch_add(1, SeqCst);
+        NoopTextSystem.layout_line(text, font_size, runs)
+    }
+}
@@ -188,6 +188,10 @@ pub struct Style {
     /// How should the position of this element be tweaked relative to the layout defined?
     #[refineable]
     pub inset: Edges<Length>,
+    /// The order in which this element is painted relative to its siblings. Siblings with a
+    /// higher z-index are painted on top, and those without one are treated as having a z-index of 0.
+    /// This only orders the children of a single div; there are no stacking contexts as in CSS.
+    pub z_index: Option<i32>,
 
     // Size properties
     /// Sets the initial size of the item
@@ -318,6 +322,15 @@ pub enum TextOverflow {
     Ellipsis(&'static str),
 }
 
+/// The distance between the tab stops that tabs in text are expanded to
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TabSize {
+    /// A tab stop every given number of spaces, same as: `tab-size: 4;` in CSS
+    Spaces(usize),
+    /// A tab stop every given distance, same as: `tab-size: 32px;` in CSS
+    Width(Pixels),
+}
+
 /// How to align text within the element
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum TextAlign {
@@ -380,6 +393,12 @@ pub struct TextStyle {
 
     /// The number of lines to display before truncating the text
     pub line_clamp: Option<usize>,
+
+    /// Whether words too long to fit on a line should be broken with a hyphen when wrapping
+    pub hyphenate: bool,
+
+    /// The distance between tab stops, or `None` to shape tabs like any other character
+    pub tab_size: Option<TabSize>,
 }
 
 impl Default for TextStyle {
@@ -407,6 +426,8 @@ impl Default for TextStyle {
             text_overflow: None,
             text_align: TextAlign::default(),
             line_clamp: None,
+            hyphenate: false,
+            tab_size: None,
         }
     }
 }
@@ -732,6 +753,7 @@ impl Default for Style {
             scrollbar_width: 0.0,
             position: Position::Relative,
             inset: Edges::auto(),
+            z_index: None,
             margin: Edges::<Length>::zero(),
             padding: Edges::<DefiniteLength>::zero(),
             border_widths: Edges::<AbsoluteLength>::zero(),
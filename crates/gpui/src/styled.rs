@@ -1,8 +1,8 @@
 use crate::{
     self as gpui, AbsoluteLength, AlignItems, BorderStyle, CursorStyle, DefiniteLength, Fill,
     FlexDirection, FlexWrap, Font, FontStyle, FontWeight, Hsla, JustifyContent, Length,
-    SharedString, StrikethroughStyle, StyleRefinement, TextOverflow, UnderlineStyle, WhiteSpace,
-    px, relative, rems,
+    SharedString, StrikethroughStyle, StyleRefinement, TabSize, TextOverflow, UnderlineStyle,
+    WhiteSpace, px, relative, rems,
 };
 use crate::{TextAlign, TextStyleRefinement};
 pub use gpui_macros::{
@@ -116,6 +116,24 @@ pub trait Styled: Sized {
         self.overflow_hidden()
     }
 
+    /// Sets words that are too long to fit on a line to be broken with a hyphen when wrapping.
+    /// [Docs](https://tailwindcss.com/docs/hyphens#automatic)
+    fn hyphens_auto(mut self) -> Self {
+        self.text_style()
+            .get_or_insert_with(Default::default)
+            .hyphenate = Some(true);
+        self
+    }
+
+    /// Sets the distance between the tab stops that tabs in the text are expanded to.
+    /// [Docs](https://developer.mozilla.org/en-US/docs/Web/CSS/tab-size)
+    fn tab_size(mut self, tab_size: TabSize) -> Self {
+        self.text_style()
+            .get_or_insert_with(Default::default)
+            .tab_size = Some(tab_size);
+        self
+    }
+
     /// Sets the flex direction of the element to `column`.
     /// [Docs](https://tailwindcss.com/docs/flex-direction#column)
     fn flex_col(mut self) -> Self {
@@ -632,7 +650,9 @@ pub trait Styled: Sized {
         self
     }
 
-    /// Sets the opacity of this element and its children.
+    /// Sets the opacity of this element and its children, from `0.0` (transparent) to `1.0`
+    /// (opaque). It's multiplied into the alpha of everything the element paints, on top of the
+    /// alpha of the colors themselves and the opacity of any ancestors.
     fn opacity(mut self, opacity: f32) -> Self {
         self.style().opacity = Some(opacity);
         self
@@ -652,3 +672,112 @@ pub trait Styled: Sized {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        self as gpui, Context, Div, InteractiveElement, IntoElement, ParentElement, Render, Styled,
+        TestAppContext, VisualTestContext, Window, blue, div, green, point, px, red, size, white,
+    };
+
+    struct TestView(fn() -> Div);
+
+    impl Render for TestView {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            (self.0)()
+        }
+    }
+
+    fn render(cx: &mut TestAppContext, render: fn() -> Div) -> &mut VisualTestContext {
+        cx.add_window_view(|_, _| TestView(render)).1
+    }
+
+    #[gpui::test]
+    fn test_fractional_widths(cx: &mut TestAppContext) {
+        let cx = render(cx, || {
+            div()
+                .flex()
+                .w(px(300.))
+                .h(px(120.))
+                .child(div().debug_selector(|| "1/3".into()).w_1_3().h_1_4())
+                .child(div().debug_selector(|| "2/5".into()).w_2_5().h_1_2())
+                .child(div().debug_selector(|| "px".into()).w(px(50.)).h(px(10.)))
+        });
+
+        let mut bounds = |selector| cx.debug_bounds(selector).unwrap();
+        assert_eq!(bounds("1/3").size, size(px(100.), px(30.)));
+        assert_eq!(bounds("2/5").size, size(px(120.), px(60.)));
+        assert_eq!(bounds("px").size, size(px(50.), px(10.)));
+        assert_eq!(bounds("px").origin.x, px(220.));
+    }
+
+    #[gpui::test]
+    fn test_gap(cx: &mut TestAppContext) {
+        let cx = render(cx, || {
+            let square =
+                |selector: &'static str| div().debug_selector(|| selector.into()).size(px(20.));
+            div()
+                .flex()
+                .items_start()
+                .child(
+                    div()
+                        .debug_selector(|| "row".into())
+                        .flex()
+                        .gap(px(10.))
+                        .children(["a", "b", "c"].map(square)),
+                )
+                .child(
+                    div()
+                        .debug_selector(|| "column".into())
+                        .flex()
+                        .flex_col()
+                        .gap_y(px(5.))
+                        .children(["d", "e", "f"].map(square)),
+                )
+        });
+
+        let mut bounds = |selector| cx.debug_bounds(selector).unwrap();
+        assert_eq!(bounds("a").origin, point(px(0.), px(0.)));
+        assert_eq!(bounds("b").origin, point(px(30.), px(0.)));
+        assert_eq!(bounds("c").origin, point(px(60.), px(0.)));
+        // There's no gap after the last child.
+        assert_eq!(bounds("row").size, size(px(80.), px(20.)));
+
+        assert_eq!(bounds("d").origin, point(px(80.), px(0.)));
+        assert_eq!(bounds("e").origin, point(px(80.), px(25.)));
+        assert_eq!(bounds("f").origin, point(px(80.), px(50.)));
+        assert_eq!(bounds("column").size, size(px(20.), px(70.)));
+    }
+
+    #[gpui::test]
+    fn test_opacity(cx: &mut TestAppContext) {
+        let cx = render(cx, || {
+            div()
+                .w(px(400.))
+                .h(px(100.))
+                .bg(white())
+                .opacity(0.5)
+                .child(div().w(px(100.)).h_4().bg(red()).opacity(0.5))
+                .child(div().w(px(200.)).h_4().bg(green().opacity(0.5)))
+                .child(div().w(px(300.)).h_4().bg(blue()).opacity(2.))
+        });
+
+        let mut alphas = cx.update(|window, _| {
+            window
+                .rendered_frame
+                .scene
+                .quads
+                .iter()
+                .map(|quad| (quad.bounds.size.width, quad.background.solid.a))
+                .collect::<Vec<_>>()
+        });
+        alphas.sort_by_key(|(width, _)| *width);
+        let alphas = alphas
+            .into_iter()
+            .map(|(_, alpha)| alpha)
+            .collect::<Vec<_>>();
+        // Nested opacities compound, and multiply into the alpha of the fill itself. Opacities
+        // above one are clamped, so they can't make an element more opaque than its parent.
+        assert_eq!(alphas, [0.25, 0.25, 0.5, 0.5]);
+    }
+}
@@ -197,3 +197,59 @@ impl Drop for Subscription {
         }
     }
 }
+
+/// A collection of subscriptions that are all cancelled together when the
+/// set is dropped, so that a view can hold any number of them in one field.
+#[must_use]
+#[derive(Default)]
+pub struct SubscriptionSet {
+    subscriptions: Vec<Subscription>,
+}
+
+impl SubscriptionSet {
+    /// Creates an empty subscription set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a subscription to this set. It will be cancelled when the set is dropped.
+    pub fn push(&mut self, subscription: Subscription) {
+        self.subscriptions.push(subscription);
+    }
+
+    /// The number of subscriptions in this set.
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    /// Whether this set contains no subscriptions.
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+
+    /// Cancels all of the subscriptions in this set, leaving it empty.
+    pub fn clear(&mut self) {
+        self.subscriptions.clear();
+    }
+
+    /// Detaches all of the subscriptions in this set. See [`Subscription::detach`].
+    pub fn detach(self) {
+        for subscription in self.subscriptions {
+            subscription.detach();
+        }
+    }
+}
+
+impl Extend<Subscription> for SubscriptionSet {
+    fn extend<I: IntoIterator<Item = Subscription>>(&mut self, subscriptions: I) {
+        self.subscriptions.extend(subscriptions);
+    }
+}
+
+impl FromIterator<Subscription> for SubscriptionSet {
+    fn from_iter<I: IntoIterator<Item = Subscription>>(subscriptions: I) -> Self {
+        Self {
+            subscriptions: subscriptions.into_iter().collect(),
+        }
+    }
+}
@@ -22,6 +22,7 @@ pub struct TaffyLayoutEngine {
     taffy: TaffyTree<NodeContext>,
     absolute_layout_bounds: FxHashMap<LayoutId, Bounds<Pixels>>,
     computed_layouts: FxHashSet<LayoutId>,
+    z_indices: FxHashMap<LayoutId, i32>,
 }
 
 const EXPECT_MESSAGE: &str = "we should avoid taffy layout errors by construction if possible";
@@ -32,6 +33,7 @@ impl TaffyLayoutEngine {
             taffy: TaffyTree::new(),
             absolute_layout_bounds: FxHashMap::default(),
             computed_layouts: FxHashSet::default(),
+            z_indices: FxHashMap::default(),
         }
     }
 
@@ -39,6 +41,7 @@ impl TaffyLayoutEngine {
         self.taffy.clear();
         self.absolute_layout_bounds.clear();
         self.computed_layouts.clear();
+        self.z_indices.clear();
     }
 
     pub fn request_layout(
@@ -48,7 +51,7 @@ impl TaffyLayoutEngine {
         children: &[LayoutId],
     ) -> LayoutId {
         let taffy_style = style.to_taffy(rem_size);
-        let layout_id = if children.is_empty() {
+        let layout_id: LayoutId = if children.is_empty() {
             self.taffy
                 .new_leaf(taffy_style)
                 .expect(EXPECT_MESSAGE)
@@ -64,6 +67,9 @@ impl TaffyLayoutEngine {
                 .into();
             parent_id
         };
+        if let Some(z_index) = style.z_index {
+            self.z_indices.insert(layout_id, z_index);
+        }
         layout_id
     }
 
@@ -81,7 +87,7 @@ impl TaffyLayoutEngine {
     ) -> LayoutId {
         let taffy_style = style.to_taffy(rem_size);
 
-        let layout_id = self
+        let layout_id: LayoutId = self
             .taffy
             .new_leaf_with_context(
                 taffy_style,
@@ -91,6 +97,9 @@ impl TaffyLayoutEngine {
             )
             .expect(EXPECT_MESSAGE)
             .into();
+        if let Some(z_index) = style.z_index {
+            self.z_indices.insert(layout_id, z_index);
+        }
         layout_id
     }
 
@@ -218,6 +227,10 @@ impl TaffyLayoutEngine {
 
         bounds
     }
+
+    pub fn z_index(&self, id: LayoutId) -> Option<i32> {
+        self.z_indices.get(&id).copied()
+    }
 }
 
 /// A unique identifier for a layout node, generated when requesting a layout from Taffy
@@ -138,23 +138,30 @@ impl TextSystem {
     ///
     /// Panics if the font and none of the fallbacks can be resolved.
     pub fn resolve_font(&self, font: &Font) -> FontId {
+        self.try_resolve_font(font)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Like [`Self::resolve_font`], but returns an error if the font and none of
+    /// the fallbacks can be resolved.
+    pub fn try_resolve_font(&self, font: &Font) -> Result<FontId> {
         if let Ok(font_id) = self.font_id(font) {
-            return font_id;
+            return Ok(font_id);
         }
         for fallback in &self.fallback_font_stack {
             if let Ok(font_id) = self.font_id(fallback) {
-                return font_id;
+                return Ok(font_id);
             }
         }
 
-        panic!(
+        Err(anyhow!(
             "failed to resolve font '{}' or any of the fallbacks: {}",
             font.family,
             self.fallback_font_stack
                 .iter()
                 .map(|fallback| &fallback.family)
                 .join(", ")
-        );
+        ))
     }
 
     /// Get the bounding box for the given font and font size.
@@ -398,7 +405,7 @@ impl WindowTextSystem {
         let mut max_wrap_lines = line_clamp.unwrap_or(usize::MAX);
         let mut wrapped_lines = 0;
 
-        let mut process_line = |line_text: SharedString| {
+        let mut process_line = |line_text: SharedString| -> Result<()> {
             let line_end = line_start + line_text.len();
 
             let mut last_font: Option<Font> = None;
@@ -417,7 +424,7 @@ impl WindowTextSystem {
                     last_font = Some(run.font.clone());
                     font_runs.push(FontRun {
                         len: run_len_within_line,
-                        font_id: self.resolve_font(&run.font),
+                        font_id: self.try_resolve_font(&run.font)?,
                     });
                 }
 
@@ -472,6 +479,7 @@ impl WindowTextSystem {
             }
 
             font_runs.clear();
+            Ok(())
         };
 
         let mut split_lines = text.split('\n');
@@ -480,16 +488,16 @@ impl WindowTextSystem {
         if let Some(first_line) = split_lines.next() {
             if let Some(second_line) = split_lines.next() {
                 processed = true;
-                process_line(first_line.to_string().into());
-                process_line(second_line.to_string().into());
+                process_line(first_line.to_string().into())?;
+                process_line(second_line.to_string().into())?;
                 for line_text in split_lines {
-                    process_line(line_text.to_string().into());
+                    process_line(line_text.to_string().into())?;
                 }
             }
         }
 
         if !processed {
-            process_line(text);
+            process_line(text)?;
         }
 
         self.font_runs_pool.lock().push(font_runs);
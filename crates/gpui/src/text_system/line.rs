@@ -204,8 +204,7 @@ fn paint_line(
         ),
     );
     window.paint_layer(line_bounds, |window| {
-        let padding_top = (line_height - layout.ascent - layout.descent) / 2.;
-        let baseline_offset = point(px(0.), padding_top + layout.ascent);
+        let baseline_offset = point(px(0.), layout.baseline(line_height));
         let mut decoration_runs = decoration_runs.iter();
         let mut wraps = wrap_boundaries.iter().peekable();
         let mut run_end = 0;
@@ -579,3 +578,24 @@ fn aligned_origin_x(
         TextAlign::Right => origin.x + align_width - line_width,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aligned_origin_x() {
+        let layout = LineLayout {
+            width: px(40.),
+            ..Default::default()
+        };
+        let origin = point(px(10.), px(0.));
+        let aligned_x =
+            |align| aligned_origin_x(origin, px(100.), Pixels::ZERO, &align, &layout, None);
+
+        assert_eq!(aligned_x(TextAlign::Left), px(10.));
+        assert_eq!(aligned_x(TextAlign::Center), px(40.));
+        // Right-aligned lines end flush with the right edge, at 10 + 100.
+        assert_eq!(aligned_x(TextAlign::Right), px(70.));
+    }
+}
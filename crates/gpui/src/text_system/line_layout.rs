@@ -54,6 +54,13 @@ pub struct ShapedGlyph {
 }
 
 impl LineLayout {
+    /// The offset of the baseline from the top of a line of the given height. Lines are
+    /// painted vertically centered within their line height.
+    pub fn baseline(&self, line_height: Pixels) -> Pixels {
+        let padding_top = (line_height - self.ascent - self.descent) / 2.;
+        padding_top + self.ascent
+    }
+
     /// The index for the character at the given x coordinate
     pub fn index_for_x(&self, x: Pixels) -> Option<usize> {
         if x >= self.width {
@@ -113,6 +120,27 @@ impl LineLayout {
         self.width
     }
 
+    /// The start of the glyph cluster containing the character at the given index.
+    ///
+    /// A single glyph can cover several characters, e.g. a ligature, so indices within
+    /// such a cluster snap back to the index of its glyph.
+    pub fn cluster_start_for_index(&self, index: usize) -> usize {
+        let mut cluster_start = 0;
+        for run in &self.runs {
+            for glyph in &run.glyphs {
+                if glyph.index > index {
+                    return cluster_start;
+                }
+                cluster_start = glyph.index;
+            }
+        }
+        if index >= self.len {
+            self.len
+        } else {
+            cluster_start
+        }
+    }
+
     /// The corresponding Font at the given index
     pub fn font_id_for_index(&self, index: usize) -> Option<FontId> {
         for run in &self.runs {
@@ -262,6 +290,12 @@ impl WrappedLineLayout {
         self.unwrapped_layout.descent
     }
 
+    /// The offset of the baseline from the top of each visual line in this layout.
+    /// See [`LineLayout::baseline`].
+    pub fn baseline(&self, line_height: Pixels) -> Pixels {
+        self.unwrapped_layout.baseline(line_height)
+    }
+
     /// The wrap boundaries in this layout
     pub fn wrap_boundaries(&self) -> &[WrapBoundary] {
         &self.wrap_boundaries
@@ -649,3 +683,84 @@ impl AsCacheKeyRef for CacheKeyRef<'_> {
         *self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GlyphId, TestTextSystem};
+
+    fn glyph(index: usize, x: f32) -> ShapedGlyph {
+        ShapedGlyph {
+            id: GlyphId(0),
+            position: point(px(x), px(0.)),
+            index,
+            is_emoji: false,
+        }
+    }
+
+    #[test]
+    fn test_cluster_start_for_index() {
+        // "ffix", where "ffi" is shaped as a single ligature glyph.
+        let layout = LineLayout {
+            font_size: px(16.),
+            width: px(30.),
+            ascent: px(12.),
+            descent: px(4.),
+            runs: vec![ShapedRun {
+                font_id: FontId(0),
+                glyphs: [glyph(0, 0.), glyph(3, 20.)].into_iter().collect(),
+            }],
+            len: 4,
+        };
+
+        assert_eq!(layout.cluster_start_for_index(0), 0);
+        assert_eq!(layout.cluster_start_for_index(1), 0);
+        assert_eq!(layout.cluster_start_for_index(2), 0);
+        assert_eq!(layout.cluster_start_for_index(3), 3);
+        assert_eq!(layout.cluster_start_for_index(4), 4);
+
+        assert_eq!(
+            layout.x_for_index(layout.cluster_start_for_index(2)),
+            px(0.)
+        );
+        assert_eq!(layout.closest_index_for_x(px(8.)), 0);
+        assert_eq!(layout.closest_index_for_x(px(12.)), 3);
+    }
+
+    #[test]
+    fn test_layouts_are_reused_across_frames() {
+        let text_system = Arc::new(TestTextSystem::default());
+        let cache = LineLayoutCache::new(text_system.clone());
+        let runs = [FontRun {
+            len: 5,
+            font_id: FontId(0),
+        }];
+        let layout = |text: &'static str, wrap_width| {
+            cache.layout_wrapped_line(text, px(16.), &runs, Some(wrap_width), None)
+        };
+
+        let first = layout("hello", px(100.));
+        assert_eq!(text_system.layout_line_count(), 1);
+
+        // Laying out the same text within a frame, or again in the next frame, reuses the
+        // shaped line instead of asking the platform to shape it again.
+        assert!(Arc::ptr_eq(&layout("hello", px(100.)), &first));
+        cache.finish_frame();
+        assert!(Arc::ptr_eq(&layout("hello", px(100.)), &first));
+        cache.finish_frame();
+        assert!(Arc::ptr_eq(&layout("hello", px(100.)), &first));
+        assert_eq!(text_system.layout_line_count(), 1);
+
+        // A different wrap width produces a new layout.
+        assert!(!Arc::ptr_eq(&layout("hello", px(20.)), &first));
+        assert_eq!(text_system.layout_line_count(), 2);
+        assert!(!Arc::ptr_eq(&layout("world", px(100.)), &first));
+        assert_eq!(text_system.layout_line_count(), 3);
+
+        // Layouts that weren't used during a frame are evicted at the end of the next one.
+        cache.finish_frame();
+        cache.finish_frame();
+        layout("hello", px(100.));
+        assert_eq!(text_system.layout_line_count(), 4);
+    }
+}
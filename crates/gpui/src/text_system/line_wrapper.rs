@@ -1,7 +1,10 @@
-use crate::{FontId, FontRun, Pixels, PlatformTextSystem, SharedString, TextRun, px};
+use crate::{FontId, FontRun, Pixels, PlatformTextSystem, SharedString, TabSize, TextRun, px};
 use collections::HashMap;
 use std::{iter, sync::Arc};
 
+/// The text inserted where a word is broken by [`LineWrapper::hyphenate_line`].
+const HYPHEN_BREAK: &str = "-\n";
+
 /// The GPUI line wrapper, used to wrap lines of text to a given width.
 pub struct LineWrapper {
     platform_text_system: Arc<dyn PlatformTextSystem>,
@@ -15,6 +18,9 @@ impl LineWrapper {
     /// The maximum indent that can be applied to a line.
     pub const MAX_INDENT: u32 = 256;
 
+    /// The minimum number of characters kept together on either side of a hyphenated break.
+    pub const MIN_HYPHENATED_FRAGMENT_LEN: usize = 2;
+
     pub(crate) fn new(
         font_id: FontId,
         font_size: Pixels,
@@ -166,6 +172,128 @@ impl LineWrapper {
         line
     }
 
+    /// Break words that are too wide to fit within `wrap_width` on their own by inserting a
+    /// hyphen and a newline, updating `runs` so that each hyphen takes on the style of the
+    /// text preceding it, and recording the insertions in `offsets`.
+    ///
+    /// Words are only broken between two alphabetic characters, leaving at least
+    /// [`Self::MIN_HYPHENATED_FRAGMENT_LEN`] characters on either side of the break.
+    pub fn hyphenate_line(
+        &mut self,
+        line: SharedString,
+        wrap_width: Pixels,
+        runs: &mut Vec<TextRun>,
+        offsets: &mut OffsetMap,
+    ) -> SharedString {
+        let hyphen_width = self.width_for_char('-');
+        let mut insertions = Vec::new();
+        let mut chars = Vec::new();
+        let mut char_indices = line.char_indices().peekable();
+        while char_indices.peek().is_some() {
+            chars.clear();
+            chars.extend(
+                char_indices
+                    .by_ref()
+                    .skip_while(|(_, c)| c.is_whitespace())
+                    .take_while(|(_, c)| !c.is_whitespace()),
+            );
+
+            let mut fragment_start = 0;
+            let mut fragment_width = px(0.);
+            let mut last_break = None;
+            for (i, &(_, c)) in chars.iter().enumerate() {
+                if i - fragment_start >= Self::MIN_HYPHENATED_FRAGMENT_LEN
+                    && chars.len() - i >= Self::MIN_HYPHENATED_FRAGMENT_LEN
+                    && chars[i - 1].1.is_alphabetic()
+                    && c.is_alphabetic()
+                    && fragment_width + hyphen_width <= wrap_width
+                {
+                    last_break = Some((i, fragment_width));
+                }
+
+                fragment_width += self.width_for_char(c);
+                if fragment_width > wrap_width {
+                    if let Some((break_ix, width_before_break)) = last_break.take() {
+                        insertions.push(chars[break_ix].0);
+                        fragment_start = break_ix;
+                        fragment_width -= width_before_break;
+                    }
+                }
+            }
+        }
+
+        if insertions.is_empty() {
+            return line;
+        }
+
+        let mut result = String::with_capacity(line.len() + insertions.len() * HYPHEN_BREAK.len());
+        let mut prev_ix = 0;
+        for &ix in &insertions {
+            result.push_str(&line[prev_ix..ix]);
+            result.push_str(HYPHEN_BREAK);
+            prev_ix = ix;
+        }
+        result.push_str(&line[prev_ix..]);
+        update_runs_after_hyphenation(&insertions, runs);
+        for &ix in insertions.iter().rev() {
+            offsets.replace(ix, "", HYPHEN_BREAK.len());
+        }
+
+        result.into()
+    }
+
+    /// Replace each tab in the line with the spaces needed to reach the next tab stop, extend
+    /// the runs to cover the inserted spaces, and record the replacements in `offsets`.
+    pub fn expand_tabs(
+        &mut self,
+        line: SharedString,
+        tab_size: TabSize,
+        runs: &mut [TextRun],
+        offsets: &mut OffsetMap,
+    ) -> SharedString {
+        if !line.contains('\t') {
+            return line;
+        }
+
+        let space_width = self.width_for_char(' ');
+        let tab_width = match tab_size {
+            TabSize::Spaces(spaces) => space_width * spaces as f32,
+            TabSize::Width(width) => width,
+        };
+        let mut result = String::with_capacity(line.len());
+        let mut expansions = Vec::new();
+        let mut x = px(0.);
+        for (ix, c) in line.char_indices() {
+            match c {
+                '\t' => {
+                    let mut spaces = 1;
+                    if tab_width > px(0.) && space_width > px(0.) {
+                        let next_stop = tab_width * ((x / tab_width).floor() + 1.);
+                        spaces = ((next_stop - x) / space_width).round().max(1.) as usize;
+                    }
+                    result.extend(iter::repeat_n(' ', spaces));
+                    expansions.push((ix, spaces - 1));
+                    x += space_width * spaces as f32;
+                }
+                '\n' => {
+                    result.push(c);
+                    x = px(0.);
+                }
+                _ => {
+                    result.push(c);
+                    x += self.width_for_char(c);
+                }
+            }
+        }
+        update_runs_after_tab_expansion(&expansions, runs);
+        // Record the replacements back to front, so each one's offset is still valid.
+        for &(ix, inserted) in expansions.iter().rev() {
+            offsets.replace(ix, "\t", inserted + 1);
+        }
+
+        result.into()
+    }
+
     pub(crate) fn is_word_char(c: char) -> bool {
         // ASCII alphanumeric characters, for English, numbers: `Hello123`, etc.
         c.is_ascii_alphanumeric() ||
@@ -243,6 +371,146 @@ fn update_runs_after_truncation(result: &str, ellipsis: &str, runs: &mut Vec<Tex
     }
 }
 
+/// Extend the run preceding each insertion point to cover the inserted hyphen break.
+fn update_runs_after_hyphenation(insertions: &[usize], runs: &mut [TextRun]) {
+    let mut run_ix = 0;
+    let mut run_end = 0;
+    let mut inserted_per_run = vec![0; runs.len()];
+    for &ix in insertions {
+        while run_ix < runs.len() && run_end + runs[run_ix].len < ix {
+            run_end += runs[run_ix].len;
+            run_ix += 1;
+        }
+        if let Some(inserted) = inserted_per_run.get_mut(run_ix) {
+            *inserted += HYPHEN_BREAK.len();
+        }
+    }
+    for (run, inserted) in runs.iter_mut().zip(inserted_per_run) {
+        run.len += inserted;
+    }
+}
+
+/// Extend the run containing each expanded tab to cover the spaces inserted in its place.
+fn update_runs_after_tab_expansion(expansions: &[(usize, usize)], runs: &mut [TextRun]) {
+    let mut run_ix = 0;
+    let mut run_start = 0;
+    let mut inserted_per_run = vec![0; runs.len()];
+    for &(ix, inserted) in expansions {
+        while run_ix < runs.len() && run_start + runs[run_ix].len <= ix {
+            run_start += runs[run_ix].len;
+            run_ix += 1;
+        }
+        if let Some(inserted_in_run) = inserted_per_run.get_mut(run_ix) {
+            *inserted_in_run += inserted;
+        }
+    }
+    for (run, inserted) in runs.iter_mut().zip(inserted_per_run) {
+        run.len += inserted;
+    }
+}
+
+/// Maps byte offsets in a line rewritten by [`LineWrapper::expand_tabs`] or
+/// [`LineWrapper::hyphenate_line`] back to the line it was given, and the other way around.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OffsetMap {
+    /// The replacements made in the rewritten line, ordered by where they start in it.
+    edits: Vec<OffsetEdit>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct OffsetEdit {
+    /// The offset of the replacement in the rewritten line.
+    start: usize,
+    /// The length of the replacement.
+    len: usize,
+    /// The text it replaced.
+    replaced: &'static str,
+}
+
+impl OffsetMap {
+    /// Records that `replaced`, found at `ix` in the line as rewritten so far, was replaced by
+    /// `len` bytes of other text.
+    fn replace(&mut self, ix: usize, replaced: &'static str, len: usize) {
+        for edit in &mut self.edits {
+            if edit.start >= ix + replaced.len() {
+                edit.start += len - replaced.len();
+            }
+        }
+        let position = self.edits.partition_point(|edit| edit.start < ix);
+        self.edits.insert(
+            position,
+            OffsetEdit {
+                start: ix,
+                len,
+                replaced,
+            },
+        );
+    }
+
+    /// Forgets the replacements past `len`, for a rewritten line that was cut off there.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.edits.retain(|edit| edit.start < len);
+        if let Some(edit) = self.edits.last_mut() {
+            edit.len = edit.len.min(len - edit.start);
+        }
+    }
+
+    /// Whether the line was rewritten at all.
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Maps an offset into the rewritten line to the line it was given. Offsets within a
+    /// replacement map to its start or, if `closest` is set, to whichever of its ends is nearer.
+    pub fn to_original(&self, ix: usize, closest: bool) -> usize {
+        let mut shift = 0;
+        for edit in &self.edits {
+            if ix < edit.start {
+                break;
+            }
+            let original_start = edit.start - shift;
+            if ix < edit.start + edit.len {
+                if closest && (ix - edit.start) * 2 >= edit.len {
+                    return original_start + edit.replaced.len();
+                }
+                return original_start;
+            }
+            shift += edit.len - edit.replaced.len();
+        }
+        ix - shift
+    }
+
+    /// Maps an offset into the given line to the rewritten one. Offsets within replaced text
+    /// map to the start of its replacement.
+    pub fn from_original(&self, ix: usize) -> usize {
+        let mut shift = 0;
+        for edit in &self.edits {
+            let original_start = edit.start - shift;
+            if ix < original_start {
+                break;
+            }
+            if ix < original_start + edit.replaced.len() {
+                return edit.start;
+            }
+            shift += edit.len - edit.replaced.len();
+        }
+        ix + shift
+    }
+
+    /// Undoes the replacements in `text`, which is the rewritten line or a prefix of it.
+    pub fn original_text(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut ix = 0;
+        for edit in self.edits.iter().take_while(|edit| edit.start < text.len()) {
+            result.push_str(&text[ix..edit.start]);
+            result.push_str(edit.replaced);
+            ix = (edit.start + edit.len).min(text.len());
+        }
+        result.push_str(&text[ix..]);
+        result
+    }
+}
+
 /// A fragment of a line that can be wrapped.
 pub enum LineFragment<'a> {
     /// A text fragment consisting of characters.
@@ -492,6 +760,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hyphenate_line() {
+        let mut wrapper = build_wrapper();
+
+        // Words that fit on a line are left for regular wrapping.
+        let mut runs = generate_test_runs(&[11]);
+        let mut offsets = OffsetMap::default();
+        assert_eq!(
+            wrapper.hyphenate_line("aaaa bbbbbb".into(), px(72.), &mut runs, &mut offsets),
+            "aaaa bbbbbb"
+        );
+        assert_eq!(runs[0].len, 11);
+        assert!(offsets.is_empty());
+
+        // Words that can't fit are broken with a hyphen, leaving room for the hyphen itself.
+        let mut runs = generate_test_runs(&[10, 13]);
+        let mut offsets = OffsetMap::default();
+        let hyphenated = wrapper.hyphenate_line(
+            "aa aaaaaaaaaaaaaaaaaaaa".into(),
+            px(72.),
+            &mut runs,
+            &mut offsets,
+        );
+        assert_eq!(hyphenated, "aa aaaaaa-\naaaaaa-\naaaaaa-\naa");
+        assert_eq!(
+            runs.iter().map(|run| run.len).collect::<Vec<_>>(),
+            &[12, 17]
+        );
+        assert_eq!(
+            offsets.original_text(&hyphenated),
+            "aa aaaaaaaaaaaaaaaaaaaa"
+        );
+        // The characters after each break start the next line, and the hyphen and the newline
+        // map to the break itself.
+        assert_eq!(
+            [9, 15, 21].map(|ix| offsets.from_original(ix)),
+            [11, 19, 27]
+        );
+        assert_eq!(
+            [9, 10, 11, 17, 18, 19].map(|ix| offsets.to_original(ix, true)),
+            [9, 9, 9, 15, 15, 15]
+        );
+
+        // Words are only broken between alphabetic characters.
+        let mut runs = generate_test_runs(&[20]);
+        let mut offsets = OffsetMap::default();
+        assert_eq!(
+            wrapper.hyphenate_line(
+                "12345678901234567890".into(),
+                px(72.),
+                &mut runs,
+                &mut offsets
+            ),
+            "12345678901234567890"
+        );
+        assert_eq!(runs[0].len, 20);
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        let mut wrapper = build_wrapper();
+
+        // Each tab reaches the next stop, however far away it is.
+        let mut runs = generate_test_runs(&[2, 9]);
+        let mut offsets = OffsetMap::default();
+        assert_eq!(
+            wrapper.expand_tabs(
+                "a\tbcd\tefg\th".into(),
+                TabSize::Spaces(4),
+                &mut runs,
+                &mut offsets
+            ),
+            "a   bcd efg h"
+        );
+        assert_eq!(runs.iter().map(|run| run.len).collect::<Vec<_>>(), &[4, 9]);
+        assert_eq!(offsets.original_text("a   bcd efg h"), "a\tbcd\tefg\th");
+        assert_eq!(offsets.original_text("a   b"), "a\tb");
+
+        // Offsets within the spaces map to the tab, or to whichever side of it is closer.
+        assert_eq!(
+            (0..=13)
+                .map(|ix| offsets.to_original(ix, false))
+                .collect::<Vec<_>>(),
+            [0, 1, 1, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+        );
+        assert_eq!(
+            (0..=4)
+                .map(|ix| offsets.to_original(ix, true))
+                .collect::<Vec<_>>(),
+            [0, 1, 1, 2, 2]
+        );
+        assert_eq!(
+            (0..=11)
+                .map(|ix| offsets.from_original(ix))
+                .collect::<Vec<_>>(),
+            [0, 1, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]
+        );
+
+        // Stops restart on each line.
+        let mut runs = generate_test_runs(&[5]);
+        let mut offsets = OffsetMap::default();
+        assert_eq!(
+            wrapper.expand_tabs(
+                "ab\n\tc".into(),
+                TabSize::Spaces(2),
+                &mut runs,
+                &mut offsets
+            ),
+            "ab\n  c"
+        );
+        assert_eq!(runs[0].len, 6);
+        assert_eq!(offsets.from_original(4), 5);
+    }
+
     #[test]
     fn test_truncate_line() {
         let mut wrapper = build_wrapper();
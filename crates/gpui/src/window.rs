@@ -405,6 +405,15 @@ type FrameCallback = Box<dyn FnOnce(&mut Window, &mut App)>;
 pub(crate) type AnyMouseListener =
     Box<dyn FnMut(&dyn Any, DispatchPhase, &mut Window, &mut App) + 'static>;
 
+/// An element's accessible name, registered with [`Window::insert_accessibility_node`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessibilityNode {
+    /// The name describing the element to assistive technologies.
+    pub label: SharedString,
+    /// The bounds the element was painted in, in window coordinates.
+    pub bounds: Bounds<Pixels>,
+}
+
 #[derive(Clone)]
 pub(crate) struct CursorStyleRequest {
     pub(crate) hitbox_id: Option<HitboxId>, // None represents whole window
@@ -500,6 +509,7 @@ pub(crate) struct Frame {
     pub(crate) input_handlers: Vec<Option<PlatformInputHandler>>,
     pub(crate) tooltip_requests: Vec<Option<TooltipRequest>>,
     pub(crate) cursor_styles: Vec<CursorStyleRequest>,
+    pub(crate) accessibility_nodes: Vec<AccessibilityNode>,
     #[cfg(any(test, feature = "test-support"))]
     pub(crate) debug_bounds: FxHashMap<String, Bounds<Pixels>>,
 }
@@ -520,6 +530,7 @@ pub(crate) struct PaintIndex {
     mouse_listeners_index: usize,
     input_handlers_index: usize,
     cursor_styles_index: usize,
+    accessibility_nodes_index: usize,
     accessed_element_states_index: usize,
     line_layout_index: LineLayoutIndex,
 }
@@ -539,6 +550,7 @@ impl Frame {
             input_handlers: Vec::new(),
             tooltip_requests: Vec::new(),
             cursor_styles: Vec::new(),
+            accessibility_nodes: Vec::new(),
 
             #[cfg(any(test, feature = "test-support"))]
             debug_bounds: FxHashMap::default(),
@@ -554,6 +566,7 @@ impl Frame {
         self.input_handlers.clear();
         self.tooltip_requests.clear();
         self.cursor_styles.clear();
+        self.accessibility_nodes.clear();
         self.hitboxes.clear();
         self.deferred_draws.clear();
         self.focus = None;
@@ -1883,6 +1896,7 @@ impl Window {
             mouse_listeners_index: self.next_frame.mouse_listeners.len(),
             input_handlers_index: self.next_frame.input_handlers.len(),
             cursor_styles_index: self.next_frame.cursor_styles.len(),
+            accessibility_nodes_index: self.next_frame.accessibility_nodes.len(),
             accessed_element_states_index: self.next_frame.accessed_element_states.len(),
             line_layout_index: self.text_system.layout_index(),
         }
@@ -1895,6 +1909,12 @@ impl Window {
                 .iter()
                 .cloned(),
         );
+        self.next_frame.accessibility_nodes.extend(
+            self.rendered_frame.accessibility_nodes
+                [range.start.accessibility_nodes_index..range.end.accessibility_nodes_index]
+                .iter()
+                .cloned(),
+        );
         self.next_frame.input_handlers.extend(
             self.rendered_frame.input_handlers
                 [range.start.input_handlers_index..range.end.input_handlers_index]
@@ -1950,6 +1970,22 @@ impl Window {
         });
     }
 
+    /// Gives the element painted within the given bounds an accessible name, which describes it to
+    /// assistive technologies in place of its visible content. This method should only be called
+    /// during the paint phase of element drawing.
+    pub fn insert_accessibility_node(&mut self, label: SharedString, bounds: Bounds<Pixels>) {
+        self.invalidator.debug_assert_paint();
+        self.next_frame
+            .accessibility_nodes
+            .push(AccessibilityNode { label, bounds });
+    }
+
+    /// The accessible names given to elements in the last rendered frame, in the order the
+    /// elements were painted.
+    pub fn accessibility_nodes(&self) -> &[AccessibilityNode] {
+        &self.rendered_frame.accessibility_nodes
+    }
+
     /// Sets a tooltip to be rendered for the upcoming frame. This method should only be called
     /// during the paint phase of element drawing.
     pub fn set_tooltip(&mut self, tooltip: AnyTooltip) -> TooltipId {
@@ -2012,19 +2048,22 @@ impl Window {
         result
     }
 
+    /// Multiplies the opacity of everything painted within the given closure by the given
+    /// opacity, which is clamped to `[0, 1]`. Nested opacities compound.
     pub(crate) fn with_element_opacity<R>(
         &mut self,
         opacity: Option<f32>,
         f: impl FnOnce(&mut Self) -> R,
     ) -> R {
-        if opacity.is_none() {
+        let Some(opacity) = opacity else {
             return f(self);
-        }
+        };
 
         self.invalidator.debug_assert_paint_or_prepaint();
-        self.element_opacity = opacity;
+        let parent_opacity = self.element_opacity;
+        self.element_opacity = Some(parent_opacity.unwrap_or(1.0) * opacity.clamp(0.0, 1.0));
         let result = f(self);
-        self.element_opacity = None;
+        self.element_opacity = parent_opacity;
         result
     }
 
@@ -2786,6 +2825,15 @@ impl Window {
         bounds
     }
 
+    /// Returns the z-index of the style the given layout node was requested with, if it had one.
+    /// Elements with children use this to determine the order in which to paint them.
+    ///
+    /// This method should only be called as part of the request layout or prepaint phase of element drawing.
+    pub fn layout_z_index(&self, layout_id: LayoutId) -> Option<i32> {
+        self.invalidator.debug_assert_prepaint();
+        self.layout_engine.as_ref().unwrap().z_index(layout_id)
+    }
+
     /// This method should be called during `prepaint`. You can use
     /// the returned [Hitbox] during `paint` or in an event handler
     /// to determine whether the inserted hitbox was the topmost.
@@ -120,6 +120,18 @@ pub fn position_style_methods(input: TokenStream) -> TokenStream {
             self
         }
 
+        /// Sets the stacking order of the element among its siblings. Siblings with a higher
+        /// z-index are painted on top; siblings with equal z-indices are painted in order.
+        ///
+        /// Unlike CSS, this doesn't create a stacking context: the order only applies among
+        /// the children of a single div, so an element can't be raised above anything outside
+        /// its parent, such as the children of its parent's siblings.
+        /// [Docs](https://tailwindcss.com/docs/z-index)
+        #visibility fn z_index(mut self, z_index: i32) -> Self {
+            self.style().z_index = Some(z_index);
+            self
+        }
+
         #(#methods)*
     };
 
@@ -1150,12 +1162,12 @@ fn box_style_suffixes() -> Vec<BoxStyleSuffix> {
         BoxStyleSuffix {
             suffix: "1_6",
             length_tokens: quote! { relative(1./6.) },
-            doc_string_suffix: "16% (1/6)",
+            doc_string_suffix: "17% (1/6)",
         },
         BoxStyleSuffix {
             suffix: "5_6",
             length_tokens: quote! { relative(5./6.) },
-            doc_string_suffix: "80% (5/6)",
+            doc_string_suffix: "83% (5/6)",
         },
         BoxStyleSuffix {
             suffix: "1_12",
@@ -1140,6 +1140,8 @@ impl ConfigurationView {
             text_overflow: None,
             text_align: Default::default(),
             line_clamp: None,
+            hyphenate: false,
+            tab_size: None,
         }
     }
 
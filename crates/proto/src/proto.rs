@@ -45,8 +45,10 @@ messages!(
     (CallCanceled, Foreground),
     (CancelCall, Foreground),
     (CancelLanguageServerWork, Foreground),
+    (ChannelActivity, Foreground),
     (ChannelMessageSent, Foreground),
     (ChannelMessageUpdate, Foreground),
+    (ChannelTypingUpdate, Foreground),
     (CloseBuffer, Foreground),
     (Commit, Background),
     (ComputeEmbeddings, Background),
@@ -63,6 +65,8 @@ messages!(
     (CreateRoom, Foreground),
     (CreateRoomResponse, Foreground),
     (DeclineCall, Foreground),
+    (DeclineChannelInvitations, Foreground),
+    (DeclineChannelInvitationsResponse, Foreground),
     (DeleteChannel, Foreground),
     (DeleteNotification, Foreground),
     (DeleteProjectEntry, Foreground),
@@ -127,6 +131,8 @@ messages!(
     (GetImplementationResponse, Background),
     (GetLlmToken, Background),
     (GetLlmTokenResponse, Background),
+    (GetMembersForChannels, Foreground),
+    (GetMembersForChannelsResponse, Foreground),
     (LanguageServerIdForName, Background),
     (LanguageServerIdForNameResponse, Background),
     (OpenUnstagedDiff, Foreground),
@@ -143,6 +149,8 @@ messages!(
     (InlayHintsResponse, Background),
     (InstallExtension, Background),
     (InviteChannelMember, Foreground),
+    (InviteChannelMembers, Foreground),
+    (InviteChannelMembersResponse, Foreground),
     (JoinChannel, Foreground),
     (JoinChannelBuffer, Foreground),
     (JoinChannelBufferResponse, Foreground),
@@ -232,7 +240,16 @@ messages!(
     (SaveBuffer, Foreground),
     (SendChannelMessage, Background),
     (SendChannelMessageResponse, Background),
+    (SetChannelArchived, Foreground),
+    (SetChannelArchivedResponse, Foreground),
+    (SetChannelDescription, Foreground),
+    (SetChannelDescriptionResponse, Foreground),
+    (SetChannelFavorite, Foreground),
+    (SetChannelMaxParticipants, Foreground),
+    (SetChannelMaxParticipantsResponse, Foreground),
     (SetChannelMemberRole, Foreground),
+    (SetChannelNotificationLevel, Foreground),
+    (SetChannelTyping, Foreground),
     (SetChannelVisibility, Foreground),
     (SetRoomParticipantRole, Foreground),
     (ShareProject, Foreground),
@@ -254,6 +271,7 @@ messages!(
     (TaskContextForLocation, Background),
     (Test, Foreground),
     (Toast, Background),
+    (TransferChannelAdmin, Foreground),
     (Unfollow, Foreground),
     (UnshareProject, Foreground),
     (Unstage, Background),
@@ -320,6 +338,7 @@ request_messages!(
     (CreateProjectEntry, ProjectEntryResponse),
     (CreateRoom, CreateRoomResponse),
     (DeclineCall, Ack),
+    (DeclineChannelInvitations, DeclineChannelInvitationsResponse),
     (DeleteChannel, Ack),
     (DeleteProjectEntry, ProjectEntryResponse),
     (ExpandProjectEntry, ExpandProjectEntryResponse),
@@ -341,6 +360,7 @@ request_messages!(
     (GetDocumentSymbols, GetDocumentSymbolsResponse),
     (GetHover, GetHoverResponse),
     (GetLlmToken, GetLlmTokenResponse),
+    (GetMembersForChannels, GetMembersForChannelsResponse),
     (GetNotifications, GetNotificationsResponse),
     (GetPrivateUserInfo, GetPrivateUserInfoResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
@@ -357,6 +377,7 @@ request_messages!(
     (InlayHints, InlayHintsResponse),
     (GetCodeLens, GetCodeLensResponse),
     (InviteChannelMember, Ack),
+    (InviteChannelMembers, InviteChannelMembersResponse),
     (JoinChannel, JoinRoomResponse),
     (JoinChannelBuffer, JoinChannelBufferResponse),
     (JoinChannelChat, JoinChannelChatResponse),
@@ -400,12 +421,18 @@ request_messages!(
     (Stage, Ack),
     (FindSearchCandidates, FindSearchCandidatesResponse),
     (SendChannelMessage, SendChannelMessageResponse),
+    (SetChannelArchived, SetChannelArchivedResponse),
+    (SetChannelDescription, SetChannelDescriptionResponse),
+    (SetChannelFavorite, Ack),
+    (SetChannelMaxParticipants, SetChannelMaxParticipantsResponse),
     (SetChannelMemberRole, Ack),
+    (SetChannelNotificationLevel, Ack),
     (SetChannelVisibility, Ack),
     (ShareProject, ShareProjectResponse),
     (SynchronizeBuffers, SynchronizeBuffersResponse),
     (TaskContextForLocation, TaskContext),
     (Test, Test),
+    (TransferChannelAdmin, Ack),
     (Unstage, Ack),
     (UpdateBuffer, Ack),
     (UpdateParticipantLocation, Ack),
@@ -17,7 +17,10 @@ use schemars::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use settings::{Settings, SettingsJsonSchemaParams, SettingsSources, add_references_to_properties};
+use settings::{
+    Settings, SettingsJsonSchemaParams, SettingsSources, SettingsStore,
+    add_references_to_properties,
+};
 use std::sync::Arc;
 use util::ResultExt as _;
 
@@ -672,6 +675,20 @@ impl ThemeSettings {
     }
 }
 
+/// Makes the given theme the active one, with the theme overrides from the settings applied on
+/// top of it, and repaints all windows so they pick it up.
+///
+/// The theme stays active until the settings are reloaded.
+pub fn set_active_theme(theme: Arc<Theme>, cx: &mut App) {
+    SettingsStore::update_global(cx, |store, cx| {
+        let mut theme_settings = store.get::<ThemeSettings>(None).clone();
+        theme_settings.active_theme = theme;
+        theme_settings.apply_theme_overrides();
+        store.override_global(theme_settings);
+        cx.refresh_windows();
+    });
+}
+
 /// Observe changes to the adjusted buffer font size.
 pub fn observe_buffer_font_size_adjustment<V: 'static>(
     cx: &mut Context<V>,
@@ -957,3 +974,67 @@ fn merge<T: Copy>(target: &mut T, value: Option<T>) {
         *target = value;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActiveTheme, LoadThemes};
+    use gpui::{Hsla, IntoElement, Render, Styled, TestAppContext, div, hsla};
+
+    struct ThemedView {
+        backgrounds: Vec<Hsla>,
+    }
+
+    impl Render for ThemedView {
+        fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            let background = cx.theme().colors().background;
+            self.backgrounds.push(background);
+            div().size_full().bg(background)
+        }
+    }
+
+    #[gpui::test]
+    fn test_set_active_theme(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            crate::init(LoadThemes::JustBase, cx);
+        });
+
+        let (view, cx) = cx.add_window_view(|_, _| ThemedView {
+            backgrounds: Vec::new(),
+        });
+        let dark_theme = cx.update(|_, cx| cx.theme().clone());
+        assert_eq!(dark_theme.appearance, Appearance::Dark);
+        view.read_with(cx, |view, _| {
+            assert_eq!(view.backgrounds, [dark_theme.colors().background]);
+        });
+
+        let light_background = hsla(0., 0., 1., 1.);
+        let mut light_theme = (*dark_theme).clone();
+        light_theme.name = "Test Light".into();
+        light_theme.appearance = Appearance::Light;
+        light_theme.styles.colors.background = light_background;
+        cx.update(|_, cx| set_active_theme(Arc::new(light_theme), cx));
+
+        cx.update(|_, cx| {
+            assert_eq!(cx.theme().name, "Test Light");
+            assert_eq!(cx.theme().appearance, Appearance::Light);
+        });
+        view.read_with(cx, |view, _| {
+            assert_eq!(
+                view.backgrounds,
+                [dark_theme.colors().background, light_background]
+            );
+        });
+
+        // Switching back repaints the window with the original colors.
+        cx.update(|_, cx| set_active_theme(dark_theme.clone(), cx));
+        view.read_with(cx, |view, _| {
+            assert_eq!(
+                view.backgrounds.last(),
+                Some(&dark_theme.colors().background)
+            );
+        });
+    }
+}
@@ -7,7 +7,7 @@ use gpui::{
     Window, actions,
 };
 use picker::{Picker, PickerDelegate};
-use settings::{SettingsStore, update_settings_file};
+use settings::update_settings_file;
 use std::sync::Arc;
 use theme::{Appearance, Theme, ThemeMeta, ThemeRegistry, ThemeSettings};
 use ui::{ListItem, ListItemSpacing, prelude::*, v_flex};
@@ -163,7 +163,7 @@ impl ThemeSelectorDelegate {
             let registry = ThemeRegistry::global(cx);
             match registry.get(&mat.string) {
                 Ok(theme) => {
-                    Self::set_theme(theme, cx);
+                    theme::set_active_theme(theme, cx);
                 }
                 Err(error) => {
                     log::error!("error loading theme {}: {}", mat.string, error)
@@ -179,16 +179,6 @@ impl ThemeSelectorDelegate {
             .position(|mat| mat.string == theme_name)
             .unwrap_or(self.selected_index);
     }
-
-    fn set_theme(theme: Arc<Theme>, cx: &mut App) {
-        SettingsStore::update_global(cx, |store, cx| {
-            let mut theme_settings = store.get::<ThemeSettings>(None).clone();
-            theme_settings.active_theme = theme;
-            theme_settings.apply_theme_overrides();
-            store.override_global(theme_settings);
-            cx.refresh_windows();
-        });
-    }
 }
 
 impl PickerDelegate for ThemeSelectorDelegate {
@@ -229,7 +219,7 @@ impl PickerDelegate for ThemeSelectorDelegate {
 
     fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<ThemeSelectorDelegate>>) {
         if !self.selection_completed {
-            Self::set_theme(self.original_theme.clone(), cx);
+            theme::set_active_theme(self.original_theme.clone(), cx);
             self.selection_completed = true;
         }
 
@@ -32,6 +32,7 @@ mod stack;
 mod tab;
 mod tab_bar;
 mod table;
+mod text_input;
 mod toggle;
 mod tooltip;
 
@@ -72,6 +73,7 @@ pub use stack::*;
 pub use tab::*;
 pub use tab_bar::*;
 pub use table::*;
+pub use text_input::*;
 pub use toggle::*;
 pub use tooltip::*;
 
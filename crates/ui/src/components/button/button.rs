@@ -397,6 +397,13 @@ impl ButtonCommon for Button {
         self.base = self.base.layer(elevation);
         self
     }
+
+    /// Sets the name that describes the button to assistive technologies,
+    /// in place of its visible label.
+    fn aria_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.base = self.base.aria_label(label);
+        self
+    }
 }
 
 impl RenderOnce for Button {
@@ -1,6 +1,8 @@
+use std::rc::Rc;
+
 use documented::Documented;
 use gpui::{
-    AnyElement, AnyView, ClickEvent, CursorStyle, DefiniteLength, Hsla, MouseButton,
+    AnyElement, AnyView, ClickEvent, CursorStyle, DefiniteLength, FocusHandle, Hsla, MouseButton,
     MouseDownEvent, MouseUpEvent, Rems, relative, transparent_black,
 };
 use smallvec::SmallVec;
@@ -38,6 +40,10 @@ pub trait ButtonCommon: Clickable + Disableable {
     fn tooltip(self, tooltip: impl Fn(&mut Window, &mut App) -> AnyView + 'static) -> Self;
 
     fn layer(self, elevation: ElevationIndex) -> Self;
+
+    /// The name that describes the button to assistive technologies, when
+    /// its visible content, such as an icon or a short label, isn't enough.
+    fn aria_label(self, label: impl Into<SharedString>) -> Self;
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
@@ -363,6 +369,7 @@ pub struct ButtonLike {
     cursor_style: CursorStyle,
     on_click: Option<Box<dyn Fn(&ClickEvent, &mut Window, &mut App) + 'static>>,
     on_right_click: Option<Box<dyn Fn(&ClickEvent, &mut Window, &mut App) + 'static>>,
+    focus_handle: Option<FocusHandle>,
     children: SmallVec<[AnyElement; 2]>,
 }
 
@@ -384,6 +391,7 @@ impl ButtonLike {
             cursor_style: CursorStyle::PointingHand,
             on_click: None,
             on_right_click: None,
+            focus_handle: None,
             layer: None,
         }
     }
@@ -411,6 +419,8 @@ impl ButtonLike {
         self
     }
 
+    /// Sets a handler that's called when the right mouse button is released over the button,
+    /// e.g. to open a context menu. Right clicks never trigger the `on_click` handler.
     pub fn on_right_click(
         mut self,
         handler: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
@@ -418,6 +428,13 @@ impl ButtonLike {
         self.on_right_click = Some(Box::new(handler));
         self
     }
+
+    /// Makes the button focusable with the given handle. While it's focused, pressing enter or
+    /// space clicks it.
+    pub fn track_focus(mut self, focus_handle: &FocusHandle) -> Self {
+        self.focus_handle = Some(focus_handle.clone());
+        self
+    }
 }
 
 impl Disableable for ButtonLike {
@@ -489,6 +506,11 @@ impl ButtonCommon for ButtonLike {
         self.layer = Some(elevation);
         self
     }
+
+    fn aria_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.base = self.base.aria_label(label);
+        self
+    }
 }
 
 impl VisibleOnHover for ButtonLike {
@@ -505,13 +527,16 @@ impl ParentElement for ButtonLike {
 }
 
 impl RenderOnce for ButtonLike {
-    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
         let style = self
             .selected_style
             .filter(|_| self.selected)
             .unwrap_or(self.style);
+        let on_click: Option<Rc<dyn Fn(&ClickEvent, &mut Window, &mut App)>> =
+            self.on_click.filter(|_| !self.disabled).map(Rc::from);
 
-        self.base
+        let button = self
+            .base
             .h_flex()
             .id(self.id.clone())
             .font_ui(cx)
@@ -534,10 +559,13 @@ impl RenderOnce for ButtonLike {
                 }
                 ButtonSize::None => this,
             })
-            .bg(style.enabled(self.layer, cx).background)
-            .when(self.disabled, |this| this.cursor_not_allowed())
+            .when(self.disabled, |this| {
+                this.cursor_not_allowed()
+                    .bg(style.disabled(self.layer, window, cx).background)
+            })
             .when(!self.disabled, |this| {
                 this.cursor_pointer()
+                    .bg(style.enabled(self.layer, cx).background)
                     .hover(|hover| hover.bg(style.hovered(self.layer, cx).background))
                     .active(|active| active.bg(style.active(cx).background))
             })
@@ -572,20 +600,36 @@ impl RenderOnce for ButtonLike {
                     )
                 },
             )
+            .when_some(on_click.clone(), |this, on_click| {
+                this.on_mouse_down(MouseButton::Left, |_, window, _| window.prevent_default())
+                    .on_click(move |event, window, cx| {
+                        cx.stop_propagation();
+                        (on_click)(event, window, cx)
+                    })
+            })
             .when_some(
-                self.on_click.filter(|_| !self.disabled),
+                on_click.filter(|_| self.focus_handle.is_some()),
                 |this, on_click| {
-                    this.on_mouse_down(MouseButton::Left, |_, window, _| window.prevent_default())
-                        .on_click(move |event, window, cx| {
+                    this.on_key_down(move |event, window, cx| {
+                        let keystroke = &event.keystroke;
+                        if matches!(keystroke.key.as_str(), "enter" | "space")
+                            && !keystroke.modifiers.modified()
+                        {
                             cx.stop_propagation();
-                            (on_click)(event, window, cx)
-                        })
+                            (on_click)(&ClickEvent::default(), window, cx)
+                        }
+                    })
                 },
             )
             .when_some(self.tooltip, |this, tooltip| {
                 this.tooltip(move |window, cx| tooltip(window, cx))
             })
-            .children(self.children)
+            .children(self.children);
+
+        match self.focus_handle {
+            Some(focus_handle) => button.track_focus(&focus_handle).into_any_element(),
+            None => button.into_any_element(),
+        }
     }
 }
 
@@ -684,3 +728,164 @@ impl Component for ButtonLike {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use gpui::{Modifiers, TestAppContext, VisualTestContext, point};
+    use std::cell::Cell;
+
+    struct TestView {
+        button: Box<dyn Fn() -> ButtonLike>,
+    }
+
+    impl Render for TestView {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .size_full()
+                .child((self.button)().width(px(100.).into()))
+        }
+    }
+
+    /// Opens a window with a 100px wide button in its top left corner.
+    fn render_button(
+        cx: &mut TestAppContext,
+        button: impl Fn() -> ButtonLike + 'static,
+    ) -> &mut VisualTestContext {
+        cx.update(init_test);
+        let (_, cx) = cx.add_window_view(|_, _| TestView {
+            button: Box::new(button),
+        });
+        cx
+    }
+
+    #[gpui::test]
+    fn test_click(cx: &mut TestAppContext) {
+        let clicked = Rc::new(Cell::new(None));
+        let cx = render_button(cx, {
+            let clicked = clicked.clone();
+            move || {
+                let clicked = clicked.clone();
+                let data = 1;
+                ButtonLike::new("button").on_click(move |_, _, _| clicked.set(Some(data)))
+            }
+        });
+
+        let inside = point(px(50.), px(10.));
+        let outside = point(px(150.), px(10.));
+        cx.simulate_click(inside, Modifiers::none());
+        assert_eq!(clicked.take(), Some(1));
+
+        // Dragging off of the button before releasing the mouse doesn't click it.
+        cx.simulate_mouse_down(inside, MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_move(outside, MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_up(outside, MouseButton::Left, Modifiers::none());
+        assert_eq!(clicked.take(), None);
+        cx.simulate_mouse_down(outside, MouseButton::Left, Modifiers::none());
+        cx.simulate_mouse_up(inside, MouseButton::Left, Modifiers::none());
+        assert_eq!(clicked.take(), None);
+    }
+
+    #[gpui::test]
+    fn test_aria_label(cx: &mut TestAppContext) {
+        let cx = render_button(cx, || {
+            ButtonLike::new("button")
+                .child(Label::new("OK"))
+                .aria_label("Confirm deletion")
+        });
+
+        // The accessible name is reported in place of the visible label.
+        let nodes = cx.update(|window, _| window.accessibility_nodes().to_vec());
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].label.as_ref(), "Confirm deletion");
+        assert_eq!(nodes[0].bounds.size.width, px(100.));
+    }
+
+    #[gpui::test]
+    fn test_keyboard_activation(cx: &mut TestAppContext) {
+        let clicks = Rc::new(Cell::new(0));
+        let focus_handle = cx.update(|cx| cx.focus_handle());
+        let cx = render_button(cx, {
+            let clicks = clicks.clone();
+            let focus_handle = focus_handle.clone();
+            move || {
+                let clicks = clicks.clone();
+                ButtonLike::new("button")
+                    .track_focus(&focus_handle)
+                    .on_click(move |_, _, _| clicks.set(clicks.get() + 1))
+            }
+        });
+
+        // Key presses only reach the button while it's focused.
+        cx.simulate_keystrokes("enter");
+        assert_eq!(clicks.get(), 0);
+
+        cx.update(|window, _| window.focus(&focus_handle));
+        cx.simulate_keystrokes("enter");
+        assert_eq!(clicks.get(), 1);
+        cx.simulate_keystrokes("space");
+        assert_eq!(clicks.get(), 2);
+        cx.simulate_keystrokes("a shift-enter");
+        assert_eq!(clicks.get(), 2);
+    }
+
+    #[gpui::test]
+    fn test_disabled(cx: &mut TestAppContext) {
+        let clicks = Rc::new(Cell::new(0));
+        let focus_handle = cx.update(|cx| cx.focus_handle());
+        let cx = render_button(cx, {
+            let clicks = clicks.clone();
+            let focus_handle = focus_handle.clone();
+            move || {
+                let clicks = clicks.clone();
+                ButtonLike::new("button")
+                    .track_focus(&focus_handle)
+                    .disabled(true)
+                    .on_click(move |_, _, _| clicks.set(clicks.get() + 1))
+            }
+        });
+
+        let inside = point(px(50.), px(10.));
+        cx.simulate_mouse_move(inside, None, Modifiers::none());
+        cx.simulate_click(inside, Modifiers::none());
+        cx.update(|window, _| window.focus(&focus_handle));
+        cx.simulate_keystrokes("enter");
+        assert_eq!(clicks.get(), 0);
+    }
+
+    #[gpui::test]
+    fn test_right_click(cx: &mut TestAppContext) {
+        let clicks = Rc::new(Cell::new(0));
+        let right_clicks = Rc::new(Cell::new(0));
+        let cx = render_button(cx, {
+            let clicks = clicks.clone();
+            let right_clicks = right_clicks.clone();
+            move || {
+                let clicks = clicks.clone();
+                let right_clicks = right_clicks.clone();
+                ButtonLike::new("button")
+                    .on_click(move |_, _, _| clicks.set(clicks.get() + 1))
+                    .on_right_click(move |event, _, _| {
+                        assert_eq!(event.up.button, MouseButton::Right);
+                        right_clicks.set(right_clicks.get() + 1)
+                    })
+            }
+        });
+
+        let inside = point(px(50.), px(10.));
+        let outside = point(px(150.), px(10.));
+        cx.simulate_mouse_down(inside, MouseButton::Right, Modifiers::none());
+        cx.simulate_mouse_up(inside, MouseButton::Right, Modifiers::none());
+        assert_eq!((clicks.get(), right_clicks.get()), (0, 1));
+
+        cx.simulate_click(inside, Modifiers::none());
+        assert_eq!((clicks.get(), right_clicks.get()), (1, 1));
+
+        // Releasing the right mouse button elsewhere doesn't count as a right click.
+        cx.simulate_mouse_down(inside, MouseButton::Right, Modifiers::none());
+        cx.simulate_mouse_move(outside, MouseButton::Right, Modifiers::none());
+        cx.simulate_mouse_up(outside, MouseButton::Right, Modifiers::none());
+        assert_eq!((clicks.get(), right_clicks.get()), (1, 1));
+    }
+}
@@ -0,0 +1,469 @@
+use std::{ops::Range, time::Duration};
+
+use gpui::{
+    Bounds, ElementInputHandler, EntityInputHandler, FocusHandle, Focusable, HighlightStyle,
+    KeyDownEvent, MouseButton, MouseDownEvent, Pixels, Point, StyledText, Subscription, Task,
+    TextLayout, UTF16Selection, UnderlineStyle, canvas, fill, point, size,
+};
+
+use crate::prelude::*;
+
+const CARET_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A single line of editable plain text, such as a message composer.
+///
+/// Text typed or composed with an input method is inserted at the caret, and the caret
+/// can be moved with the arrow keys or by clicking. Pressing enter calls the
+/// [`TextInput::on_submit`] handler with the current content.
+///
+/// For anything beyond short plain text, use an `Editor` instead.
+pub struct TextInput {
+    focus_handle: FocusHandle,
+    content: String,
+    /// Byte offset of the caret within `content`.
+    cursor: usize,
+    /// The text being composed by an input method, as a byte range within `content`.
+    marked_range: Option<Range<usize>>,
+    /// The layout of the text as last rendered, used to answer the platform's queries
+    /// about where characters are.
+    last_layout: Option<TextLayout>,
+    placeholder: SharedString,
+    on_submit: Option<Box<dyn Fn(&mut Self, &str, &mut Context<Self>) + 'static>>,
+    caret_visible: bool,
+    blink_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TextInput {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let focus_handle = cx.focus_handle();
+        let _subscriptions = vec![
+            cx.on_focus(&focus_handle, window, |this, _, cx| {
+                this.restart_blinking(cx)
+            }),
+            cx.on_blur(&focus_handle, window, |this, _, _| this.blink_task = None),
+        ];
+        Self {
+            focus_handle,
+            content: String::new(),
+            cursor: 0,
+            marked_range: None,
+            last_layout: None,
+            placeholder: SharedString::default(),
+            on_submit: None,
+            caret_visible: true,
+            blink_task: None,
+            _subscriptions,
+        }
+    }
+
+    /// Sets the text shown while the input is empty.
+    pub fn placeholder(mut self, placeholder: impl Into<SharedString>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// Sets the handler called with the content of the input when enter is pressed.
+    pub fn on_submit(
+        mut self,
+        handler: impl Fn(&mut Self, &str, &mut Context<Self>) + 'static,
+    ) -> Self {
+        self.on_submit = Some(Box::new(handler));
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.content
+    }
+
+    /// Replaces the content of the input, moving the caret to its end.
+    pub fn set_text(&mut self, text: impl Into<String>, cx: &mut Context<Self>) {
+        self.content = text.into();
+        self.cursor = self.content.len();
+        self.marked_range = None;
+        cx.notify();
+    }
+
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.set_text(String::new(), cx);
+    }
+
+    fn backspace(&mut self) {
+        if let Some(ch) = self.content[..self.cursor].chars().next_back() {
+            self.cursor -= ch.len_utf8();
+            self.content.remove(self.cursor);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.cursor < self.content.len() {
+            self.content.remove(self.cursor);
+        }
+    }
+
+    fn move_left(&mut self) {
+        if let Some(ch) = self.content[..self.cursor].chars().next_back() {
+            self.cursor -= ch.len_utf8();
+        }
+    }
+
+    fn move_right(&mut self) {
+        if let Some(ch) = self.content[self.cursor..].chars().next() {
+            self.cursor += ch.len_utf8();
+        }
+    }
+
+    fn submit(&mut self, cx: &mut Context<Self>) {
+        if let Some(on_submit) = self.on_submit.take() {
+            let content = self.content.clone();
+            on_submit(self, &content, cx);
+            self.on_submit = Some(on_submit);
+        }
+    }
+
+    /// Handles the keys that submit, delete or move the caret. Typed text reaches the input
+    /// through its [`EntityInputHandler`] implementation instead, so that input methods work.
+    fn handle_key_down(&mut self, event: &KeyDownEvent, cx: &mut Context<Self>) {
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.platform
+            || keystroke.modifiers.control
+            || self.marked_range.is_some()
+        {
+            cx.propagate();
+            return;
+        }
+
+        match keystroke.key.as_str() {
+            "enter" => self.submit(cx),
+            "backspace" => self.backspace(),
+            "delete" => self.delete(),
+            "left" => self.move_left(),
+            "right" => self.move_right(),
+            "home" => self.cursor = 0,
+            "end" => self.cursor = self.content.len(),
+            _ => {
+                cx.propagate();
+                return;
+            }
+        }
+        self.restart_blinking(cx);
+    }
+
+    fn offset_from_utf16(&self, offset: usize) -> usize {
+        utf8_offset(&self.content, offset)
+    }
+
+    fn offset_to_utf16(&self, offset: usize) -> usize {
+        self.content[..offset].chars().map(char::len_utf16).sum()
+    }
+
+    fn range_to_utf16(&self, range: &Range<usize>) -> Range<usize> {
+        self.offset_to_utf16(range.start)..self.offset_to_utf16(range.end)
+    }
+
+    fn range_from_utf16(&self, range_utf16: &Range<usize>) -> Range<usize> {
+        self.offset_from_utf16(range_utf16.start)..self.offset_from_utf16(range_utf16.end)
+    }
+
+    /// The range replaced by input that doesn't name one: the marked text if there is
+    /// any, or the caret otherwise.
+    fn replacement_range(&self, range_utf16: Option<Range<usize>>) -> Range<usize> {
+        range_utf16
+            .map(|range_utf16| self.range_from_utf16(&range_utf16))
+            .or(self.marked_range.clone())
+            .unwrap_or(self.cursor..self.cursor)
+    }
+
+    /// Shows the caret and restarts its blink cycle, so it stays visible while typing.
+    fn restart_blinking(&mut self, cx: &mut Context<Self>) {
+        self.caret_visible = true;
+        cx.notify();
+        self.blink_task = Some(cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(CARET_BLINK_INTERVAL).await;
+                let result = this.update(cx, |this, cx| {
+                    this.caret_visible = !this.caret_visible;
+                    cx.notify();
+                });
+                if result.is_err() {
+                    break;
+                }
+            }
+        }));
+    }
+}
+
+/// Converts an offset in UTF-16 code units to a byte offset within `text`.
+fn utf8_offset(text: &str, utf16_offset: usize) -> usize {
+    let mut utf16_count = 0;
+    text.char_indices()
+        .find(|(_, ch)| {
+            utf16_count += ch.len_utf16();
+            utf16_count > utf16_offset
+        })
+        .map_or(text.len(), |(ix, _)| ix)
+}
+
+impl Focusable for TextInput {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EntityInputHandler for TextInput {
+    fn text_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        actual_range: &mut Option<Range<usize>>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<String> {
+        let range = self.range_from_utf16(&range_utf16);
+        actual_range.replace(self.range_to_utf16(&range));
+        Some(self.content[range].to_string())
+    }
+
+    fn selected_text_range(
+        &mut self,
+        _ignore_disabled_input: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<UTF16Selection> {
+        Some(UTF16Selection {
+            range: self.range_to_utf16(&(self.cursor..self.cursor)),
+            reversed: false,
+        })
+    }
+
+    fn marked_text_range(
+        &self,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Range<usize>> {
+        self.marked_range
+            .as_ref()
+            .map(|range| self.range_to_utf16(range))
+    }
+
+    fn unmark_text(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
+        self.marked_range = None;
+    }
+
+    fn replace_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        text: &str,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // The input is a single line, so line breaks and tabs aren't inserted.
+        if text.chars().any(char::is_control) {
+            return;
+        }
+        let range = self.replacement_range(range_utf16);
+        self.content.replace_range(range.clone(), text);
+        self.cursor = range.start + text.len();
+        self.marked_range = None;
+        self.restart_blinking(cx);
+    }
+
+    fn replace_and_mark_text_in_range(
+        &mut self,
+        range_utf16: Option<Range<usize>>,
+        new_text: &str,
+        new_selected_range_utf16: Option<Range<usize>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let range = self.replacement_range(range_utf16);
+        self.content.replace_range(range.clone(), new_text);
+        self.marked_range =
+            (!new_text.is_empty()).then(|| range.start..range.start + new_text.len());
+        // The selected range is relative to the new text. Without a selection, the caret
+        // goes to its end.
+        self.cursor = range.start
+            + new_selected_range_utf16.map_or(new_text.len(), |selected_range_utf16| {
+                utf8_offset(new_text, selected_range_utf16.end)
+            });
+        self.restart_blinking(cx);
+    }
+
+    fn bounds_for_range(
+        &mut self,
+        range_utf16: Range<usize>,
+        _element_bounds: Bounds<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<Bounds<Pixels>> {
+        let layout = self.last_layout.as_ref()?;
+        let range = self.range_from_utf16(&range_utf16);
+        let start = layout.position_for_index(range.start)?;
+        let end = layout.position_for_index(range.end)?;
+        Some(Bounds::from_corners(
+            start,
+            point(end.x, end.y + layout.line_height()),
+        ))
+    }
+
+    fn character_index_for_point(
+        &mut self,
+        point: Point<Pixels>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        if self.content.is_empty() {
+            return Some(0);
+        }
+        let index = self.last_layout.as_ref()?.index_for_position(point).ok()?;
+        Some(self.offset_to_utf16(index))
+    }
+}
+
+impl Render for TextInput {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let focused = self.focus_handle.is_focused(window);
+        let is_empty = self.content.is_empty();
+        let text = StyledText::new(if is_empty {
+            self.placeholder.clone()
+        } else {
+            SharedString::from(self.content.clone())
+        })
+        .with_highlights(self.marked_range.clone().map(|marked_range| {
+            let underline = UnderlineStyle {
+                thickness: px(1.),
+                ..Default::default()
+            };
+            (
+                marked_range,
+                HighlightStyle {
+                    underline: Some(underline),
+                    ..Default::default()
+                },
+            )
+        }));
+        let text_layout = text.layout().clone();
+        self.last_layout = Some(text_layout.clone());
+        let cursor = self.cursor;
+        let show_caret = focused && self.caret_visible;
+        let caret_color = colors.text;
+        let focus_handle = self.focus_handle.clone();
+        let entity = cx.entity();
+
+        div()
+            .id("text-input")
+            .track_focus(&self.focus_handle)
+            .relative()
+            .px_1()
+            .border_1()
+            .rounded_sm()
+            .border_color(if focused {
+                colors.border_focused
+            } else {
+                colors.border
+            })
+            .text_color(if is_empty {
+                colors.text_placeholder
+            } else {
+                colors.text
+            })
+            .on_key_down(cx.listener(|this, event, _, cx| this.handle_key_down(event, cx)))
+            .on_mouse_down(MouseButton::Left, {
+                let text_layout = text_layout.clone();
+                cx.listener(move |this, event: &MouseDownEvent, window, cx| {
+                    window.focus(&this.focus_handle);
+                    if !this.content.is_empty() && this.marked_range.is_none() {
+                        this.cursor = text_layout
+                            .closest_index_for_position(event.position)
+                            .unwrap_or_else(|ix| ix);
+                    }
+                    this.restart_blinking(cx);
+                })
+            })
+            .child(text)
+            .child(
+                canvas(
+                    |_, _, _| {},
+                    move |bounds, _, window, cx| {
+                        window.handle_input(
+                            &focus_handle,
+                            ElementInputHandler::new(bounds, entity),
+                            cx,
+                        );
+                        if !show_caret {
+                            return;
+                        }
+                        if let Some(position) = text_layout.position_for_index(cursor) {
+                            let caret =
+                                Bounds::new(position, size(px(1.), text_layout.line_height()));
+                            window.paint_quad(fill(caret, caret_color));
+                        }
+                    },
+                )
+                .absolute()
+                .size_full(),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use gpui::{Entity, TestAppContext, VisualTestContext};
+    use std::{cell::RefCell, rc::Rc};
+
+    fn build_input(
+        cx: &mut TestAppContext,
+        on_submit: impl Fn(&mut TextInput, &str, &mut Context<TextInput>) + 'static,
+    ) -> (Entity<TextInput>, &mut VisualTestContext) {
+        cx.update(init_test);
+        let (input, cx) =
+            cx.add_window_view(|window, cx| TextInput::new(window, cx).on_submit(on_submit));
+        cx.update(|window, cx| window.focus(&input.focus_handle(cx)));
+        (input, cx)
+    }
+
+    #[gpui::test]
+    fn test_typing_and_submit(cx: &mut TestAppContext) {
+        let submitted = Rc::new(RefCell::new(Vec::new()));
+        let (input, cx) = build_input(cx, {
+            let submitted = submitted.clone();
+            move |_, text, _| submitted.borrow_mut().push(text.to_string())
+        });
+
+        cx.simulate_keystrokes("h i backspace enter");
+        assert_eq!(*submitted.borrow(), ["h"]);
+        input.read_with(cx, |input, _| assert_eq!(input.text(), "h"));
+
+        // Text is inserted at the caret.
+        cx.simulate_keystrokes("left a end b");
+        input.read_with(cx, |input, _| assert_eq!(input.text(), "ahb"));
+    }
+
+    #[gpui::test]
+    fn test_input_method_composition(cx: &mut TestAppContext) {
+        let submitted = Rc::new(RefCell::new(Vec::new()));
+        let (input, cx) = build_input(cx, {
+            let submitted = submitted.clone();
+            move |_, text, _| submitted.borrow_mut().push(text.to_string())
+        });
+        cx.simulate_keystrokes("a");
+
+        // Keys pressed while composing go to the input method, not the input.
+        input.update_in(cx, |input, window, cx| {
+            input.replace_and_mark_text_in_range(None, "ㅎ", None, window, cx);
+            input.replace_and_mark_text_in_range(None, "하", None, window, cx);
+            assert_eq!(input.marked_text_range(window, cx), Some(1..2));
+        });
+        cx.simulate_keystrokes("enter");
+        assert!(submitted.borrow().is_empty());
+
+        input.update_in(cx, |input, window, cx| {
+            input.replace_text_in_range(None, "한", window, cx);
+            assert_eq!(input.marked_text_range(window, cx), None);
+        });
+        cx.simulate_keystrokes("b enter");
+        assert_eq!(*submitted.borrow(), ["a한b"]);
+    }
+}
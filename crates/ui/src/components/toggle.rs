@@ -760,3 +760,52 @@ impl Component for CheckboxWithLabel {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use gpui::{Modifiers, TestAppContext, point};
+
+    struct CheckboxView {
+        state: ToggleState,
+        toggles: Vec<ToggleState>,
+    }
+
+    impl Render for CheckboxView {
+        fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+            div()
+                .size_full()
+                .child(Checkbox::new("checkbox", self.state).on_click(cx.listener(
+                    |this, state: &ToggleState, _, cx| {
+                        this.state = *state;
+                        this.toggles.push(*state);
+                        cx.notify();
+                    },
+                )))
+        }
+    }
+
+    #[gpui::test]
+    fn test_checkbox_toggles(cx: &mut TestAppContext) {
+        cx.update(init_test);
+        let (view, cx) = cx.add_window_view(|_, _| CheckboxView {
+            state: ToggleState::Unselected,
+            toggles: Vec::new(),
+        });
+
+        let center = point(
+            Checkbox::container_size() / 2.,
+            Checkbox::container_size() / 2.,
+        );
+        cx.simulate_click(center, Modifiers::none());
+        cx.simulate_click(center, Modifiers::none());
+
+        view.read_with(cx, |view, _| {
+            assert_eq!(
+                view.toggles,
+                [ToggleState::Selected, ToggleState::Unselected]
+            );
+        });
+    }
+}
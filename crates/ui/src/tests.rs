@@ -1 +1,9 @@
 mod path_str;
+
+/// Installs the test settings and base theme that rendering components requires.
+#[cfg(test)]
+pub(crate) fn init_test(cx: &mut gpui::App) {
+    let settings = settings::SettingsStore::test(cx);
+    cx.set_global(settings);
+    theme::init(theme::LoadThemes::JustBase, cx);
+}
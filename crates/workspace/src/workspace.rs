@@ -6260,6 +6260,10 @@ pub fn join_channel(
                             ErrorCode::Forbidden => {
                                 "This channel is private, and you do not have access. Please ask someone to add you and try again.".into()
                             }
+                            ErrorCode::ChannelFull => match err.error_tag("max_participants") {
+                                Some(max_participants) => format!("This channel is full, it can't have more than {max_participants} participants at once. Please try again later.").into(),
+                                None => "This channel is full. Please try again later.".into(),
+                            },
                             ErrorCode::Disconnected => "Please check your internet connection and try again.".into(),
                             _ => format!("{}\n\nPlease try again.", err).into(),
                         };